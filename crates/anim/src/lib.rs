@@ -7,11 +7,11 @@ pub mod rc;
 
 use mech3ax_types::Hex;
 
-const SIGNATURE: Hex<u32> = Hex(0x08170616);
+pub const SIGNATURE: Hex<u32> = Hex(0x08170616);
 
-const VERSION_RC: u32 = 28;
-const VERSION_MW: u32 = 39;
-const VERSION_PM: u32 = 50;
+pub const VERSION_RC: u32 = 28;
+pub const VERSION_MW: u32 = 39;
+pub const VERSION_PM: u32 = 50;
 
 #[derive(Debug, Clone, Copy)]
 pub enum SaveItem<'a> {
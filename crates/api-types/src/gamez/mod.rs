@@ -3,7 +3,7 @@ pub mod model;
 pub mod nodes;
 
 use crate::{Color, Count, IndexO, api, sum};
-use materials::Material;
+use materials::{Material, Soil};
 use mech3ax_timestamp::DateTime;
 use model::Model;
 use nodes::Node;
@@ -51,6 +51,8 @@ api! {
         model_array_size: Count,
         node_array_size: Count,
         node_last_free: i32,
+        /// CS only: the texture image pointers, which can't be derived.
+        image_ptrs: Option<Vec<u32>> = { None },
     }
 }
 
@@ -63,3 +65,25 @@ api! {
         metadata: GameZMetadata,
     }
 }
+
+api! {
+    /// How much of a soil map a soil type covers.
+    struct SoilMapCoverage {
+        soil: Soil,
+        /// The color on the soil map, as `#rrggbb`.
+        color: String,
+        pixels: u64,
+    }
+}
+
+api! {
+    /// The legend of a soil map rendered by `unzbd soil-map`.
+    struct SoilMapLegend {
+        width: u32,
+        height: u32,
+        /// The color of areas without terrain, as `#rrggbb`.
+        background: String,
+        /// Every soil type, in order, even if it doesn't cover any pixels.
+        soils: Vec<SoilMapCoverage>,
+    }
+}
@@ -15,7 +15,7 @@ macro_rules! api {
             pub $field_name: $field_ty,
         )*}
 
-        $crate::api!(@md $name { $($field_name: $field_ty $( { $($default)* })?,)* });
+        $crate::api!(@md $name { $($field_name: $field_ty $( { $($default)* })? $( [ $($serde)* ] )?,)* });
     };
     (@sem ) => {
         ::mech3ax_metadata_types::TypeSemantic::Ref
@@ -50,6 +50,15 @@ macro_rules! api {
     (@default 0u32) => {
         ::mech3ax_metadata_types::DefaultHandling::PointerIsZero
     };
+    (@serde_default ) => {
+        false
+    };
+    (@serde_default default $($rest:tt)*) => {
+        true
+    };
+    (@serde_default $first:tt $($rest:tt)*) => {
+        $crate::api!(@serde_default $($rest)*)
+    };
     (@serde None) => {
         #[serde(skip_serializing_if = "Option::is_none", default)]
     };
@@ -91,7 +100,7 @@ macro_rules! api {
         $crate::api!(@md $name $(: $semantic)? { $($field_name: $field_ty,)* });
     };
     (@md $name:ident $(: $semantic:tt)? {$(
-        $field_name:ident: $field_ty:ty $( { $($default:tt)* })?,
+        $field_name:ident: $field_ty:ty $( { $($default:tt)* })? $( [ $($serde:tt)* ] )?,
     )*}) => {
         impl ::mech3ax_metadata_types::DerivedMetadata for $name {
             const TYPE_INFO: &'static ::mech3ax_metadata_types::TypeInfo =
@@ -103,6 +112,7 @@ macro_rules! api {
                             name: stringify!($field_name),
                             type_info: <$field_ty as ::mech3ax_metadata_types::DerivedMetadata>::TYPE_INFO,
                             default: $crate::api!(@default $($($default)*)? ),
+                            serde_default: $crate::api!(@serde_default $($($serde)*)? ),
                         },
                     )*],
                     module_path: ::std::module_path!(),
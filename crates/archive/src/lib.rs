@@ -1,11 +1,15 @@
 #![warn(clippy::all, clippy::cargo)]
 #![allow(clippy::identity_op)]
+mod patch;
 mod read;
+mod reader;
 mod write;
 
 use bytemuck::{AnyBitPattern, NoUninit};
 use mech3ax_types::{Ascii, Hex, impl_as_bytes};
-pub use read::read_archive;
+pub use patch::{ArchivePatch, patch_archive};
+pub use read::{HEADER_ONE_SIZE, HEADER_TWO_SIZE, TABLE_ENTRY_SIZE, read_archive};
+pub use reader::{ArchiveReader, ArchiveReaderEntry};
 use std::fmt;
 pub use write::write_archive;
 
@@ -23,8 +27,8 @@ pub enum Version {
     Two(Mode),
 }
 
-const VERSION_ONE: u32 = 1;
-const VERSION_TWO: u32 = 2;
+pub const VERSION_ONE: u32 = 1;
+pub const VERSION_TWO: u32 = 2;
 
 #[derive(Debug, Clone, Copy, NoUninit, AnyBitPattern)]
 #[repr(C)]
@@ -0,0 +1,120 @@
+//! Replace, add, and delete archive entries without extracting the archive.
+use crate::{ArchiveReader, Version, write_archive};
+use log::debug;
+use mech3ax_api_types::archive::ArchiveEntry;
+use mech3ax_common::io_ext::CountingWriter;
+use mech3ax_common::{Error, Result, assert_with_msg};
+use std::io::{Read, Seek, Write};
+
+/// The maximum length of an entry name in bytes. The entry name field is 64
+/// bytes, and must be zero-terminated.
+const ENTRY_NAME_MAX_LEN: usize = 64 - 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArchivePatch {
+    /// Replace the data of an existing entry, keeping its flags and info.
+    Replace { name: String, data: Vec<u8> },
+    /// Append a new entry.
+    Add { entry: ArchiveEntry, data: Vec<u8> },
+    /// Remove an existing entry.
+    Delete { name: String },
+}
+
+enum Source {
+    Original(usize),
+    Patched(Vec<u8>),
+}
+
+fn filename(entry: &ArchiveEntry) -> &str {
+    entry.rename.as_deref().unwrap_or(&entry.name)
+}
+
+fn find(entries: &[(ArchiveEntry, Source)], name: &str) -> Result<usize> {
+    entries
+        .iter()
+        .position(|(entry, _)| filename(entry) == name)
+        .ok_or_else(|| assert_with_msg!("Entry `{}` not found in archive", name))
+}
+
+fn validate_entry_name(name: &str) -> Result<()> {
+    if !name.is_ascii() {
+        return Err(assert_with_msg!("Entry name `{}` must be ASCII", name));
+    }
+    if name.len() > ENTRY_NAME_MAX_LEN {
+        return Err(assert_with_msg!(
+            "Entry name `{}` is too long: must be <= {} bytes, but was {}",
+            name,
+            ENTRY_NAME_MAX_LEN,
+            name.len(),
+        ));
+    }
+    Ok(())
+}
+
+/// Apply patches to an archive, and write the patched archive.
+///
+/// Patches are applied in order. Entries are identified by their unique name
+/// (i.e. the rename, if present). Untouched entries are copied as-is,
+/// including their flags and info. Offsets, the table, and the checksum (for
+/// `PM` reader archives) are recomputed. Returns the new manifest.
+pub fn patch_archive<R, W>(
+    reader: &mut ArchiveReader<R>,
+    write: &mut CountingWriter<W>,
+    patches: Vec<ArchivePatch>,
+    version: Version,
+) -> Result<Vec<ArchiveEntry>>
+where
+    R: Read + Seek,
+    W: Write,
+{
+    let mut entries: Vec<(ArchiveEntry, Source)> = reader
+        .manifest()
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| (entry, Source::Original(index)))
+        .collect();
+
+    for patch in patches {
+        match patch {
+            ArchivePatch::Replace { name, data } => {
+                debug!("Replacing entry `{}`", name);
+                let index = find(&entries, &name)?;
+                entries[index].1 = Source::Patched(data);
+            }
+            ArchivePatch::Add { entry, data } => {
+                let name = filename(&entry);
+                debug!("Adding entry `{}`", name);
+                validate_entry_name(&entry.name)?;
+                if find(&entries, name).is_ok() {
+                    return Err(assert_with_msg!(
+                        "Entry `{}` already exists in archive",
+                        name
+                    ));
+                }
+                entries.push((entry, Source::Patched(data)));
+            }
+            ArchivePatch::Delete { name } => {
+                debug!("Deleting entry `{}`", name);
+                let index = find(&entries, &name)?;
+                entries.remove(index);
+            }
+        }
+    }
+
+    let (manifest, sources): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+    let mut sources = sources.into_iter();
+    write_archive::<_, _, Error>(
+        write,
+        &manifest,
+        |name, _offset| match sources.next() {
+            Some(Source::Original(index)) => reader.read_entry(index),
+            Some(Source::Patched(data)) => Ok(data),
+            None => Err(assert_with_msg!("Entry `{}` has no data", name)),
+        },
+        version,
+    )?;
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,106 @@
+use super::*;
+use crate::{Mode, read_archive};
+use mech3ax_api_types::archive::{ArchiveEntryInfo, ArchiveEntryInfoInvalid};
+use mech3ax_common::io_ext::CountingReader;
+use std::io::Cursor;
+
+fn entry(name: &str, filetime: u64) -> ArchiveEntry {
+    // garbage after the zero terminator, so this is read back as invalid
+    let mut comment = vec![0; 64];
+    comment[1] = 0xAA;
+    ArchiveEntry {
+        name: name.to_string(),
+        rename: None,
+        flags: 0,
+        info: ArchiveEntryInfo::Invalid(ArchiveEntryInfoInvalid { comment, filetime }),
+    }
+}
+
+fn archive(version: Version) -> Vec<u8> {
+    let entries = vec![entry("a.zrd", 1), entry("b.zrd", 2), entry("c.zrd", 3)];
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    write_archive::<_, _, Error>(
+        &mut write,
+        &entries,
+        |name, _| Ok(name.as_bytes().to_vec()),
+        version,
+    )
+    .unwrap();
+    write.into_inner()
+}
+
+fn patch(version: Version, patches: Vec<ArchivePatch>) -> Result<Vec<u8>> {
+    let read = CountingReader::new(Cursor::new(archive(version)));
+    let mut reader = ArchiveReader::new(read, version)?;
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    patch_archive(&mut reader, &mut write, patches, version)?;
+    Ok(write.into_inner())
+}
+
+fn read(data: Vec<u8>, version: Version) -> Vec<(ArchiveEntry, Vec<u8>)> {
+    let mut files = Vec::new();
+    let mut read = CountingReader::new(Cursor::new(data));
+    let manifest = read_archive::<_, _, Error>(
+        &mut read,
+        |_, data, _| {
+            files.push(data);
+            Ok(())
+        },
+        version,
+    )
+    .unwrap();
+    manifest.into_iter().zip(files).collect()
+}
+
+#[test]
+fn patch_replace_add_delete() {
+    let version = Version::Two(Mode::Reader);
+    let patches = vec![
+        ArchivePatch::Delete {
+            name: "a.zrd".to_string(),
+        },
+        ArchivePatch::Replace {
+            name: "c.zrd".to_string(),
+            data: b"patched".to_vec(),
+        },
+        ArchivePatch::Add {
+            entry: entry("d.zrd", 4),
+            data: b"added".to_vec(),
+        },
+    ];
+    let data = patch(version, patches).unwrap();
+    // reading with `Mode::Reader` validates the recomputed checksum
+    let actual = read(data, version);
+    let expected = vec![
+        (entry("b.zrd", 2), b"b.zrd".to_vec()),
+        (entry("c.zrd", 3), b"patched".to_vec()),
+        (entry("d.zrd", 4), b"added".to_vec()),
+    ];
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn patch_empty_is_identity() {
+    let version = Version::One;
+    let data = patch(version, vec![]).unwrap();
+    assert_eq!(data, archive(version));
+}
+
+#[test]
+fn patch_invalid() {
+    let version = Version::One;
+    let missing = ArchivePatch::Delete {
+        name: "z.zrd".to_string(),
+    };
+    assert!(patch(version, vec![missing]).is_err());
+    let duplicate = ArchivePatch::Add {
+        entry: entry("a.zrd", 0),
+        data: vec![],
+    };
+    assert!(patch(version, vec![duplicate]).is_err());
+    let long = ArchivePatch::Add {
+        entry: entry(&"x".repeat(64), 0),
+        data: vec![],
+    };
+    assert!(patch(version, vec![long]).is_err());
+}
@@ -11,17 +11,17 @@ use mech3ax_timestamp::nt::from_filetime;
 use mech3ax_types::{AsBytes, u32_to_i64, u32_to_usize};
 use std::io::{Read, Seek, SeekFrom};
 
-const HEADER_ONE_SIZE: i64 = u32_to_i64(HeaderOneC::SIZE);
-const HEADER_TWO_SIZE: i64 = u32_to_i64(HeaderTwoC::SIZE);
-const TABLE_ENTRY_SIZE: i64 = u32_to_i64(TableEntryC::SIZE);
+pub const HEADER_ONE_SIZE: i64 = u32_to_i64(HeaderOneC::SIZE);
+pub const HEADER_TWO_SIZE: i64 = u32_to_i64(HeaderTwoC::SIZE);
+pub const TABLE_ENTRY_SIZE: i64 = u32_to_i64(TableEntryC::SIZE);
 
 #[derive(Debug)]
-struct TableEntry {
-    name: String,
-    start: u32,
-    len: u32,
-    flags: u32,
-    info: ArchiveEntryInfo,
+pub(crate) struct TableEntry {
+    pub(crate) name: String,
+    pub(crate) start: u32,
+    pub(crate) len: u32,
+    pub(crate) flags: u32,
+    pub(crate) info: ArchiveEntryInfo,
 }
 
 pub fn read_archive<R, F, E>(
@@ -89,7 +89,7 @@ where
     Ok(entries)
 }
 
-fn read_table(
+pub(crate) fn read_table(
     read: &mut CountingReader<impl Read + Seek>,
     version: Version,
 ) -> Result<(Vec<TableEntry>, u32)> {
@@ -139,7 +139,15 @@ fn read_table(
 
             let entry_start = entry.start;
             let entry_len = entry.length;
-            let entry_end = entry_start + entry_len;
+            let entry_end = entry_start.checked_add(entry_len).ok_or_else(|| {
+                assert_with_msg!(
+                    "entry {} end overflows (start: {}, length: {}) (at {})",
+                    index,
+                    entry_start,
+                    entry_len,
+                    read.prev + 0
+                )
+            })?;
 
             assert_that!("entry start", entry_start < entry_end, read.prev + 0)?;
             assert_that!("entry end", entry_end <= table_start, read.prev + 4)?;
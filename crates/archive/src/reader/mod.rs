@@ -0,0 +1,150 @@
+//! Random access to archive entries, without reading the whole archive.
+use super::{Mode, Version};
+use crate::read::{TableEntry, read_table};
+use log::{debug, trace};
+use mech3ax_api_types::archive::{ArchiveEntry, ArchiveEntryInfo};
+use mech3ax_common::io_ext::CountingReader;
+use mech3ax_common::{Rename, Result, assert_that, assert_with_msg};
+use mech3ax_crc32::{CRC32_INIT, crc32_update};
+use mech3ax_types::u32_to_usize;
+use std::io::{Read, Seek, SeekFrom};
+
+/// An archive table entry, including its location in the archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveReaderEntry {
+    pub name: String,
+    /// The unique name used for extraction, if the name is a duplicate.
+    pub rename: Option<String>,
+    pub start: u32,
+    pub len: u32,
+    pub flags: u32,
+    pub info: ArchiveEntryInfo,
+}
+
+impl ArchiveReaderEntry {
+    /// The unique name of the entry, which is the rename if present.
+    pub fn filename(&self) -> &str {
+        self.rename.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// A seekable archive reader.
+///
+/// The table is parsed once when the reader is created, and entries are read
+/// on demand. Since entries are not read up front, the checksum of `PM`
+/// reader archives is only validated by [`ArchiveReader::verify_checksum`].
+pub struct ArchiveReader<R: Read + Seek> {
+    read: CountingReader<R>,
+    entries: Vec<ArchiveReaderEntry>,
+    checksum: u32,
+    version: Version,
+}
+
+impl<R: Read + Seek> ArchiveReader<R> {
+    pub fn new(mut read: CountingReader<R>, version: Version) -> Result<Self> {
+        let (entries, checksum) = read_table(&mut read, version)?;
+        let mut seen = Rename::new();
+        let entries = entries
+            .into_iter()
+            .map(|entry| {
+                let TableEntry {
+                    name,
+                    start,
+                    len,
+                    flags,
+                    info,
+                } = entry;
+                let rename = seen.insert(&name);
+                ArchiveReaderEntry {
+                    name,
+                    rename,
+                    start,
+                    len,
+                    flags,
+                    info,
+                }
+            })
+            .collect();
+        Ok(Self {
+            read,
+            entries,
+            checksum,
+            version,
+        })
+    }
+
+    pub fn entries(&self) -> &[ArchiveReaderEntry] {
+        &self.entries
+    }
+
+    /// The archive manifest, as produced by `read_archive`.
+    pub fn manifest(&self) -> Vec<ArchiveEntry> {
+        self.entries
+            .iter()
+            .map(|entry| ArchiveEntry {
+                name: entry.name.clone(),
+                rename: entry.rename.clone(),
+                flags: entry.flags,
+                info: entry.info.clone(),
+            })
+            .collect()
+    }
+
+    /// Find the index of an entry by its unique name (see
+    /// [`ArchiveReaderEntry::filename`]).
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.filename() == name)
+    }
+
+    pub fn read_entry(&mut self, index: usize) -> Result<Vec<u8>> {
+        let entry = self.entries.get(index).ok_or_else(|| {
+            assert_with_msg!(
+                "Entry index {} is out of range (count: {})",
+                index,
+                self.entries.len()
+            )
+        })?;
+        let start = u32_to_usize(entry.start);
+        let len = u32_to_usize(entry.len);
+        trace!(
+            "Reading entry {}/`{}` data with length {} at {}",
+            index, entry.name, len, start
+        );
+        self.read.seek(SeekFrom::Start(u64::from(entry.start)))?;
+        let mut buffer = vec![0; len];
+        self.read.read_exact(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    pub fn read_entry_by_name(&mut self, name: &str) -> Result<Vec<u8>> {
+        let index = self
+            .find(name)
+            .ok_or_else(|| assert_with_msg!("Entry `{}` not found", name))?;
+        self.read_entry(index)
+    }
+
+    /// Read all entries to validate the checksum. This is a no-op unless the
+    /// archive is a `PM` reader archive.
+    pub fn verify_checksum(&mut self) -> Result<()> {
+        if !matches!(self.version, Version::Two(Mode::Reader)) {
+            return Ok(());
+        }
+        debug!("Verifying archive checksum");
+        let mut crc = CRC32_INIT;
+        for index in 0..self.entries.len() {
+            let buffer = self.read_entry(index)?;
+            crc = crc32_update(crc, &buffer);
+        }
+        assert_that!("archive checksum", crc == self.checksum, self.read.offset)?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> CountingReader<R> {
+        self.read
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,74 @@
+use super::*;
+use crate::write_archive;
+use mech3ax_api_types::archive::ArchiveEntryInfoInvalid;
+use mech3ax_common::Error;
+use mech3ax_common::io_ext::CountingWriter;
+use std::io::Cursor;
+
+fn entry(name: &str, rename: Option<&str>) -> ArchiveEntry {
+    ArchiveEntry {
+        name: name.to_string(),
+        rename: rename.map(str::to_string),
+        flags: 0,
+        info: ArchiveEntryInfo::Invalid(ArchiveEntryInfoInvalid {
+            comment: vec![0; 64],
+            filetime: 0,
+        }),
+    }
+}
+
+fn archive(version: Version) -> (Vec<ArchiveEntry>, Vec<u8>) {
+    let entries = vec![
+        entry("a.wav", None),
+        entry("b.wav", None),
+        entry("a.wav", Some("a-1.wav")),
+    ];
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    write_archive::<_, _, Error>(
+        &mut write,
+        &entries,
+        |name, _| Ok(name.as_bytes().to_vec()),
+        version,
+    )
+    .unwrap();
+    (entries, write.into_inner())
+}
+
+#[test]
+fn reader_random_access() {
+    let (entries, data) = archive(Version::One);
+    let read = CountingReader::new(Cursor::new(data));
+    let mut reader = ArchiveReader::new(read, Version::One).unwrap();
+
+    assert_eq!(reader.manifest(), entries);
+    assert_eq!(reader.find("a-1.wav"), Some(2));
+    assert_eq!(reader.read_entry(2).unwrap(), b"a-1.wav");
+    assert_eq!(reader.read_entry_by_name("a.wav").unwrap(), b"a.wav");
+    assert_eq!(reader.read_entry(1).unwrap(), b"b.wav");
+    assert!(reader.read_entry(3).is_err());
+    assert!(reader.read_entry_by_name("c.wav").is_err());
+}
+
+#[test]
+fn reader_motion_lengths() {
+    let version = Version::Two(Mode::Motion);
+    let (_, data) = archive(version);
+    let read = CountingReader::new(Cursor::new(data));
+    let mut reader = ArchiveReader::new(read, version).unwrap();
+    assert_eq!(reader.entries()[0].len, 5);
+    assert_eq!(reader.read_entry(2).unwrap(), b"a-1.wav");
+}
+
+#[test]
+fn reader_checksum() {
+    let version = Version::Two(Mode::Reader);
+    let (_, mut data) = archive(version);
+    let read = CountingReader::new(Cursor::new(data.clone()));
+    let mut reader = ArchiveReader::new(read, version).unwrap();
+    reader.verify_checksum().unwrap();
+
+    data[0] ^= 0xFF;
+    let read = CountingReader::new(Cursor::new(data));
+    let mut reader = ArchiveReader::new(read, version).unwrap();
+    assert!(reader.verify_checksum().is_err());
+}
@@ -6,10 +6,40 @@ use std::io::{Read, Result, Seek, SeekFrom, Write};
 #[cfg(not(target_endian = "little"))]
 compile_error!("only little-endian architectures are supported");
 
+/// A region of the input covered by a single read, see
+/// [`CountingReader::record_layout`].
+///
+/// The name is the type name for structures, or the kind of read otherwise
+/// (e.g. `u32` or `string`). Fields aren't recorded, so an offset inside a
+/// structure can only be given relative to its start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadSpan {
+    pub offset: usize,
+    pub len: usize,
+    pub name: &'static str,
+}
+
+impl ReadSpan {
+    /// Find the span covering the offset, or the closest span before it.
+    pub fn find(layout: &[Self], offset: usize) -> Option<&Self> {
+        layout
+            .iter()
+            .rev()
+            .find(|span| span.offset <= offset && offset < span.offset + span.len)
+            .or_else(|| {
+                layout
+                    .iter()
+                    .filter(|span| span.offset <= offset)
+                    .max_by_key(|span| span.offset)
+            })
+    }
+}
+
 pub struct CountingReader<R: Read> {
     inner: R,
     pub offset: usize,
     pub prev: usize,
+    layout: Option<Vec<ReadSpan>>,
 }
 
 impl<R: Read> CountingReader<R> {
@@ -19,9 +49,22 @@ impl<R: Read> CountingReader<R> {
             inner: read,
             offset: 0,
             prev: 0,
+            layout: None,
         }
     }
 
+    /// Record the offset, length, and type of every subsequent read, so
+    /// that offsets can be attributed to the structure that was read there
+    /// (but not to a field).
+    pub fn record_layout(&mut self) {
+        self.layout = Some(Vec::new());
+    }
+
+    /// Take the recorded layout (empty if recording wasn't enabled).
+    pub fn take_layout(&mut self) -> Vec<ReadSpan> {
+        self.layout.take().unwrap_or_default()
+    }
+
     pub fn get_mut(&mut self) -> &mut R {
         &mut self.inner
     }
@@ -32,45 +75,57 @@ impl<R: Read> CountingReader<R> {
     }
 
     #[inline]
-    pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+    fn read_named(&mut self, buf: &mut [u8], name: &'static str) -> Result<()> {
         self.inner.read_exact(buf)?;
+        if let Some(layout) = &mut self.layout {
+            layout.push(ReadSpan {
+                offset: self.offset,
+                len: buf.len(),
+                name,
+            });
+        }
         self.prev = self.offset;
         self.offset += buf.len();
         Ok(())
     }
 
+    #[inline]
+    pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.read_named(buf, "bytes")
+    }
+
     #[inline]
     pub fn read_u32(&mut self) -> Result<u32> {
         let mut buf = [0; 4];
-        self.read_exact(&mut buf)?;
+        self.read_named(&mut buf, "u32")?;
         Ok(u32::from_le_bytes(buf))
     }
 
     #[inline]
     pub fn read_i32(&mut self) -> Result<i32> {
         let mut buf = [0; 4];
-        self.read_exact(&mut buf)?;
+        self.read_named(&mut buf, "i32")?;
         Ok(i32::from_le_bytes(buf))
     }
 
     #[inline]
     pub fn read_f32(&mut self) -> Result<f32> {
         let mut buf = [0; 4];
-        self.read_exact(&mut buf)?;
+        self.read_named(&mut buf, "f32")?;
         Ok(f32::from_le_bytes(buf))
     }
 
     #[inline]
     pub fn read_u16(&mut self) -> Result<u16> {
         let mut buf = [0; 2];
-        self.read_exact(&mut buf)?;
+        self.read_named(&mut buf, "u16")?;
         Ok(u16::from_le_bytes(buf))
     }
 
     #[inline]
     pub fn read_i16(&mut self) -> Result<i16> {
         let mut buf = [0; 2];
-        self.read_exact(&mut buf)?;
+        self.read_named(&mut buf, "i16")?;
         Ok(i16::from_le_bytes(buf))
     }
 
@@ -79,7 +134,7 @@ impl<R: Read> CountingReader<R> {
         let mut s = S::zeroed();
         let buf = s.as_bytes_mut();
         let len = buf.len();
-        self.read_exact(buf)?;
+        self.read_named(buf, std::any::type_name::<S>())?;
         trace!("{:#?} (len: {}, at {})", s, len, self.prev);
         Ok(s)
     }
@@ -88,7 +143,7 @@ impl<R: Read> CountingReader<R> {
     pub fn read_struct_no_log<S: AsBytes>(&mut self) -> Result<S> {
         let mut s = S::zeroed();
         let buf = s.as_bytes_mut();
-        self.read_exact(buf)?;
+        self.read_named(buf, std::any::type_name::<S>())?;
         Ok(s)
     }
 
@@ -96,7 +151,7 @@ impl<R: Read> CountingReader<R> {
         let offset = self.offset;
         let len = u32_to_usize(self.read_u32()?);
         let mut buf = vec![0u8; len];
-        self.read_exact(&mut buf)?;
+        self.read_named(&mut buf, "string")?;
         trace!("`{}` (len: {}, at {})", buf.escape_ascii(), len, offset);
         if !buf.is_ascii() {
             // is_ascii is optimised, only try and find the invalid character after it
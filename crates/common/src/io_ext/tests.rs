@@ -124,3 +124,41 @@ fn string_roundtrip() {
     assert_eq!(expected, actual);
     assert_eq!(reader.offset, expected.len() + 4);
 }
+
+#[test]
+fn layout_recorded() {
+    let mut writer = CountingWriter::new(Cursor::new(vec![]), 0);
+    writer.write_u32(1).unwrap();
+    writer
+        .write_struct(&TestStruct {
+            name: Ascii::from_str_padded("Hello World"),
+            int: 2,
+        })
+        .unwrap();
+    writer.write_string("foo").unwrap();
+
+    let mut cursor = writer.into_inner();
+    cursor.set_position(0);
+    let mut reader = CountingReader::new(cursor);
+    reader.read_u32().unwrap();
+    reader.record_layout();
+    let _: TestStruct = reader.read_struct().unwrap();
+    reader.read_string().unwrap();
+    let layout = reader.take_layout();
+
+    let size = std::mem::size_of::<TestStruct>();
+    assert_eq!(layout.len(), 3);
+    assert_eq!(layout[0].offset, 4);
+    assert_eq!(layout[0].len, size);
+    assert!(layout[0].name.ends_with("TestStruct"));
+    assert_eq!(layout[1].name, "u32");
+    assert_eq!(layout[2].name, "string");
+
+    let span = ReadSpan::find(&layout, 4 + 5).unwrap();
+    assert!(span.name.ends_with("TestStruct"));
+    assert_eq!(ReadSpan::find(&layout, 0), None);
+    // past the end returns the closest span
+    let span = ReadSpan::find(&layout, 1000).unwrap();
+    assert_eq!(span.name, "string");
+    assert!(reader.take_layout().is_empty());
+}
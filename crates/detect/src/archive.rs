@@ -0,0 +1,111 @@
+use super::{ARCHIVE_ONE, Detection, FileKind, ONLY_MW, ONLY_PM};
+use log::debug;
+use mech3ax_archive::{
+    ArchiveReader, ArchiveReaderEntry, HEADER_ONE_SIZE, HEADER_TWO_SIZE, Mode, TABLE_ENTRY_SIZE,
+    VERSION_ONE, VERSION_TWO, Version,
+};
+use mech3ax_common::Result;
+use mech3ax_common::io_ext::CountingReader;
+use mech3ax_gamez::mechlib::{VERSION_MW as MECHLIB_VERSION_MW, VERSION_PM as MECHLIB_VERSION_PM};
+use std::io::{Read, Seek, SeekFrom};
+
+fn read_u32_at<R: Read + Seek>(read: &mut CountingReader<R>, offset: usize) -> Result<u32> {
+    read.seek(SeekFrom::Start(offset as u64))?;
+    Ok(read.read_u32()?)
+}
+
+/// Check the archive trailer is plausible, before trying to read the table.
+fn archive_version<R: Read + Seek>(
+    read: &mut CountingReader<R>,
+    size: usize,
+) -> Result<Option<Version>> {
+    let fits = |header_size: i64, count: u32| {
+        let table_size = header_size + i64::from(count) * TABLE_ENTRY_SIZE;
+        table_size <= size as i64
+    };
+    if size >= HEADER_TWO_SIZE as usize {
+        let version = read_u32_at(read, size - HEADER_TWO_SIZE as usize)?;
+        let count = read.read_u32()?;
+        if version == VERSION_TWO && fits(HEADER_TWO_SIZE, count) {
+            return Ok(Some(Version::Two(Mode::ReaderBypass)));
+        }
+    }
+    if size >= HEADER_ONE_SIZE as usize {
+        let version = read_u32_at(read, size - HEADER_ONE_SIZE as usize)?;
+        let count = read.read_u32()?;
+        if version == VERSION_ONE && fits(HEADER_ONE_SIZE, count) {
+            return Ok(Some(Version::One));
+        }
+    }
+    Ok(None)
+}
+
+fn has_ext(entries: &[ArchiveReaderEntry], ext: &str) -> bool {
+    !entries.is_empty()
+        && entries.iter().all(|entry| {
+            entry
+                .name
+                .rsplit_once('.')
+                .is_some_and(|(_, e)| e.eq_ignore_ascii_case(ext))
+        })
+}
+
+fn has_name(entries: &[ArchiveReaderEntry], name: &str) -> bool {
+    entries.iter().any(|entry| entry.name == name)
+}
+
+pub(crate) fn detect_archive<R: Read + Seek>(
+    read: &mut CountingReader<R>,
+    size: usize,
+    name: &str,
+) -> Result<Option<Detection>> {
+    let Some(version) = archive_version(read, size)? else {
+        return Ok(None);
+    };
+    // `read_table` seeks relative to the end, and checks entries are plausible
+    let inner = CountingReader::new(read.get_mut());
+    let mut reader = match ArchiveReader::new(inner, version) {
+        Ok(reader) => reader,
+        Err(e) => {
+            debug!("Not an archive: {:?}", e);
+            return Ok(None);
+        }
+    };
+    let entries = reader.entries();
+    let is_pm = matches!(version, Version::Two(_));
+    let default_games = if is_pm { ONLY_PM } else { ARCHIVE_ONE };
+
+    if has_name(entries, "zSaveHeader") {
+        return Ok(Some(Detection::new(FileKind::Savegame, ONLY_MW)));
+    }
+    if has_name(entries, "format") && has_name(entries, "version") {
+        let data = reader.read_entry_by_name("version")?;
+        let version = data
+            .get(0..4)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u32::from_le_bytes);
+        let games = match version {
+            Some(MECHLIB_VERSION_MW) => ONLY_MW,
+            Some(MECHLIB_VERSION_PM) => ONLY_PM,
+            _ => default_games,
+        };
+        return Ok(Some(Detection::new(FileKind::Mechlib, games)));
+    }
+    if has_ext(entries, "zrd") {
+        return Ok(Some(Detection::new(FileKind::Reader, default_games)));
+    }
+    if has_ext(entries, "wav") {
+        return Ok(Some(Detection::new(FileKind::Sounds, default_games)));
+    }
+    // the PM motion archive stores a length of 1 for every entry, which is
+    // backfilled when reading with `Mode::Motion`
+    if is_pm && !entries.is_empty() && entries.iter().all(|entry| entry.len == 1) {
+        return Ok(Some(Detection::new(FileKind::Motion, ONLY_PM)));
+    }
+    // the MW motion archive has no distinguishing entries, and RC and CS
+    // don't have motion
+    if !is_pm && name == "motion.zbd" {
+        return Ok(Some(Detection::new(FileKind::Motion, ONLY_MW)));
+    }
+    Ok(Some(Detection::new(FileKind::Archive, default_games)))
+}
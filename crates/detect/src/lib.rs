@@ -0,0 +1,203 @@
+//! Detect the kind of a file, and which games it could be from, by sniffing
+//! the header (or the archive table).
+#![warn(clippy::all, clippy::cargo)]
+mod archive;
+
+use mech3ax_common::io_ext::CountingReader;
+use mech3ax_common::{GameType, Result};
+use std::fmt;
+use std::io::{Read, Seek, SeekFrom};
+
+// the readers' constants, named for matching
+const INTERP_SIGNATURE: u32 = mech3ax_interp::SIGNATURE.0;
+const INTERP_VERSION: u32 = mech3ax_interp::VERSION;
+const ANIM_SIGNATURE: u32 = mech3ax_anim::SIGNATURE.0;
+const ANIM_VERSION_RC: u32 = mech3ax_anim::VERSION_RC;
+const ANIM_VERSION_MW: u32 = mech3ax_anim::VERSION_MW;
+const ANIM_VERSION_PM: u32 = mech3ax_anim::VERSION_PM;
+const GAMEZ_SIGNATURE: u32 = mech3ax_gamez::gamez::SIGNATURE;
+const GAMEZ_VERSION_RC: u32 = mech3ax_gamez::gamez::VERSION_RC;
+const GAMEZ_VERSION_MW: u32 = mech3ax_gamez::gamez::VERSION_MW;
+const GAMEZ_VERSION_PM: u32 = mech3ax_gamez::gamez::VERSION_PM;
+const GAMEZ_VERSION_CS: u32 = mech3ax_gamez::gamez::VERSION_CS;
+const MAP_VERSION: u32 = mech3ax_zmap::MAP_VERSION;
+
+pub const ALL_GAMES: &[GameType] = &[GameType::MW, GameType::PM, GameType::RC, GameType::CS];
+const ONLY_MW: &[GameType] = &[GameType::MW];
+/// MW and PM read messages the same way.
+const MW_PM: &[GameType] = &[GameType::MW, GameType::PM];
+const ONLY_PM: &[GameType] = &[GameType::PM];
+const ONLY_RC: &[GameType] = &[GameType::RC];
+const ONLY_CS: &[GameType] = &[GameType::CS];
+/// Games that use version one archives.
+const ARCHIVE_ONE: &[GameType] = &[GameType::MW, GameType::RC, GameType::CS];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Sounds,
+    Interp,
+    Reader,
+    Messages,
+    Textures,
+    Motion,
+    Mechlib,
+    Gamez,
+    Anim,
+    Savegame,
+    Zmap,
+    /// An archive with unrecognised entries.
+    Archive,
+}
+
+impl fmt::Display for FileKind {
+    /// The subcommand name.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Sounds => "sounds",
+            Self::Interp => "interp",
+            Self::Reader => "reader",
+            Self::Messages => "messages",
+            Self::Textures => "textures",
+            Self::Motion => "motion",
+            Self::Mechlib => "mechlib",
+            Self::Gamez => "gamez",
+            Self::Anim => "anim",
+            Self::Savegame => "savegame",
+            Self::Zmap => "zmap",
+            Self::Archive => "archive",
+        };
+        f.write_str(s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Detection {
+    pub kind: FileKind,
+    /// The games the file could be from. For most file kinds, the data is
+    /// read the same way for all of these games.
+    pub games: &'static [GameType],
+}
+
+impl Detection {
+    const fn new(kind: FileKind, games: &'static [GameType]) -> Self {
+        Self { kind, games }
+    }
+
+    /// The game, if the file can only be from one game.
+    pub fn game(&self) -> Option<GameType> {
+        match self.games {
+            [game] => Some(*game),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Detection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (", self.kind)?;
+        for (index, game) in self.games.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", game)?;
+        }
+        f.write_str(")")
+    }
+}
+
+fn u32_at(buf: &[u8], offset: usize) -> Option<u32> {
+    let bytes = buf.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn i32_at(buf: &[u8], offset: usize) -> Option<i32> {
+    u32_at(buf, offset).map(|v| v as i32)
+}
+
+fn detect_header(head: &[u8], name: &str) -> Option<Detection> {
+    if head.starts_with(b"MZ") {
+        let games = match name {
+            "mech3msg.dll" => MW_PM,
+            "messages.dll" => ONLY_RC,
+            "strings.dll" => ONLY_CS,
+            _ => ALL_GAMES,
+        };
+        return Some(Detection::new(FileKind::Messages, games));
+    }
+
+    let signature = u32_at(head, 0)?;
+    let version = u32_at(head, 4)?;
+    match signature {
+        INTERP_SIGNATURE if version == INTERP_VERSION => {
+            return Some(Detection::new(FileKind::Interp, ALL_GAMES));
+        }
+        ANIM_SIGNATURE => {
+            let games = match version {
+                ANIM_VERSION_RC => ONLY_RC,
+                ANIM_VERSION_MW => ONLY_MW,
+                ANIM_VERSION_PM => ONLY_PM,
+                _ => return None,
+            };
+            return Some(Detection::new(FileKind::Anim, games));
+        }
+        GAMEZ_SIGNATURE => {
+            let games = match version {
+                GAMEZ_VERSION_RC => ONLY_RC,
+                GAMEZ_VERSION_MW => ONLY_MW,
+                GAMEZ_VERSION_PM => ONLY_PM,
+                GAMEZ_VERSION_CS => ONLY_CS,
+                _ => return None,
+            };
+            return Some(Detection::new(FileKind::Gamez, games));
+        }
+        _ => {}
+    }
+    None
+}
+
+fn detect_weak_header(head: &[u8]) -> Option<Detection> {
+    // textures: zero, has entries, palette count, texture count, zero, zero
+    let is_textures = u32_at(head, 0) == Some(0)
+        && u32_at(head, 4) == Some(1)
+        && i32_at(head, 8).is_some_and(|count| count >= 0)
+        && u32_at(head, 12).is_some_and(|count| count > 0)
+        && u32_at(head, 16) == Some(0)
+        && u32_at(head, 20) == Some(0);
+    if is_textures {
+        return Some(Detection::new(FileKind::Textures, ALL_GAMES));
+    }
+
+    let is_zmap = u32_at(head, 0) == Some(MAP_VERSION)
+        && u32_at(head, 4).is_some_and(|unk04| (1..=31).contains(&unk04));
+    if is_zmap {
+        return Some(Detection::new(FileKind::Zmap, ONLY_RC));
+    }
+    None
+}
+
+/// Detect the kind of a file, and which games it could be from.
+///
+/// The file name is optional, but helps with some ambiguous cases (e.g.
+/// `Mech3Msg.dll`, or archives without recognisable entries).
+pub fn detect<R: Read + Seek>(
+    read: &mut CountingReader<R>,
+    name: Option<&str>,
+) -> Result<Option<Detection>> {
+    let name = name.unwrap_or_default().to_ascii_lowercase();
+
+    let size = read.seek(SeekFrom::End(0))?;
+    read.seek(SeekFrom::Start(0))?;
+    let mut head = vec![0; size.min(24)];
+    read.read_exact(&mut head)?;
+
+    if let Some(detection) = detect_header(&head, &name) {
+        return Ok(Some(detection));
+    }
+    if let Some(detection) = archive::detect_archive(read, size, &name)? {
+        return Ok(Some(detection));
+    }
+    Ok(detect_weak_header(&head))
+}
+
+#[cfg(test)]
+mod tests;
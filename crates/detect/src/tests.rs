@@ -0,0 +1,204 @@
+use super::*;
+use mech3ax_api_types::anim::{AnimMetadata, AnimMission};
+use mech3ax_api_types::archive::{ArchiveEntry, ArchiveEntryInfo, ArchiveEntryInfoInvalid};
+use mech3ax_api_types::gamez::{GameZ, GameZMetadata};
+use mech3ax_api_types::zmap::Zmap;
+use mech3ax_api_types::{Count, Vec3};
+use mech3ax_archive::{HEADER_ONE_SIZE, Mode, TABLE_ENTRY_SIZE, Version, write_archive};
+use mech3ax_common::Error;
+use mech3ax_common::io_ext::CountingWriter;
+use mech3ax_timestamp::DateTime;
+use std::io::Cursor;
+
+fn detect_data(data: Vec<u8>, name: Option<&str>) -> Option<Detection> {
+    let mut read = CountingReader::new(Cursor::new(data));
+    detect(&mut read, name).unwrap()
+}
+
+fn archive(names: &[&str], version: Version) -> Vec<u8> {
+    let entries: Vec<ArchiveEntry> = names
+        .iter()
+        .map(|name| ArchiveEntry {
+            name: name.to_string(),
+            rename: None,
+            flags: 0,
+            info: ArchiveEntryInfo::Invalid(ArchiveEntryInfoInvalid {
+                comment: vec![0; 64],
+                filetime: 0,
+            }),
+        })
+        .collect();
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    write_archive::<_, _, Error>(
+        &mut write,
+        &entries,
+        |name, _| match name {
+            "version" => Ok(41u32.to_le_bytes().to_vec()),
+            _ => Ok(vec![0; 16]),
+        },
+        version,
+    )
+    .unwrap();
+    write.into_inner()
+}
+
+fn header(signature: u32, version: u32) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&signature.to_le_bytes());
+    data.extend_from_slice(&version.to_le_bytes());
+    data.extend_from_slice(&[0; 32]);
+    data
+}
+
+#[test]
+fn detect_interp() {
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    mech3ax_interp::write_interp(&mut write, &[]).unwrap();
+    let detection = detect_data(write.into_inner(), None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Interp, ALL_GAMES));
+}
+
+#[test]
+fn detect_gamez() {
+    let gamez = GameZ {
+        textures: vec![],
+        materials: vec![],
+        models: vec![],
+        nodes: vec![],
+        metadata: GameZMetadata {
+            datetime: DateTime::UNIX_EPOCH,
+            material_array_size: Count::from_usize(1).unwrap(),
+            model_array_size: Count::from_usize(1).unwrap(),
+            node_array_size: Count::from_usize(1).unwrap(),
+            node_last_free: 0,
+            image_ptrs: None,
+        },
+    };
+    type WriteGameZ = fn(&mut CountingWriter<Vec<u8>>, &GameZ) -> mech3ax_common::Result<()>;
+    let writers: [(WriteGameZ, GameType); 4] = [
+        (mech3ax_gamez::gamez::mw::write_gamez, GameType::MW),
+        (mech3ax_gamez::gamez::pm::write_gamez, GameType::PM),
+        (mech3ax_gamez::gamez::rc::write_gamez, GameType::RC),
+        (mech3ax_gamez::gamez::cs::write_gamez, GameType::CS),
+    ];
+    for (write_gamez, game) in writers {
+        let mut write = CountingWriter::new(Vec::new(), 0);
+        write_gamez(&mut write, &gamez).unwrap();
+        let detection = detect_data(write.into_inner(), None).unwrap();
+        assert_eq!(detection.kind, FileKind::Gamez, "{}", game);
+        assert_eq!(detection.game(), Some(game));
+    }
+}
+
+#[test]
+fn detect_anim() {
+    let metadata = AnimMetadata {
+        mission: AnimMission::Unknown,
+        gravity: -9.8,
+        datetime: Some(DateTime::UNIX_EPOCH),
+        anim_def_names: vec![],
+        script_names: vec![],
+        anim_list: vec![],
+    };
+    type WriteAnim = fn(
+        &mut CountingWriter<Vec<u8>>,
+        &AnimMetadata,
+        fn(mech3ax_anim::LoadItemName<'_>) -> Result<mech3ax_anim::LoadItem>,
+    ) -> Result<()>;
+    let writers: [(WriteAnim, GameType); 3] = [
+        (mech3ax_anim::mw::write_anim, GameType::MW),
+        (mech3ax_anim::pm::write_anim, GameType::PM),
+        (mech3ax_anim::rc::write_anim, GameType::RC),
+    ];
+    for (write_anim, game) in writers {
+        let mut write = CountingWriter::new(Vec::new(), 0);
+        write_anim(&mut write, &metadata, |name| {
+            Err(mech3ax_common::err!("Unexpected load of {:?}", name))
+        })
+        .unwrap();
+        let detection = detect_data(write.into_inner(), None).unwrap();
+        assert_eq!(detection.kind, FileKind::Anim, "{}", game);
+        assert_eq!(detection.game(), Some(game));
+    }
+}
+
+#[test]
+fn detect_zmap() {
+    let map = Zmap {
+        unk04: 1,
+        min: Vec3::DEFAULT,
+        max: Vec3::DEFAULT,
+        features: vec![],
+    };
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    mech3ax_zmap::write_map(&mut write, &map).unwrap();
+    let detection = detect_data(write.into_inner(), None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Zmap, ONLY_RC));
+}
+
+#[test]
+fn detect_signatures() {
+    let detection = detect_data(header(ANIM_SIGNATURE, ANIM_VERSION_MW), None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Anim, ONLY_MW));
+    let detection = detect_data(header(GAMEZ_SIGNATURE, GAMEZ_VERSION_CS), None).unwrap();
+    assert_eq!(detection.game(), Some(GameType::CS));
+    assert_eq!(detect_data(header(GAMEZ_SIGNATURE, 0), None), None);
+
+    let detection = detect_data(b"MZ\x90\x00".to_vec(), Some("Mech3Msg.dll")).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Messages, MW_PM));
+    let detection = detect_data(b"MZ\x90\x00".to_vec(), None).unwrap();
+    assert_eq!(detection.game(), None);
+}
+
+#[test]
+fn detect_archives() {
+    let data = archive(&["a.zrd", "b.zrd"], Version::One);
+    let detection = detect_data(data, None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Reader, ARCHIVE_ONE));
+
+    let data = archive(&["a.wav"], Version::Two(Mode::Sounds));
+    let detection = detect_data(data, None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Sounds, ONLY_PM));
+
+    let data = archive(&["walk", "run"], Version::Two(Mode::Motion));
+    let detection = detect_data(data, None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Motion, ONLY_PM));
+
+    let data = archive(&["format", "version", "materials"], Version::One);
+    let detection = detect_data(data, None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Mechlib, ONLY_PM));
+
+    let data = archive(&["zSaveHeader", "foo"], Version::One);
+    let detection = detect_data(data, None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Savegame, ONLY_MW));
+
+    let data = archive(&["walk"], Version::One);
+    let detection = detect_data(data.clone(), None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Archive, ARCHIVE_ONE));
+    let detection = detect_data(data, Some("MOTION.ZBD")).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Motion, ONLY_MW));
+}
+
+#[test]
+fn detect_archive_entry_overflow() {
+    let mut data = archive(&["a.zrd"], Version::One);
+    let start = data.len() - HEADER_ONE_SIZE as usize - TABLE_ENTRY_SIZE as usize;
+    data[start..start + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(detect_data(data, None), None);
+}
+
+#[test]
+fn detect_textures() {
+    let mut data = Vec::new();
+    for value in [0u32, 1, 0, 1, 0, 0] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    let detection = detect_data(data, None).unwrap();
+    assert_eq!(detection, Detection::new(FileKind::Textures, ALL_GAMES));
+}
+
+#[test]
+fn detect_unknown() {
+    assert_eq!(detect_data(vec![], None), None);
+    assert_eq!(detect_data(vec![0xFF; 100], None), None);
+}
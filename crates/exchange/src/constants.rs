@@ -50,7 +50,8 @@ type_map! {
     Bytes => 51,
     Seq => 60,
     Struct => 70,
-    // Map => 71,
+    Map => 71,
     EnumUnit => 80,
     EnumNewType => 81,
+    Header => 90,
 }
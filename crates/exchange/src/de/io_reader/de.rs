@@ -168,11 +168,15 @@ impl<'a, 'de: 'a, R: Read> de::Deserializer<'de> for &'a mut IoReader<R> {
     }
 
     #[inline]
-    fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        err_unsupported!()
+        let len = self.read_map()?;
+        visitor.visit_map(SizedMapAccess {
+            deserializer: self,
+            len,
+        })
     }
 
     #[inline]
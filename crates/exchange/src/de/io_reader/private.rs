@@ -120,6 +120,12 @@ impl<R: Read> IoReader<R> {
         self.read_usize()
     }
 
+    #[inline]
+    pub fn read_map(&mut self) -> Result<usize> {
+        self.expect_type(TypeMap::Map)?;
+        self.read_usize()
+    }
+
     #[inline]
     pub fn read_struct(&mut self) -> Result<usize> {
         self.expect_type(TypeMap::Struct)?;
@@ -1,23 +1,97 @@
 // mod slice_reader;
 mod io_reader;
 
-use crate::error::Result;
+use crate::constants::TypeMap;
+use crate::error::{Error, ErrorCode, Result};
+use std::io::Read;
 
+const HEADER_TYPE: [u8; 4] = TypeMap::Header.to_u32().to_le_bytes();
+
+/// Split the optional schema header from a slice.
+fn split_header(slice: &[u8]) -> (Option<u64>, &[u8]) {
+    match slice.split_first_chunk::<4>() {
+        Some((ty, rest)) if *ty == HEADER_TYPE => match rest.split_first_chunk::<8>() {
+            Some((hash, rest)) => (Some(u64::from_le_bytes(*hash)), rest),
+            // let the deserializer report the error
+            None => (None, slice),
+        },
+        _ => (None, slice),
+    }
+}
+
+/// Read the optional schema header from a reader. If there is no header, the
+/// bytes that were read must be prepended to the remaining data.
+fn read_header<R: Read>(reader: &mut R) -> Result<(Option<u64>, Vec<u8>)> {
+    let mut ty = [0u8; 4];
+    reader.read_exact(&mut ty).map_err(Error::io)?;
+    if ty != HEADER_TYPE {
+        return Ok((None, ty.to_vec()));
+    }
+    let mut hash = [0u8; 8];
+    reader.read_exact(&mut hash).map_err(Error::io)?;
+    Ok((Some(u64::from_le_bytes(hash)), Vec::new()))
+}
+
+fn check_header(header: Option<u64>, schema_hash: u64) -> Result<()> {
+    match header {
+        // the header is optional, so data without one can't be checked
+        None => Ok(()),
+        Some(actual) if actual == schema_hash => Ok(()),
+        Some(actual) => Err(Error::new(ErrorCode::SchemaMismatch {
+            expected: schema_hash,
+            actual,
+        })),
+    }
+}
+
+/// Deserialize a value from binary exchange data.
+///
+/// A schema header is skipped, but not checked.
 #[inline]
 pub fn from_slice<'a, T>(slice: &'a [u8]) -> Result<T>
 where
     T: serde::Deserialize<'a>,
 {
+    let (_header, slice) = split_header(slice);
     let mut reader = io_reader::IoReader::new(slice);
     T::deserialize(&mut reader)
 }
 
+/// Deserialize a value from binary exchange data.
+///
+/// A schema header is skipped, but not checked.
 #[inline]
-pub fn from_reader<R, T>(reader: R) -> Result<T>
+pub fn from_reader<R, T>(mut reader: R) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+    R: std::io::Read,
+{
+    let (_header, prefix) = read_header(&mut reader)?;
+    let mut reader = io_reader::IoReader::new(prefix.as_slice().chain(reader));
+    T::deserialize(&mut reader)
+}
+
+/// Deserialize a value from binary exchange data, checking the schema hash if
+/// the data has a schema header.
+pub fn from_slice_with_schema<'a, T>(slice: &'a [u8], schema_hash: u64) -> Result<T>
+where
+    T: serde::Deserialize<'a>,
+{
+    let (header, slice) = split_header(slice);
+    check_header(header, schema_hash)?;
+    let mut reader = io_reader::IoReader::new(slice);
+    T::deserialize(&mut reader)
+}
+
+/// Deserialize a value from binary exchange data, checking the schema hash if
+/// the data has a schema header.
+pub fn from_reader_with_schema<R, T>(mut reader: R, schema_hash: u64) -> Result<T>
 where
     T: serde::de::DeserializeOwned,
     R: std::io::Read,
 {
-    let mut reader = io_reader::IoReader::new(reader);
+    let (header, prefix) = read_header(&mut reader)?;
+    check_header(header, schema_hash)?;
+    let mut reader = io_reader::IoReader::new(prefix.as_slice().chain(reader));
     T::deserialize(&mut reader)
 }
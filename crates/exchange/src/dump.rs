@@ -0,0 +1,218 @@
+//! A schema-less dumper for the exchange format.
+//!
+//! Since the format is self-describing (except for struct names and enum
+//! variant names), any buffer can be decoded into a tree of values, which is
+//! useful for debugging mismatches between serializers.
+use crate::constants::TypeMap;
+use crate::error::{Error, ErrorCode, Result};
+use std::fmt::Write as _;
+
+/// The maximum nesting depth, to avoid stack overflows on malicious input.
+const MAX_DEPTH: usize = 128;
+/// The maximum number of bytes printed for byte buffers.
+const MAX_BYTES: usize = 32;
+
+struct Dumper<'a, 'o> {
+    data: &'a [u8],
+    offset: usize,
+    out: &'o mut String,
+}
+
+impl<'a> Dumper<'a, '_> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| Error::io(std::io::ErrorKind::UnexpectedEof.into()))?;
+        let buf = &self.data[self.offset..end];
+        self.offset = end;
+        Ok(buf)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let buf = self.take(N)?;
+        // cannot fail, since the length was checked
+        Ok(buf.try_into().unwrap())
+    }
+
+    fn read_type(&mut self) -> Result<TypeMap> {
+        let value = u32::from_le_bytes(self.take_array()?);
+        TypeMap::from_u32(value).ok_or_else(|| Error::new(ErrorCode::InvalidType))
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take_array()?))
+    }
+
+    fn read_usize(&mut self) -> Result<usize> {
+        Ok(usize::from_le_bytes(self.take_array()?))
+    }
+
+    fn read_string_raw(&mut self) -> Result<String> {
+        let len = self.read_usize()?;
+        let buf = self.take(len)?;
+        String::from_utf8(buf.to_vec()).map_err(|e| Error::new(ErrorCode::InvalidUtf8(e)))
+    }
+
+    fn line(&mut self, offset: usize, depth: usize, label: &str, value: std::fmt::Arguments<'_>) {
+        let _ = writeln!(
+            self.out,
+            "{:08X}  {:indent$}{}{}",
+            offset,
+            "",
+            label,
+            value,
+            indent = depth * 2
+        );
+    }
+
+    fn header(&mut self) -> Result<()> {
+        let is_header = self
+            .data
+            .get(..4)
+            .is_some_and(|ty| ty == TypeMap::Header.to_u32().to_le_bytes());
+        if is_header {
+            self.offset += 4;
+            let hash = u64::from_le_bytes(self.take_array()?);
+            self.line(0, 0, "", format_args!("Header(schema {:016X})", hash));
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, depth: usize, label: &str) -> Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ErrorCode::Custom(format!(
+                "maximum depth of {} exceeded",
+                MAX_DEPTH
+            ))));
+        }
+        let offset = self.offset;
+        match self.read_type()? {
+            TypeMap::U8 => {
+                let v = u8::from_le_bytes(self.take_array()?);
+                self.line(offset, depth, label, format_args!("U8 {}", v));
+            }
+            TypeMap::U16 => {
+                let v = u16::from_le_bytes(self.take_array()?);
+                self.line(offset, depth, label, format_args!("U16 {}", v));
+            }
+            TypeMap::U32 => {
+                let v = u32::from_le_bytes(self.take_array()?);
+                self.line(offset, depth, label, format_args!("U32 {}", v));
+            }
+            TypeMap::U64 => {
+                let v = u64::from_le_bytes(self.take_array()?);
+                self.line(offset, depth, label, format_args!("U64 {}", v));
+            }
+            TypeMap::I8 => {
+                let v = i8::from_le_bytes(self.take_array()?);
+                self.line(offset, depth, label, format_args!("I8 {}", v));
+            }
+            TypeMap::I16 => {
+                let v = i16::from_le_bytes(self.take_array()?);
+                self.line(offset, depth, label, format_args!("I16 {}", v));
+            }
+            TypeMap::I32 => {
+                let v = i32::from_le_bytes(self.take_array()?);
+                self.line(offset, depth, label, format_args!("I32 {}", v));
+            }
+            TypeMap::F32 => {
+                let v = f32::from_le_bytes(self.take_array()?);
+                self.line(offset, depth, label, format_args!("F32 {:?}", v));
+            }
+            TypeMap::BoolTrue => self.line(offset, depth, label, format_args!("Bool true")),
+            TypeMap::BoolFalse => self.line(offset, depth, label, format_args!("Bool false")),
+            TypeMap::None => self.line(offset, depth, label, format_args!("None")),
+            TypeMap::Some => {
+                self.line(offset, depth, label, format_args!("Some"));
+                self.value(depth + 1, "")?;
+            }
+            TypeMap::Str => {
+                let v = self.read_string_raw()?;
+                self.line(offset, depth, label, format_args!("Str {:?}", v));
+            }
+            TypeMap::Bytes => {
+                let len = self.read_usize()?;
+                let buf = self.take(len)?;
+                let mut hex = String::new();
+                for b in buf.iter().take(MAX_BYTES) {
+                    let _ = write!(hex, " {:02X}", b);
+                }
+                if len > MAX_BYTES {
+                    hex.push_str(" ...");
+                }
+                self.line(offset, depth, label, format_args!("Bytes({}){}", len, hex));
+            }
+            TypeMap::Seq => {
+                let len = self.read_usize()?;
+                self.line(offset, depth, label, format_args!("Seq({})", len));
+                for index in 0..len {
+                    self.value(depth + 1, &format!("[{}]: ", index))?;
+                }
+            }
+            TypeMap::Struct => {
+                let len = self.read_usize()?;
+                self.line(offset, depth, label, format_args!("Struct({})", len));
+                for _ in 0..len {
+                    let key_offset = self.offset;
+                    let ty = self.read_type()?;
+                    if ty != TypeMap::Str {
+                        self.offset = key_offset;
+                        return Err(Error::unexpected_type(TypeMap::Str, ty));
+                    }
+                    let key = self.read_string_raw()?;
+                    self.value(depth + 1, &format!("{}: ", key))?;
+                }
+            }
+            TypeMap::Map => {
+                let len = self.read_usize()?;
+                self.line(offset, depth, label, format_args!("Map({})", len));
+                for _ in 0..len {
+                    self.value(depth + 1, "key: ")?;
+                    self.value(depth + 2, "value: ")?;
+                }
+            }
+            TypeMap::EnumUnit => {
+                let index = self.read_u32()?;
+                self.line(offset, depth, label, format_args!("EnumUnit({})", index));
+            }
+            TypeMap::EnumNewType => {
+                let index = self.read_u32()?;
+                self.line(offset, depth, label, format_args!("EnumNewType({})", index));
+                self.value(depth + 1, "")?;
+            }
+            // the header is only valid at the start
+            TypeMap::Header => return Err(Error::unexpected_type("value", TypeMap::Header)),
+        }
+        Ok(())
+    }
+}
+
+/// Dump an exchange buffer as an indented tree of values, one per line,
+/// prefixed by the offset of each value. A schema header is also printed.
+///
+/// On error, the tree up to the error is kept, and a final line with the
+/// offset of the error is appended, so partial output can still be inspected.
+pub fn dump(data: &[u8], out: &mut String) -> Result<()> {
+    let mut dumper = Dumper {
+        data,
+        offset: 0,
+        out,
+    };
+    let result = dumper
+        .header()
+        .and_then(|()| dumper.value(0, ""))
+        .and_then(|()| {
+            let trailing = dumper.data.len() - dumper.offset;
+            if trailing > 0 {
+                Err(Error::new(ErrorCode::TrailingData(trailing)))
+            } else {
+                Ok(())
+            }
+        });
+    if let Err(e) = &result {
+        let _ = writeln!(dumper.out, "{:08X}  error: {}", dumper.offset, e);
+    }
+    result
+}
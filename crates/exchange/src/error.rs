@@ -25,9 +25,17 @@ pub enum ErrorCode {
     },
     InvalidUtf8(std::string::FromUtf8Error),
     InvalidVariant,
+    /// Data remained after the value was read.
+    TrailingData(usize),
+    /// The schema hash in the header doesn't match the expected schema hash,
+    /// i.e. the data was written by an incompatible version.
+    SchemaMismatch {
+        expected: u64,
+        actual: u64,
+    },
 
     // --- Writers ---
-    /// A sequence must have a length to be serialized.
+    /// A sequence or map must have a length to be serialized.
     SequenceMustHaveLength,
 }
 
@@ -45,6 +53,12 @@ impl fmt::Display for ErrorCode {
             }
             ErrorCode::InvalidUtf8(e) => fmt::Display::fmt(e, f),
             ErrorCode::InvalidVariant => f.write_str("invalid variant"),
+            ErrorCode::TrailingData(len) => write!(f, "{} bytes of trailing data", len),
+            ErrorCode::SchemaMismatch { expected, actual } => write!(
+                f,
+                "schema mismatch: expected {:016X}, found {:016X} (incompatible versions?)",
+                expected, actual
+            ),
             // Writers
             ErrorCode::SequenceMustHaveLength => f.write_str("sequence must have a known length"),
         }
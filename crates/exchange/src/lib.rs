@@ -1,8 +1,10 @@
 mod constants;
 mod de;
+mod dump;
 mod error;
 mod ser;
 
-pub use de::{from_reader, from_slice};
+pub use de::{from_reader, from_reader_with_schema, from_slice, from_slice_with_schema};
+pub use dump::dump;
 pub use error::{Error, ErrorCode, Result};
-pub use ser::{to_vec, to_writer};
+pub use ser::{to_vec, to_vec_with_schema, to_writer, to_writer_with_schema};
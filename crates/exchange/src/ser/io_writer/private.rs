@@ -104,6 +104,19 @@ impl<W: Write> IoWriter<W> {
         self.write_usize(len)
     }
 
+    #[inline]
+    pub fn write_map_unsized(&mut self, len: Option<usize>) -> Result<()> {
+        let len = len.ok_or_else(|| Error::new(ErrorCode::SequenceMustHaveLength))?;
+        self.write_type(TypeMap::Map)?;
+        self.write_usize(len)
+    }
+
+    #[inline]
+    pub fn write_header(&mut self, schema_hash: u64) -> Result<()> {
+        self.write_type(TypeMap::Header)?;
+        self.write_all(&schema_hash.to_le_bytes())
+    }
+
     #[inline]
     pub fn write_struct(&mut self, len: usize) -> Result<()> {
         self.write_type(TypeMap::Struct)?;
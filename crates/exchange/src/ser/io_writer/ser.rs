@@ -119,8 +119,9 @@ impl<W: Write> ser::Serializer for &mut IoWriter<W> {
     }
 
     #[inline]
-    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        err_unsupported!()
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        self.write_map_unsized(len)?;
+        Ok(self)
     }
 
     #[inline]
@@ -284,28 +285,19 @@ impl<W: Write> ser::SerializeMap for &mut IoWriter<W> {
     type Error = Error;
 
     #[inline]
-    fn serialize_key<T>(&mut self, _key: &T) -> Result<()>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        err_unsupported!()
+        key.serialize(&mut **self)
     }
 
     #[inline]
-    fn serialize_value<T>(&mut self, _value: &T) -> Result<()>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        err_unsupported!()
-    }
-
-    #[inline]
-    fn serialize_entry<K, V>(&mut self, _key: &K, _value: &V) -> Result<()>
-    where
-        K: ?Sized + Serialize,
-        V: ?Sized + Serialize,
-    {
-        err_unsupported!()
+        value.serialize(&mut **self)
     }
 
     #[inline]
@@ -27,3 +27,32 @@ where
     let _ = serializer.finish()?;
     Ok(())
 }
+
+/// Serialize a value to binary exchange data, with a schema header.
+///
+/// The schema hash allows readers to detect data written by an incompatible
+/// version, see [`from_slice_with_schema`](crate::from_slice_with_schema).
+pub fn to_vec_with_schema<T>(value: &T, schema_hash: u64) -> Result<Vec<u8>>
+where
+    T: ?Sized + serde::Serialize,
+{
+    let writer = std::io::Cursor::new(Vec::new());
+    let mut serializer = io_writer::IoWriter::new(writer);
+    serializer.write_header(schema_hash)?;
+    value.serialize(&mut serializer)?;
+    let cursor = serializer.finish()?;
+    Ok(cursor.into_inner())
+}
+
+/// Serialize a value to binary exchange data, with a schema header.
+pub fn to_writer_with_schema<W, T>(writer: W, value: &T, schema_hash: u64) -> Result<()>
+where
+    T: ?Sized + serde::Serialize,
+    W: std::io::Write,
+{
+    let mut serializer = io_writer::IoWriter::new(writer);
+    serializer.write_header(schema_hash)?;
+    value.serialize(&mut serializer)?;
+    let _ = serializer.finish()?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use super::{Bytes, assert_matches};
+use mech3ax_exchange::{ErrorCode, dump, to_vec, to_vec_with_schema};
+use std::collections::BTreeMap;
+
+#[derive(serde::Serialize)]
+enum Kind {
+    Unit,
+    NewType(i16),
+}
+
+#[derive(serde::Serialize)]
+struct Inner {
+    flag: bool,
+    data: Bytes,
+}
+
+#[derive(serde::Serialize)]
+struct Outer {
+    name: String,
+    value: Option<f32>,
+    missing: Option<u8>,
+    items: Vec<Inner>,
+    kinds: Vec<Kind>,
+}
+
+#[test]
+fn dump_tree() {
+    let value = Outer {
+        name: "foo".to_string(),
+        value: Some(1.5),
+        missing: None,
+        items: vec![Inner {
+            flag: true,
+            data: Bytes(vec![0xDE, 0xAD]),
+        }],
+        kinds: vec![Kind::Unit, Kind::NewType(-1)],
+    };
+    let data = to_vec(&value).unwrap();
+    let mut out = String::new();
+    dump(&data, &mut out).unwrap();
+
+    let expected = r#"00000000  Struct(5)
+0000001C    name: Str "foo"
+0000003C    value: Some
+00000040      F32 1.5
+0000005B    missing: None
+00000070    items: Seq(1)
+0000007C      [0]: Struct(2)
+00000098        flag: Bool true
+000000AC        data: Bytes(2) DE AD
+000000CB    kinds: Seq(2)
+000000D7      [0]: EnumUnit(0)
+000000DF      [1]: EnumNewType(1)
+000000E7        I16 -1
+"#;
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn dump_keeps_partial_output() {
+    let mut data = to_vec(&vec![1u32, 2u32]).unwrap();
+    data.truncate(data.len() - 2);
+    let mut out = String::new();
+    let err = dump(&data, &mut out).unwrap_err();
+    assert_matches!(err.code(), ErrorCode::IO(_));
+    assert!(out.starts_with("00000000  Seq(2)\n0000000C    [0]: U32 1\n"));
+    assert!(
+        out.ends_with("00000018  error: unexpected end of file\n"),
+        "{}",
+        out
+    );
+}
+
+#[test]
+fn dump_trailing_data() {
+    let mut data = to_vec(&true).unwrap();
+    data.push(0);
+    let mut out = String::new();
+    let err = dump(&data, &mut out).unwrap_err();
+    assert_matches!(err.code(), ErrorCode::TrailingData(1));
+    assert_eq!(
+        out,
+        "00000000  Bool true\n00000004  error: 1 bytes of trailing data\n"
+    );
+}
+
+#[test]
+fn dump_invalid_type() {
+    let data = 99u32.to_le_bytes();
+    let mut out = String::new();
+    let err = dump(&data, &mut out).unwrap_err();
+    assert_matches!(err.code(), ErrorCode::InvalidType);
+}
+
+#[test]
+fn dump_map_and_header() {
+    let value = BTreeMap::from([("a".to_string(), 1u8)]);
+    let data = to_vec_with_schema(&value, 0xABCD).unwrap();
+    let mut out = String::new();
+    dump(&data, &mut out).unwrap();
+
+    let expected = r#"00000000  Header(schema 000000000000ABCD)
+0000000C  Map(1)
+00000018    key: Str "a"
+00000025      value: U8 1
+"#;
+    assert_eq!(out, expected);
+}
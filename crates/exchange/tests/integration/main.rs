@@ -1,5 +1,7 @@
 mod bytes;
+mod dump_tests;
 mod roundtrip_tests;
+mod schema_tests;
 mod ser_tests;
 
 pub(crate) use bytes::Bytes;
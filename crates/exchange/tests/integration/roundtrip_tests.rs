@@ -1,5 +1,6 @@
 use super::Bytes;
 use mech3ax_exchange::{from_slice, to_vec};
+use std::collections::BTreeMap;
 
 macro_rules! round_trip {
     ($type:ty, $value:expr) => {
@@ -100,6 +101,16 @@ fn seq_tests() {
     round_trip!(Vec<bool>, vec![false, true]);
 }
 
+#[test]
+fn map_tests() {
+    round_trip!(BTreeMap<String, u32>, BTreeMap::new());
+    round_trip!(
+        BTreeMap<String, u32>,
+        BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+    );
+    round_trip!(BTreeMap<u8, Vec<bool>>, BTreeMap::from([(0, vec![true])]));
+}
+
 #[test]
 fn field_struct_tests() {
     #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
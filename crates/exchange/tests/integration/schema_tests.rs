@@ -0,0 +1,64 @@
+use super::assert_matches;
+use mech3ax_exchange::{
+    ErrorCode, from_reader, from_reader_with_schema, from_slice, from_slice_with_schema, to_vec,
+    to_vec_with_schema,
+};
+
+const HASH: u64 = 0x0123_4567_89AB_CDEF;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Value {
+    a: u32,
+    b: Vec<String>,
+}
+
+fn value() -> Value {
+    Value {
+        a: 42,
+        b: vec!["foo".to_string()],
+    }
+}
+
+#[test]
+fn header_round_trip() {
+    let data = to_vec_with_schema(&value(), HASH).unwrap();
+    assert_eq!(data.len(), to_vec(&value()).unwrap().len() + 12);
+
+    let actual: Value = from_slice_with_schema(&data, HASH).unwrap();
+    assert_eq!(actual, value());
+    let actual: Value = from_reader_with_schema(data.as_slice(), HASH).unwrap();
+    assert_eq!(actual, value());
+}
+
+#[test]
+fn header_mismatch() {
+    let data = to_vec_with_schema(&value(), HASH).unwrap();
+
+    let err = from_slice_with_schema::<Value>(&data, HASH + 1).unwrap_err();
+    assert_matches!(
+        err.code(),
+        ErrorCode::SchemaMismatch {
+            expected: 0x0123_4567_89AB_CDF0,
+            actual: HASH,
+        }
+    );
+    let err = from_reader_with_schema::<_, Value>(data.as_slice(), HASH + 1).unwrap_err();
+    assert_matches!(err.code(), ErrorCode::SchemaMismatch { .. });
+}
+
+#[test]
+fn header_is_optional() {
+    // data without a header can't be checked
+    let data = to_vec(&value()).unwrap();
+    let actual: Value = from_slice_with_schema(&data, HASH).unwrap();
+    assert_eq!(actual, value());
+    let actual: Value = from_reader_with_schema(data.as_slice(), HASH).unwrap();
+    assert_eq!(actual, value());
+
+    // readers without a schema skip the header
+    let data = to_vec_with_schema(&value(), HASH).unwrap();
+    let actual: Value = from_slice(&data).unwrap();
+    assert_eq!(actual, value());
+    let actual: Value = from_reader(data.as_slice()).unwrap();
+    assert_eq!(actual, value());
+}
@@ -101,7 +101,8 @@ fn tuple_tests() {
 
 #[test]
 fn map_tests() {
-    assert_unsupported!(HashMap<bool, bool>, HashMap::new());
+    assert_supported!(HashMap<bool, bool>, HashMap::new());
+    assert_supported!(HashMap<String, u32>, HashMap::from([("a".to_string(), 1)]));
 }
 
 #[test]
@@ -0,0 +1,449 @@
+//! Recompute node and model bounds after geometry or transforms were edited.
+//!
+//! The engine's algorithm isn't known, so this is a best guess that reports
+//! any differences from the previous (e.g. vanilla) values:
+//!
+//! * A model's midpoint and diagonal are the center and half diagonal of its
+//!   vertices' bounds, using the same approximate square root as
+//!   `partition_diag`.
+//! * A node's model bounding box is its model's vertex bounds, transformed into
+//!   world space by the node's and its parents' `Object3d` transforms. Nodes
+//!   with several parents use the first parent reached, which is logged.
+//! * A node's child bounding box is the union of its children's node bounding
+//!   boxes, and its node bounding box is the union of the other two.
+//!
+//! Only bounding boxes indicated by the node flags are recomputed, and only
+//! for object 3D and LOD nodes. Other nodes keep their bounding boxes, which
+//! are still used for their parents.
+use crate::nodes::math::approx_sqrt;
+use crate::nodes::object3d::math::{combine, node_matrix, transform_point};
+use log::warn;
+use mech3ax_api_types::gamez::model::Model;
+use mech3ax_api_types::gamez::nodes::{BoundingBox, Node, NodeData, NodeFlags};
+use mech3ax_api_types::gamez::{GameZ, MechlibModel};
+use mech3ax_api_types::{AffineMatrix, Vec3};
+use mech3ax_common::{Result, err};
+use std::fmt;
+
+/// Which of a node's bounding boxes differs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeBbox {
+    Node,
+    Model,
+    Child,
+}
+
+/// A recomputed value that differs from the previous value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoundsDiff {
+    Node {
+        index: usize,
+        name: String,
+        bbox: NodeBbox,
+        previous: BoundingBox,
+        recomputed: BoundingBox,
+    },
+    ModelMid {
+        index: usize,
+        previous: Vec3,
+        recomputed: Vec3,
+    },
+    ModelDiag {
+        index: usize,
+        previous: f32,
+        recomputed: f32,
+    },
+}
+
+impl fmt::Display for BoundsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Node {
+                index,
+                name,
+                bbox,
+                previous,
+                recomputed,
+            } => write!(
+                f,
+                "node {} `{}` {:?} bbox: {:?} -> {:?}",
+                index, name, bbox, previous, recomputed
+            ),
+            Self::ModelMid {
+                index,
+                previous,
+                recomputed,
+            } => write!(f, "model {} mid: {:?} -> {:?}", index, previous, recomputed),
+            Self::ModelDiag {
+                index,
+                previous,
+                recomputed,
+            } => write!(
+                f,
+                "model {} diag: {:?} -> {:?}",
+                index, previous, recomputed
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min: Vec3,
+    max: Vec3,
+}
+
+impl Bounds {
+    fn from_points(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Self {
+            min: first,
+            max: first,
+        };
+        for point in points {
+            bounds.add(point);
+        }
+        Some(bounds)
+    }
+
+    fn from_bbox(bbox: &BoundingBox) -> Self {
+        // cannot fail, since there are two points
+        Self::from_points([bbox.a, bbox.b]).unwrap()
+    }
+
+    fn add(&mut self, point: Vec3) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.min.z = self.min.z.min(point.z);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+        self.max.z = self.max.z.max(point.z);
+    }
+
+    fn union(self, other: Self) -> Self {
+        let mut bounds = self;
+        bounds.add(other.min);
+        bounds.add(other.max);
+        bounds
+    }
+
+    fn corners(&self) -> [Vec3; 8] {
+        let Self { min, max } = *self;
+        [
+            Vec3 {
+                x: min.x,
+                y: min.y,
+                z: min.z,
+            },
+            Vec3 {
+                x: max.x,
+                y: min.y,
+                z: min.z,
+            },
+            Vec3 {
+                x: min.x,
+                y: max.y,
+                z: min.z,
+            },
+            Vec3 {
+                x: max.x,
+                y: max.y,
+                z: min.z,
+            },
+            Vec3 {
+                x: min.x,
+                y: min.y,
+                z: max.z,
+            },
+            Vec3 {
+                x: max.x,
+                y: min.y,
+                z: max.z,
+            },
+            Vec3 {
+                x: min.x,
+                y: max.y,
+                z: max.z,
+            },
+            Vec3 {
+                x: max.x,
+                y: max.y,
+                z: max.z,
+            },
+        ]
+    }
+
+    fn transform(&self, m: &AffineMatrix) -> Self {
+        // cannot fail, since there are eight corners
+        Self::from_points(self.corners().map(|p| transform_point(m, p))).unwrap()
+    }
+
+    fn to_bbox(self) -> BoundingBox {
+        BoundingBox {
+            a: self.min,
+            b: self.max,
+        }
+    }
+}
+
+fn model_bounds(model: &Model) -> Option<Bounds> {
+    Bounds::from_points(model.vertices.iter().copied())
+}
+
+fn model_mid_diag(bounds: Option<Bounds>) -> (Vec3, f32) {
+    let Some(Bounds { min, max }) = bounds else {
+        return (Vec3::DEFAULT, 0.0);
+    };
+    let mid = Vec3 {
+        x: (min.x + max.x) * 0.5,
+        y: (min.y + max.y) * 0.5,
+        z: (min.z + max.z) * 0.5,
+    };
+    // must perform this calculation with doubles to avoid loss of precision
+    let hx = (max.x as f64 - min.x as f64) * 0.5;
+    let hy = (max.y as f64 - min.y as f64) * 0.5;
+    let hz = (max.z as f64 - min.z as f64) * 0.5;
+    let diag = approx_sqrt((hx * hx + hy * hy + hz * hz) as f32);
+    (mid, diag)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    New,
+    Active,
+    Done,
+}
+
+struct Pass<'a> {
+    nodes: &'a [Node],
+    models: &'a [Model],
+    world: Vec<Option<AffineMatrix>>,
+    visit: Vec<Visit>,
+    recomputed: Vec<Option<(BoundingBox, BoundingBox, BoundingBox)>>,
+}
+
+impl Pass<'_> {
+    fn child(&self, parent: usize, child: usize) -> Result<usize> {
+        if child < self.nodes.len() {
+            Ok(child)
+        } else {
+            Err(err!(
+                "Expected node {} child index {} < {}",
+                parent,
+                child,
+                self.nodes.len()
+            ))
+        }
+    }
+
+    fn world_matrices(&mut self, index: usize, parent: &AffineMatrix) -> Result<()> {
+        if self.world[index].is_some() {
+            // reached via another parent; the first one wins
+            warn!(
+                "WARN: node {} `{}` has several parents, using the transform of the first one",
+                index, self.nodes[index].name
+            );
+            return Ok(());
+        }
+        let node = &self.nodes[index];
+        let world = match node_matrix(node) {
+            Some(local) => combine(&local, parent),
+            None => *parent,
+        };
+        // set before the children, so cycles terminate
+        self.world[index] = Some(world);
+        for child in &node.child_indices {
+            let child = self.child(index, child.to_usize())?;
+            self.world_matrices(child, &world)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the node bounding box used by the parent, if any.
+    fn node_bounds(&mut self, index: usize) -> Result<Option<Bounds>> {
+        match self.visit[index] {
+            Visit::Done => return Ok(self.effective(index)),
+            Visit::Active => {
+                return Err(err!("Expected node {} to not be its own ancestor", index));
+            }
+            Visit::New => {}
+        }
+        self.visit[index] = Visit::Active;
+
+        let node = &self.nodes[index];
+        let mut child_bounds: Option<Bounds> = None;
+        for child in &node.child_indices {
+            let child = self.child(index, child.to_usize())?;
+            if let Some(bounds) = self.node_bounds(child)? {
+                child_bounds = Some(match child_bounds {
+                    Some(acc) => acc.union(bounds),
+                    None => bounds,
+                });
+            }
+        }
+
+        let recompute = matches!(node.data, NodeData::Object3d(_) | NodeData::Lod(_));
+        if recompute {
+            let world = self.world[index].unwrap_or(AffineMatrix::IDENTITY);
+            let model_bounds = node
+                .model_index
+                .to_usize()
+                .and_then(|i| self.models.get(i))
+                .and_then(model_bounds)
+                .map(|bounds| bounds.transform(&world));
+
+            let model_bbox = if node.flags.contains(NodeFlags::BBOX_MODEL) {
+                model_bounds.map_or(BoundingBox::EMPTY, Bounds::to_bbox)
+            } else {
+                node.model_bbox
+            };
+            let child_bbox = if node.flags.contains(NodeFlags::BBOX_CHILD) {
+                child_bounds.map_or(BoundingBox::EMPTY, Bounds::to_bbox)
+            } else {
+                node.child_bbox
+            };
+            let node_bbox = if node.flags.contains(NodeFlags::BBOX_NODE) {
+                let bounds = match (model_bounds, child_bounds) {
+                    (Some(m), Some(c)) => Some(m.union(c)),
+                    (m, c) => m.or(c),
+                };
+                bounds.map_or(BoundingBox::EMPTY, Bounds::to_bbox)
+            } else {
+                node.node_bbox
+            };
+            self.recomputed[index] = Some((node_bbox, model_bbox, child_bbox));
+        }
+
+        self.visit[index] = Visit::Done;
+        Ok(self.effective(index))
+    }
+
+    fn effective(&self, index: usize) -> Option<Bounds> {
+        let node = &self.nodes[index];
+        if !node.flags.contains(NodeFlags::BBOX_NODE) {
+            return None;
+        }
+        let bbox = match &self.recomputed[index] {
+            Some((node_bbox, _, _)) => node_bbox,
+            None => &node.node_bbox,
+        };
+        if *bbox == BoundingBox::EMPTY {
+            None
+        } else {
+            Some(Bounds::from_bbox(bbox))
+        }
+    }
+}
+
+fn diff_bbox(
+    diffs: &mut Vec<BoundsDiff>,
+    index: usize,
+    name: &str,
+    bbox: NodeBbox,
+    field: &mut BoundingBox,
+    recomputed: BoundingBox,
+) {
+    if *field != recomputed {
+        diffs.push(BoundsDiff::Node {
+            index,
+            name: name.to_string(),
+            bbox,
+            previous: *field,
+            recomputed,
+        });
+        *field = recomputed;
+    }
+}
+
+/// Recompute the node and model bounds, returning where the recomputed
+/// values differ from the previous values.
+pub fn recompute_bounds(nodes: &mut [Node], models: &mut [Model]) -> Result<Vec<BoundsDiff>> {
+    let mut diffs = Vec::new();
+
+    for (index, model) in models.iter_mut().enumerate() {
+        let (mid, diag) = model_mid_diag(model_bounds(model));
+        if model.bbox_mid != mid {
+            diffs.push(BoundsDiff::ModelMid {
+                index,
+                previous: model.bbox_mid,
+                recomputed: mid,
+            });
+            model.bbox_mid = mid;
+        }
+        if model.bbox_diag != diag {
+            diffs.push(BoundsDiff::ModelDiag {
+                index,
+                previous: model.bbox_diag,
+                recomputed: diag,
+            });
+            model.bbox_diag = diag;
+        }
+    }
+
+    let count = nodes.len();
+    let mut pass = Pass {
+        nodes,
+        models,
+        world: vec![None; count],
+        visit: vec![Visit::New; count],
+        recomputed: vec![None; count],
+    };
+
+    // roots first, so that nodes are transformed by their parents
+    for index in 0..count {
+        if pass.nodes[index].parent_indices.is_empty() {
+            pass.world_matrices(index, &AffineMatrix::IDENTITY)?;
+        }
+    }
+    for index in 0..count {
+        pass.node_bounds(index)?;
+    }
+
+    let recomputed = pass.recomputed;
+    for (index, (node, recomputed)) in nodes.iter_mut().zip(recomputed).enumerate() {
+        let Some((node_bbox, model_bbox, child_bbox)) = recomputed else {
+            continue;
+        };
+        let name = node.name.clone();
+        diff_bbox(
+            &mut diffs,
+            index,
+            &name,
+            NodeBbox::Node,
+            &mut node.node_bbox,
+            node_bbox,
+        );
+        diff_bbox(
+            &mut diffs,
+            index,
+            &name,
+            NodeBbox::Model,
+            &mut node.model_bbox,
+            model_bbox,
+        );
+        diff_bbox(
+            &mut diffs,
+            index,
+            &name,
+            NodeBbox::Child,
+            &mut node.child_bbox,
+            child_bbox,
+        );
+    }
+
+    Ok(diffs)
+}
+
+/// Recompute the bounds of a GameZ, see [`recompute_bounds`].
+pub fn recompute_gamez_bounds(gamez: &mut GameZ) -> Result<Vec<BoundsDiff>> {
+    recompute_bounds(&mut gamez.nodes, &mut gamez.models)
+}
+
+/// Recompute the bounds of a mechlib model, see [`recompute_bounds`].
+pub fn recompute_mechlib_bounds(model: &mut MechlibModel) -> Result<Vec<BoundsDiff>> {
+    recompute_bounds(&mut model.nodes, &mut model.models)
+}
+
+#[cfg(test)]
+mod tests;
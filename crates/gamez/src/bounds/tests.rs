@@ -0,0 +1,192 @@
+use super::*;
+use mech3ax_api_types::gamez::model::{FacadeMode, ModelFlags, ModelType, UvCoord};
+use mech3ax_api_types::gamez::nodes::{
+    ActiveBoundingBox, Object3d, RotateTranslateScale, Transform,
+};
+use mech3ax_api_types::{IndexO, IndexR};
+
+fn v(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+fn bbox(a: Vec3, b: Vec3) -> BoundingBox {
+    BoundingBox { a, b }
+}
+
+fn model(vertices: Vec<Vec3>) -> Model {
+    Model {
+        model_type: ModelType::Default,
+        facade_mode: FacadeMode::CylindricalY,
+        flags: ModelFlags::empty(),
+        parent_count: 1,
+        vertices,
+        normals: Vec::new(),
+        morphs: Vec::new(),
+        lights: Vec::new(),
+        polygons: Vec::new(),
+        texture_scroll: UvCoord { u: 0.0, v: 0.0 },
+        bbox_mid: Vec3::DEFAULT,
+        bbox_diag: 0.0,
+        polygons_ptr: 0,
+        vertices_ptr: 0,
+        normals_ptr: 0,
+        lights_ptr: 0,
+        morphs_ptr: 0,
+        material_refs_ptr: 0,
+    }
+}
+
+fn object3d(name: &str, transform: Transform) -> Node {
+    let data = NodeData::Object3d(Object3d {
+        opacity: None,
+        color: None,
+        unk: 0.0,
+        transform,
+        signs: 0,
+    });
+    Node {
+        name: name.to_string(),
+        flags: NodeFlags::empty(),
+        update_flags: 0,
+        zone_id: 0,
+        model_index: IndexO::NONE,
+        area_partition: None,
+        virtual_partition: None,
+        parent_indices: Vec::new(),
+        child_indices: Vec::new(),
+        active_bbox: ActiveBoundingBox::Node,
+        node_bbox: BoundingBox::EMPTY,
+        model_bbox: BoundingBox::EMPTY,
+        child_bbox: BoundingBox::EMPTY,
+        field192: 0,
+        field196: 0,
+        field200: 0,
+        field204: 0,
+        data,
+        data_ptr: 0,
+        parent_array_ptr: 0,
+        child_array_ptr: 0,
+        index: 0,
+    }
+}
+
+fn translate(x: f32, y: f32, z: f32) -> Transform {
+    Transform::RotateTranslateScale(RotateTranslateScale {
+        rotate: Vec3::DEFAULT,
+        translate: v(x, y, z),
+        scale: v(1.0, 1.0, 1.0),
+        original: None,
+    })
+}
+
+fn idx(index: usize) -> IndexR {
+    IndexR::from_usize(index).unwrap()
+}
+
+fn link(nodes: &mut [Node], parent: usize, child: usize) {
+    nodes[parent].child_indices.push(idx(child));
+    nodes[child].parent_indices.push(idx(parent));
+}
+
+/// A root translated by 10 in x, with a child that has a model.
+fn hierarchy() -> (Vec<Node>, Vec<Model>) {
+    let mut nodes = vec![
+        object3d("root", translate(10.0, 0.0, 0.0)),
+        object3d("child", Transform::Initial),
+    ];
+    nodes[0].flags = NodeFlags::BBOX_NODE | NodeFlags::BBOX_CHILD;
+    nodes[1].flags = NodeFlags::BBOX_NODE | NodeFlags::BBOX_MODEL;
+    nodes[1].model_index = IndexO::from_usize(0).unwrap();
+    link(&mut nodes, 0, 1);
+    let models = vec![model(vec![v(-1.0, -2.0, -3.0), v(3.0, 2.0, 1.0)])];
+    (nodes, models)
+}
+
+#[test]
+fn model_mid_and_diag() {
+    let (mut nodes, mut models) = hierarchy();
+    recompute_bounds(&mut nodes, &mut models).unwrap();
+
+    assert_eq!(models[0].bbox_mid, v(1.0, 0.0, -1.0));
+    // half sides of 2.0, so sqrt(12.0), approximately
+    assert_eq!(models[0].bbox_diag, approx_sqrt(12.0));
+    assert!((models[0].bbox_diag - 12.0f32.sqrt()).abs() < 0.25);
+}
+
+#[test]
+fn node_bboxes_are_transformed() {
+    let (mut nodes, mut models) = hierarchy();
+    recompute_bounds(&mut nodes, &mut models).unwrap();
+
+    let expected = bbox(v(9.0, -2.0, -3.0), v(13.0, 2.0, 1.0));
+    assert_eq!(nodes[1].model_bbox, expected);
+    assert_eq!(nodes[1].node_bbox, expected);
+    // no child flag
+    assert_eq!(nodes[1].child_bbox, BoundingBox::EMPTY);
+
+    assert_eq!(nodes[0].child_bbox, expected);
+    assert_eq!(nodes[0].node_bbox, expected);
+    // no model flag
+    assert_eq!(nodes[0].model_bbox, BoundingBox::EMPTY);
+}
+
+#[test]
+fn differences_are_reported() {
+    let (mut nodes, mut models) = hierarchy();
+    let diffs = recompute_bounds(&mut nodes, &mut models).unwrap();
+    // model mid, model diag, child node + model, root node + child
+    assert_eq!(diffs.len(), 6);
+    assert!(diffs.contains(&BoundsDiff::Node {
+        index: 1,
+        name: "child".to_string(),
+        bbox: NodeBbox::Model,
+        previous: BoundingBox::EMPTY,
+        recomputed: bbox(v(9.0, -2.0, -3.0), v(13.0, 2.0, 1.0)),
+    }));
+    assert_eq!(
+        diffs[0].to_string(),
+        "model 0 mid: Vec3 { x: 0.0, y: 0.0, z: 0.0 } -> Vec3 { x: 1.0, y: 0.0, z: -1.0 }"
+    );
+
+    // recomputing again is stable
+    let diffs = recompute_bounds(&mut nodes, &mut models).unwrap();
+    assert_eq!(diffs, vec![]);
+}
+
+#[test]
+fn unflagged_bboxes_are_kept() {
+    let (mut nodes, mut models) = hierarchy();
+    let vanilla = bbox(v(1.0, 1.0, 1.0), v(2.0, 2.0, 2.0));
+    nodes[1].flags = NodeFlags::empty();
+    nodes[1].model_bbox = vanilla;
+    recompute_bounds(&mut nodes, &mut models).unwrap();
+
+    assert_eq!(nodes[1].model_bbox, vanilla);
+    // the child doesn't have a node bounding box
+    assert_eq!(nodes[0].child_bbox, BoundingBox::EMPTY);
+}
+
+#[test]
+fn several_parents_use_the_first() {
+    let (mut nodes, mut models) = hierarchy();
+    nodes.push(object3d("other", translate(100.0, 0.0, 0.0)));
+    link(&mut nodes, 2, 1);
+    recompute_bounds(&mut nodes, &mut models).unwrap();
+
+    let expected = bbox(v(9.0, -2.0, -3.0), v(13.0, 2.0, 1.0));
+    assert_eq!(nodes[1].model_bbox, expected);
+}
+
+#[test]
+fn invalid_child_index_fails() {
+    let (mut nodes, mut models) = hierarchy();
+    nodes[1].child_indices.push(idx(5));
+    assert!(recompute_bounds(&mut nodes, &mut models).is_err());
+}
+
+#[test]
+fn cycle_fails() {
+    let (mut nodes, mut models) = hierarchy();
+    link(&mut nodes, 1, 0);
+    assert!(recompute_bounds(&mut nodes, &mut models).is_err());
+}
@@ -0,0 +1,181 @@
+//! Find and remove textures, materials, and models that aren't used.
+//!
+//! Use is transitive: models are used by nodes, materials by the polygons of
+//! used models, and textures by used materials (including their texture
+//! cycles), or as the mip texture of a used texture. Other files may still
+//! refer to entries by index or name (e.g. animations), which isn't checked.
+use mech3ax_api_types::gamez::GameZ;
+use mech3ax_api_types::gamez::materials::Material;
+use mech3ax_api_types::{IndexO, IndexR};
+use mech3ax_common::{Result, err};
+
+/// The indices of unused entries, in ascending order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Unused {
+    pub textures: Vec<usize>,
+    pub materials: Vec<usize>,
+    pub models: Vec<usize>,
+}
+
+impl Unused {
+    pub fn is_empty(&self) -> bool {
+        self.textures.is_empty() && self.materials.is_empty() && self.models.is_empty()
+    }
+}
+
+fn mark(used: &mut [bool], index: usize, what: &str) -> Result<bool> {
+    let len = used.len();
+    let Some(entry) = used.get_mut(index) else {
+        return Err(err!("Expected {} index {} < {}", what, index, len));
+    };
+    let first = !*entry;
+    *entry = true;
+    Ok(first)
+}
+
+fn unused(used: &[bool]) -> Vec<usize> {
+    used.iter()
+        .enumerate()
+        .filter_map(|(index, used)| (!used).then_some(index))
+        .collect()
+}
+
+/// Find textures, materials, and models that aren't used.
+pub fn find_unused(gamez: &GameZ) -> Result<Unused> {
+    let mut models = vec![false; gamez.models.len()];
+    for node in &gamez.nodes {
+        if let Some(index) = node.model_index.to_usize() {
+            mark(&mut models, index, "model")?;
+        }
+    }
+
+    let mut materials = vec![false; gamez.materials.len()];
+    let used_models = gamez.models.iter().zip(&models).filter(|(_, used)| **used);
+    for (model, _) in used_models {
+        for material in model.polygons.iter().flat_map(|p| &p.materials) {
+            mark(
+                &mut materials,
+                material.material_index.to_usize(),
+                "material",
+            )?;
+        }
+    }
+
+    let mut textures = vec![false; gamez.textures.len()];
+    let mut pending = Vec::new();
+    let used_materials = gamez.materials.iter().zip(&materials).filter(|(_, u)| **u);
+    for (material, _) in used_materials {
+        if let Material::Textured(textured) = material {
+            let cycle = textured.cycle.iter().flat_map(|c| &c.texture_indices);
+            for index in std::iter::once(&textured.texture_index).chain(cycle) {
+                let index = index.to_usize();
+                if mark(&mut textures, index, "texture")? {
+                    pending.push(index);
+                }
+            }
+        }
+    }
+    while let Some(index) = pending.pop() {
+        if let Some(mip_index) = gamez.textures[index].mip_index.to_usize()
+            && mark(&mut textures, mip_index, "texture")?
+        {
+            pending.push(mip_index);
+        }
+    }
+
+    Ok(Unused {
+        textures: unused(&textures),
+        materials: unused(&materials),
+        models: unused(&models),
+    })
+}
+
+/// Maps old indices to new indices, after the unused entries are removed.
+fn index_map(len: usize, unused: &[usize]) -> Vec<Option<usize>> {
+    let mut map = vec![None; len];
+    let mut unused = unused.iter().peekable();
+    let mut next = 0;
+    for (index, entry) in map.iter_mut().enumerate() {
+        if unused.next_if_eq(&&index).is_none() {
+            *entry = Some(next);
+            next += 1;
+        }
+    }
+    map
+}
+
+fn remap_r(index: &mut IndexR, map: &[Option<usize>], what: &str) -> Result<()> {
+    let Some(new) = map[index.to_usize()].and_then(IndexR::from_usize) else {
+        return Err(err!("Expected {} index {} to be used", what, index));
+    };
+    *index = new;
+    Ok(())
+}
+
+fn remap_o(index: &mut IndexO, map: &[Option<usize>], what: &str) -> Result<()> {
+    if let Some(old) = index.to_usize() {
+        let Some(new) = map[old].and_then(IndexO::from_usize) else {
+            return Err(err!("Expected {} index {} to be used", what, old));
+        };
+        *index = new;
+    }
+    Ok(())
+}
+
+fn retain_used<T>(items: &mut Vec<T>, map: &[Option<usize>]) {
+    let mut index = 0;
+    items.retain(|_| {
+        let keep = map.get(index).is_none_or(Option::is_some);
+        index += 1;
+        keep
+    });
+}
+
+/// Remove textures, materials, and models that aren't used, and remap all
+/// indices. Returns what was removed.
+///
+/// The material and model array sizes are kept. In CS, the texture image
+/// pointers of removed textures are also removed.
+pub fn compact(gamez: &mut GameZ) -> Result<Unused> {
+    let unused = find_unused(gamez)?;
+    if unused.is_empty() {
+        return Ok(unused);
+    }
+
+    let textures = index_map(gamez.textures.len(), &unused.textures);
+    let materials = index_map(gamez.materials.len(), &unused.materials);
+    let models = index_map(gamez.models.len(), &unused.models);
+
+    retain_used(&mut gamez.textures, &textures);
+    if let Some(image_ptrs) = &mut gamez.metadata.image_ptrs {
+        retain_used(image_ptrs, &textures);
+    }
+    retain_used(&mut gamez.materials, &materials);
+    retain_used(&mut gamez.models, &models);
+
+    for texture in &mut gamez.textures {
+        remap_o(&mut texture.mip_index, &textures, "texture")?;
+    }
+    for material in &mut gamez.materials {
+        if let Material::Textured(textured) = material {
+            remap_r(&mut textured.texture_index, &textures, "texture")?;
+            if let Some(cycle) = &mut textured.cycle {
+                for index in &mut cycle.texture_indices {
+                    remap_r(index, &textures, "texture")?;
+                }
+            }
+        }
+    }
+    for model in &mut gamez.models {
+        for material in model.polygons.iter_mut().flat_map(|p| &mut p.materials) {
+            remap_r(&mut material.material_index, &materials, "material")?;
+        }
+    }
+    for node in &mut gamez.nodes {
+        remap_o(&mut node.model_index, &models, "model")?;
+    }
+    Ok(unused)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,201 @@
+use super::*;
+use mech3ax_api_types::gamez::materials::{ColoredMaterial, CycleData, Soil, TexturedMaterial};
+use mech3ax_api_types::gamez::model::{
+    FacadeMode, Model, ModelFlags, ModelType, Polygon, PolygonFlags, PolygonMaterial, UvCoord,
+};
+use mech3ax_api_types::gamez::nodes::{ActiveBoundingBox, BoundingBox, Node, NodeData, NodeFlags};
+use mech3ax_api_types::gamez::{GameZMetadata, Texture};
+use mech3ax_api_types::{Color, Count, Vec3};
+use mech3ax_timestamp::unix::from_timestamp;
+
+fn idx(index: usize) -> IndexR {
+    IndexR::from_usize(index).unwrap()
+}
+
+fn texture(name: &str) -> Texture {
+    Texture {
+        name: name.to_string(),
+        mip_index: IndexO::NONE,
+    }
+}
+
+fn textured(texture_index: usize, cycle: &[usize]) -> Material {
+    let cycle = (!cycle.is_empty()).then(|| CycleData {
+        texture_indices: cycle.iter().copied().map(idx).collect(),
+        looping: true,
+        speed: 1.0,
+        current_frame: 0,
+        cycle_ptr: 1,
+        tex_map_ptr: 1,
+    });
+    Material::Textured(TexturedMaterial {
+        texture_index: idx(texture_index),
+        soil: Soil::Default,
+        cycle,
+        flag: false,
+    })
+}
+
+fn colored() -> Material {
+    Material::Colored(ColoredMaterial {
+        color: Color::BLACK,
+        alpha: 255,
+        soil: Soil::Default,
+    })
+}
+
+fn model(materials: &[usize]) -> Model {
+    let polygons = materials
+        .iter()
+        .map(|&material_index| Polygon {
+            flags: PolygonFlags::empty(),
+            priority: 0,
+            zone_set: Vec::new(),
+            vertex_indices: Vec::new(),
+            normal_indices: None,
+            vertex_colors: Vec::new(),
+            materials: vec![PolygonMaterial {
+                material_index: idx(material_index),
+                uv_coords: None,
+            }],
+            vertex_indices_ptr: 0,
+            normal_indices_ptr: 0,
+            uvs_ptr: 0,
+            vertex_colors_ptr: 0,
+            matl_refs_ptr: 0,
+            materials_ptr: 0,
+        })
+        .collect();
+    Model {
+        model_type: ModelType::Default,
+        facade_mode: FacadeMode::CylindricalY,
+        flags: ModelFlags::empty(),
+        parent_count: 1,
+        vertices: Vec::new(),
+        normals: Vec::new(),
+        morphs: Vec::new(),
+        lights: Vec::new(),
+        polygons,
+        texture_scroll: UvCoord { u: 0.0, v: 0.0 },
+        bbox_mid: Vec3::DEFAULT,
+        bbox_diag: 0.0,
+        polygons_ptr: 0,
+        vertices_ptr: 0,
+        normals_ptr: 0,
+        lights_ptr: 0,
+        morphs_ptr: 0,
+        material_refs_ptr: 0,
+    }
+}
+
+fn node(model_index: Option<usize>) -> Node {
+    Node {
+        name: "node".to_string(),
+        flags: NodeFlags::empty(),
+        update_flags: 0,
+        zone_id: 0,
+        model_index: model_index
+            .and_then(IndexO::from_usize)
+            .unwrap_or(IndexO::NONE),
+        area_partition: None,
+        virtual_partition: None,
+        parent_indices: Vec::new(),
+        child_indices: Vec::new(),
+        active_bbox: ActiveBoundingBox::Node,
+        node_bbox: BoundingBox::EMPTY,
+        model_bbox: BoundingBox::EMPTY,
+        child_bbox: BoundingBox::EMPTY,
+        field192: 0,
+        field196: 0,
+        field200: 0,
+        field204: 0,
+        data: NodeData::Empty,
+        data_ptr: 0,
+        parent_array_ptr: 0,
+        child_array_ptr: 0,
+        index: 0,
+    }
+}
+
+/// Textures: 0 unused, 1 used, 2 only via a cycle, 3 only via a mip, 4 only
+/// by an unused material. Materials: 0 unused, 1 used, 2 only by an unused
+/// model, 3 used. Models: 0 unused, 1 used.
+fn gamez() -> GameZ {
+    let mut textures = vec![
+        texture("unused"),
+        texture("used"),
+        texture("cycle"),
+        texture("mip"),
+        texture("orphan"),
+    ];
+    textures[1].mip_index = IndexO::from_usize(3).unwrap();
+    GameZ {
+        textures,
+        materials: vec![
+            textured(4, &[]),
+            textured(1, &[1, 2]),
+            textured(0, &[]),
+            colored(),
+        ],
+        models: vec![model(&[2]), model(&[1, 3])],
+        nodes: vec![node(None), node(Some(1))],
+        metadata: GameZMetadata {
+            datetime: from_timestamp(0x3A000000),
+            material_array_size: Count::from_i16(8).unwrap(),
+            model_array_size: Count::from_i16(8).unwrap(),
+            node_array_size: Count::from_i16(2).unwrap(),
+            node_last_free: 2,
+            image_ptrs: Some(vec![10, 11, 12, 13, 14]),
+        },
+    }
+}
+
+#[test]
+fn unused_is_transitive() {
+    let unused = find_unused(&gamez()).unwrap();
+    assert_eq!(
+        unused,
+        Unused {
+            textures: vec![0, 4],
+            materials: vec![0, 2],
+            models: vec![0],
+        }
+    );
+}
+
+#[test]
+fn compact_remaps_indices() {
+    let mut gamez = gamez();
+    let removed = compact(&mut gamez).unwrap();
+    assert!(!removed.is_empty());
+
+    let names: Vec<_> = gamez.textures.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["used", "cycle", "mip"]);
+    assert_eq!(gamez.textures[0].mip_index, IndexO::from_usize(2).unwrap());
+    assert_eq!(gamez.metadata.image_ptrs, Some(vec![11, 12, 13]));
+
+    assert_eq!(gamez.materials, vec![textured(0, &[0, 1]), colored()]);
+    assert_eq!(gamez.models.len(), 1);
+    let indices: Vec<_> = gamez.models[0]
+        .polygons
+        .iter()
+        .map(|p| p.materials[0].material_index)
+        .collect();
+    assert_eq!(indices, vec![idx(0), idx(1)]);
+    assert_eq!(gamez.nodes[1].model_index, IndexO::from_usize(0).unwrap());
+    assert_eq!(gamez.metadata.material_array_size.to_usize(), 8);
+
+    // nothing else is unused
+    assert!(find_unused(&gamez).unwrap().is_empty());
+    let before = gamez.clone();
+    assert!(compact(&mut gamez).unwrap().is_empty());
+    assert_eq!(gamez, before);
+}
+
+#[test]
+fn invalid_indices_fail() {
+    let mut gamez = gamez();
+    gamez.nodes[0].model_index = IndexO::from_usize(5).unwrap();
+    assert!(find_unused(&gamez).is_err());
+    assert!(compact(&mut gamez).is_err());
+}
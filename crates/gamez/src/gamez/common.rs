@@ -2,13 +2,12 @@ use bytemuck::{AnyBitPattern, NoUninit};
 use mech3ax_api_types::{Count, Count32};
 use mech3ax_common::{Result, chk, err};
 use mech3ax_types::{Offsets, impl_as_bytes};
-pub(crate) const SIGNATURE: u32 = 0x02971222;
+pub const SIGNATURE: u32 = 0x02971222;
 
-pub(crate) const VERSION_RC: u32 = 15;
-pub(crate) const VERSION_MW: u32 = 27;
-pub(crate) const VERSION_PM: u32 = 41;
-#[expect(dead_code)]
-pub(crate) const VERSION_CS: u32 = 42;
+pub const VERSION_RC: u32 = 15;
+pub const VERSION_MW: u32 = 27;
+pub const VERSION_PM: u32 = 41;
+pub const VERSION_CS: u32 = 42;
 
 pub(crate) fn texture_count(value: Count32) -> Result<Count, String> {
     let v: i32 = value.value;
@@ -0,0 +1,130 @@
+//! CS `gamez.zbd` support.
+//!
+//! As far as it is known, the CS layout matches PM's: the header (apart from
+//! the version), the texture directory, the materials, and the model and node
+//! formats are all read and written with the PM code. The only known
+//! difference is that the texture image pointers can't be derived from the
+//! campaign like in PM, so they are kept in the metadata instead.
+use super::common::{SIGNATURE, VERSION_CS, texture_count};
+use super::pm::{HeaderPmC, models, nodes};
+use crate::materials;
+use crate::textures::pm as textures;
+use mech3ax_api_types::gamez::{GameZ, GameZMetadata};
+use mech3ax_common::io_ext::{CountingReader, CountingWriter};
+use mech3ax_common::{Result, assert_that, chk, len};
+use mech3ax_timestamp::unix::{from_timestamp, to_timestamp};
+use mech3ax_types::{AsBytes as _, Ptr, u32_to_usize};
+use std::io::{Read, Write};
+
+pub fn read_gamez(read: &mut CountingReader<impl Read>) -> Result<GameZ> {
+    let header: HeaderPmC = read.read_struct()?;
+    let offset = read.prev;
+
+    chk!(offset, header.signature == SIGNATURE)?;
+    chk!(offset, header.version == VERSION_CS)?;
+    let datetime = from_timestamp(header.timestamp);
+    let texture_count = chk!(offset, texture_count(header.texture_count))?;
+    chk!(offset, header.textures_offset == HeaderPmC::SIZE)?;
+    chk!(offset, header.materials_offset > header.textures_offset)?;
+    chk!(offset, header.models_offset > header.materials_offset)?;
+    chk!(offset, header.nodes_offset > header.models_offset)?;
+    let node_array_size = chk!(offset, ?header.node_array_size)?;
+    chk!(offset, header.node_last_free <= header.node_array_size)?;
+
+    let textures_offset = u32_to_usize(header.textures_offset);
+    let materials_offset = u32_to_usize(header.materials_offset);
+    let models_offset = u32_to_usize(header.models_offset);
+    let nodes_offset = u32_to_usize(header.nodes_offset);
+
+    assert_that!(
+        "textures offset",
+        read.offset == textures_offset,
+        read.offset
+    )?;
+    // unlike PM, there's no way to derive the image pointers
+    let (textures, image_ptrs) = textures::read_texture_directory_ptrs(read, texture_count)?;
+
+    assert_that!(
+        "materials offset",
+        read.offset == materials_offset,
+        read.offset
+    )?;
+    let (materials, material_count, material_array_size) =
+        materials::read_materials_ng(read, texture_count)?;
+
+    assert_that!("models offset", read.offset == models_offset, read.offset)?;
+    let (models, model_count, model_array_size) =
+        models::read_models(read, nodes_offset, material_count)?;
+
+    assert_that!("nodes offset", read.offset == nodes_offset, read.offset)?;
+    let nodes = nodes::read_nodes(read, node_array_size, model_count)?;
+
+    read.assert_end()?;
+
+    let metadata = GameZMetadata {
+        datetime,
+        material_array_size,
+        model_array_size,
+        node_array_size,
+        node_last_free: header.node_last_free,
+        image_ptrs: Some(image_ptrs),
+    };
+    Ok(GameZ {
+        textures,
+        materials,
+        models,
+        nodes,
+        metadata,
+    })
+}
+
+pub fn write_gamez(write: &mut CountingWriter<impl Write>, gamez: &GameZ) -> Result<()> {
+    let texture_count = len!(gamez.textures.len(), "GameZ textures")?;
+    let node_array_size = len!(gamez.nodes.len(), "GameZ nodes")?;
+
+    let GameZMetadata {
+        datetime,
+        material_array_size,
+        model_array_size,
+        node_array_size: _,
+        node_last_free,
+        ref image_ptrs,
+    } = gamez.metadata;
+
+    let textures_offset = HeaderPmC::SIZE;
+    let materials_offset = textures_offset + textures::size_texture_directory(texture_count);
+    let models_offset =
+        materials_offset + materials::size_materials(&gamez.materials, material_array_size);
+    let mut models = models::gather_materials(&gamez.materials, &gamez.models);
+    let nodes_offset = models::size_models(models_offset, model_array_size, &mut models);
+
+    let timestamp = to_timestamp(&datetime);
+
+    let header = HeaderPmC {
+        signature: SIGNATURE,
+        version: VERSION_CS,
+        timestamp,
+        texture_count: texture_count.maybe(),
+        textures_offset,
+        materials_offset,
+        models_offset,
+        node_array_size: node_array_size.maybe(),
+        node_last_free,
+        nodes_offset,
+    };
+    write.write_struct(&header)?;
+
+    // missing image pointers are assumed to be assigned
+    let image_ptrs = match image_ptrs {
+        Some(image_ptrs) => image_ptrs.clone(),
+        None => vec![Ptr::NON_NULL.0; gamez.textures.len()],
+    };
+    textures::write_texture_directory(write, &gamez.textures, texture_count, &image_ptrs)?;
+    materials::write_materials_ng(write, &gamez.materials, material_array_size, texture_count)?;
+    models::write_models(write, &models, model_array_size)?;
+    nodes::write_nodes(write, &gamez.nodes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
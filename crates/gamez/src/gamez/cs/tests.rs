@@ -0,0 +1,301 @@
+use super::*;
+use crate::scene::SceneGraph;
+use mech3ax_api_types::gamez::materials::{
+    ColoredMaterial, CycleData, Material, Soil, TexturedMaterial,
+};
+use mech3ax_api_types::gamez::model::{
+    FacadeMode, Model, ModelFlags, ModelType, Polygon, PolygonFlags, PolygonMaterial, UvCoord,
+};
+use mech3ax_api_types::gamez::nodes::{
+    ActiveBoundingBox, BoundingBox, Node, NodeData, NodeFlags, Object3d, RotateTranslateScale,
+    Transform,
+};
+use mech3ax_api_types::gamez::{GameZMetadata, Texture};
+use mech3ax_api_types::{Color, Count, IndexO, IndexR, Vec3};
+use mech3ax_common::GameType;
+use mech3ax_timestamp::unix::from_timestamp;
+use mech3ax_types::Ptr;
+use std::io::Cursor;
+
+fn v(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+fn idx(index: usize) -> IndexR {
+    IndexR::from_usize(index).unwrap()
+}
+
+fn idx_o(index: usize) -> IndexO {
+    IndexO::from_usize(index).unwrap()
+}
+
+fn texture(name: &str) -> Texture {
+    Texture {
+        name: name.to_string(),
+        mip_index: IndexO::NONE,
+    }
+}
+
+/// A textured material, with a looping cycle if `cycle` isn't empty.
+fn textured(texture_index: usize, cycle: &[usize]) -> Material {
+    let cycle = (!cycle.is_empty()).then(|| CycleData {
+        texture_indices: cycle.iter().copied().map(idx).collect(),
+        looping: true,
+        speed: 1.0,
+        current_frame: 0,
+        cycle_ptr: 1,
+        tex_map_ptr: 1,
+    });
+    Material::Textured(TexturedMaterial {
+        texture_index: idx(texture_index),
+        soil: Soil::Default,
+        cycle,
+        flag: false,
+    })
+}
+
+fn colored(color: Color, soil: Soil) -> Material {
+    Material::Colored(ColoredMaterial {
+        color,
+        alpha: 255,
+        soil,
+    })
+}
+
+/// A polygon (as a fan) with a single material, without UV coordinates.
+fn polygon(vertex_indices: Vec<u32>, material_index: usize) -> Polygon {
+    Polygon {
+        flags: PolygonFlags::empty(),
+        priority: 0,
+        zone_set: Vec::new(),
+        vertex_indices,
+        normal_indices: None,
+        vertex_colors: Vec::new(),
+        materials: vec![PolygonMaterial {
+            material_index: idx(material_index),
+            uv_coords: None,
+        }],
+        vertex_indices_ptr: 0,
+        normal_indices_ptr: 0,
+        uvs_ptr: 0,
+        vertex_colors_ptr: 0,
+        matl_refs_ptr: 0,
+        materials_ptr: 0,
+    }
+}
+
+/// A model without parents.
+fn model(vertices: Vec<Vec3>, polygons: Vec<Polygon>) -> Model {
+    Model {
+        model_type: ModelType::Default,
+        facade_mode: FacadeMode::CylindricalY,
+        flags: ModelFlags::empty(),
+        parent_count: 0,
+        vertices,
+        normals: Vec::new(),
+        morphs: Vec::new(),
+        lights: Vec::new(),
+        polygons,
+        texture_scroll: UvCoord { u: 0.0, v: 0.0 },
+        bbox_mid: Vec3::DEFAULT,
+        bbox_diag: 0.0,
+        polygons_ptr: 0,
+        vertices_ptr: 0,
+        normals_ptr: 0,
+        lights_ptr: 0,
+        morphs_ptr: 0,
+        material_refs_ptr: 0,
+    }
+}
+
+fn node(name: &str, data: NodeData) -> Node {
+    Node {
+        name: name.to_string(),
+        flags: NodeFlags::empty(),
+        update_flags: 0,
+        zone_id: 0,
+        model_index: IndexO::NONE,
+        area_partition: None,
+        virtual_partition: None,
+        parent_indices: Vec::new(),
+        child_indices: Vec::new(),
+        active_bbox: ActiveBoundingBox::Node,
+        node_bbox: BoundingBox::EMPTY,
+        model_bbox: BoundingBox::EMPTY,
+        child_bbox: BoundingBox::EMPTY,
+        field192: 0,
+        field196: 0,
+        field200: 0,
+        field204: 0,
+        data,
+        data_ptr: 0,
+        parent_array_ptr: 0,
+        child_array_ptr: 0,
+        index: 0,
+    }
+}
+
+fn translate(x: f32, y: f32, z: f32) -> Transform {
+    Transform::RotateTranslateScale(RotateTranslateScale {
+        rotate: Vec3::DEFAULT,
+        translate: v(x, y, z),
+        scale: v(1.0, 1.0, 1.0),
+        original: None,
+    })
+}
+
+fn object3d(name: &str, transform: Transform) -> Node {
+    node(
+        name,
+        NodeData::Object3d(Object3d {
+            opacity: None,
+            color: None,
+            unk: 0.0,
+            transform,
+            signs: 0,
+        }),
+    )
+}
+
+/// Link a child to a parent, including the array pointers.
+fn link(nodes: &mut [Node], parent: usize, child: usize) {
+    nodes[parent].child_indices.push(idx(child));
+    nodes[parent].child_array_ptr = Ptr::NON_NULL.0;
+    nodes[child].parent_indices.push(idx(parent));
+    nodes[child].parent_array_ptr = Ptr::NON_NULL.0;
+}
+
+/// A GameZ with array sizes that fit the entries.
+fn gamez(
+    textures: Vec<Texture>,
+    materials: Vec<Material>,
+    models: Vec<Model>,
+    nodes: Vec<Node>,
+) -> GameZ {
+    let metadata = GameZMetadata {
+        datetime: from_timestamp(0x3A000000),
+        material_array_size: Count::from_usize(materials.len() + 1).unwrap(),
+        model_array_size: Count::from_usize(models.len() + 1).unwrap(),
+        node_array_size: Count::from_usize(nodes.len()).unwrap(),
+        node_last_free: nodes.len() as i32,
+        image_ptrs: None,
+    };
+    GameZ {
+        textures,
+        materials,
+        models,
+        nodes,
+        metadata,
+    }
+}
+
+/// A GameZ with two textures (one with an image pointer), three materials
+/// (one with a cycle), a model, and two linked nodes, with the pointers that
+/// the CS reader expects to be set.
+fn cs_gamez() -> GameZ {
+    let textures = vec![texture("wall"), texture("floor")];
+    let materials = vec![
+        textured(0, &[]),
+        textured(1, &[0, 1]),
+        colored(Color::BLACK, Soil::Concrete),
+    ];
+    let mut models = vec![model(
+        vec![v(0.0, 0.0, 0.0), v(1.0, 0.0, 0.0), v(1.0, 0.0, 1.0)],
+        vec![polygon(vec![0, 1, 2], 2)],
+    )];
+    let mut nodes = vec![
+        object3d("parent", translate(1.0, 2.0, 3.0)),
+        object3d("child", translate(0.0, 0.0, 0.0)),
+    ];
+    nodes[1].model_index = idx_o(0);
+    // the pointers the reader expects to be set
+    let model = &mut models[0];
+    model.parent_count = 1;
+    model.polygons_ptr = 1;
+    model.vertices_ptr = 1;
+    model.material_refs_ptr = 1;
+    let polygon = &mut model.polygons[0];
+    polygon.vertex_indices_ptr = 1;
+    polygon.materials_ptr = 1;
+    polygon.vertex_colors_ptr = 1;
+    polygon.vertex_colors = vec![Color::BLACK; 3];
+    polygon.matl_refs_ptr = 1;
+    link(&mut nodes, 0, 1);
+    for node in &mut nodes {
+        node.data_ptr = 1;
+        node.field196 = 160;
+        // the matrix calculated from the transform has a negative zero
+        if let NodeData::Object3d(object3d) = &mut node.data {
+            object3d.signs = 128;
+        }
+    }
+    let mut gamez = gamez(textures, materials, models, nodes);
+    gamez.metadata.image_ptrs = Some(vec![0x0012_3456, 0]);
+    gamez
+}
+
+fn round_trip(expected: &GameZ) -> GameZ {
+    let mut write = CountingWriter::new(Cursor::new(Vec::new()), 0);
+    write_gamez(&mut write, expected).unwrap();
+    let buf = write.into_inner().into_inner();
+
+    let mut read = CountingReader::new(Cursor::new(buf.clone()));
+    let actual = read_gamez(&mut read).unwrap();
+
+    // the written data must also be stable
+    let mut write = CountingWriter::new(Cursor::new(Vec::new()), 0);
+    write_gamez(&mut write, &actual).unwrap();
+    assert_eq!(write.into_inner().into_inner(), buf);
+    actual
+}
+
+#[test]
+fn textures_materials_models_nodes_roundtrip() {
+    let expected = cs_gamez();
+    let actual = round_trip(&expected);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn missing_image_ptrs_are_assigned() {
+    let mut expected = cs_gamez();
+    expected.metadata.image_ptrs = None;
+    let actual = round_trip(&expected);
+    let image_ptrs = actual.metadata.image_ptrs.unwrap();
+    assert_eq!(image_ptrs.len(), 2);
+    assert!(image_ptrs.iter().all(|ptr| *ptr != 0));
+}
+
+#[test]
+fn image_ptrs_mismatch_fails() {
+    let mut gamez = cs_gamez();
+    gamez.metadata.image_ptrs = Some(vec![0x0012_3456]);
+    let mut write = CountingWriter::new(Cursor::new(Vec::new()), 0);
+    assert!(write_gamez(&mut write, &gamez).is_err());
+}
+
+#[test]
+fn merged_textures_keep_image_ptrs() {
+    let mut source = cs_gamez();
+    source.textures[1].name = "sky".to_string();
+    source.metadata.image_ptrs = Some(vec![0x0012_3456, 0x0012_3789]);
+    // use the material with a cycle of both textures
+    source.models[0].polygons[0].materials[0].material_index = idx(1);
+    let source = SceneGraph::new(source).unwrap();
+
+    let mut target = SceneGraph::new(cs_gamez()).unwrap();
+    let child = source.find("child").unwrap();
+    target
+        .merge_subtree(&source, child, None, GameType::CS)
+        .unwrap();
+    let expected = target.into_gamez(GameType::CS);
+    // `wall` already exists, `sky` is new
+    assert_eq!(expected.textures.len(), 3);
+    assert_eq!(
+        expected.metadata.image_ptrs,
+        Some(vec![0x0012_3456, 0, 0x0012_3789])
+    );
+
+    let actual = round_trip(&expected);
+    assert_eq!(actual, expected);
+}
@@ -1,4 +1,8 @@
 mod common;
+pub mod cs;
 pub mod mw;
+pub mod partitions;
 pub mod pm;
 pub mod rc;
+
+pub use common::{SIGNATURE, VERSION_CS, VERSION_MW, VERSION_PM, VERSION_RC};
@@ -79,6 +79,7 @@ pub fn read_gamez(read: &mut CountingReader<impl Read + Seek>) -> Result<GameZ>
         model_array_size,
         node_array_size,
         node_last_free: header.node_last_free,
+        image_ptrs: None,
     };
     Ok(GameZ {
         textures,
@@ -98,6 +99,7 @@ pub fn write_gamez(write: &mut CountingWriter<impl Write>, gamez: &GameZ) -> Res
         model_array_size,
         node_array_size,
         mut node_last_free,
+        image_ptrs: _,
     } = gamez.metadata;
 
     let textures_offset = HeaderMwC::SIZE;
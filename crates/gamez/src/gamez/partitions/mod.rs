@@ -0,0 +1,409 @@
+//! Recompute the world's area partitions after nodes were edited.
+//!
+//! The engine culls nodes using the area partitions, which are otherwise
+//! copied verbatim. Which nodes take part in partitioning isn't known, so
+//! only nodes that already have an area partition are considered. Each node
+//! is added to every partition its node bounding box overlaps in x and z,
+//! widened by the world's partition inclusion tolerances, and its own area
+//! partition is the one containing the bounding box center.
+//!
+//! This is a best guess at the engine's rules, and hasn't been checked
+//! against the vanilla partitions. Recomputing is lossy: vanilla partitions
+//! will likely not come out unchanged.
+//!
+//! The y bounds of each partition span the nodes in it. The partition
+//! midpoint and diagonal aren't stored, but are derived from these bounds when
+//! writing (see `partition_diag`).
+use crate::nodes::range::RangeI32;
+use log::debug;
+use mech3ax_api_types::gamez::nodes::{
+    Area, BoundingBox, Node, NodeData, Partition, WorldPartition, WorldPartitionValue,
+};
+use mech3ax_api_types::{IndexR, Vec3};
+use mech3ax_common::{GameType, Result, err};
+use mech3ax_types::Ptr;
+
+const SIZE: i32 = 256;
+const SIZE_F: f32 = SIZE as f32;
+// the world reader asserts these values (`partition_inclusion_tol_low/high`)
+const INCLUSION_TOL_LOW: f32 = 3.0;
+const INCLUSION_TOL_HIGH: f32 = 3.0;
+
+struct Member {
+    node_index: IndexR,
+    y_min: f32,
+    y_max: f32,
+}
+
+/// The partition grid of the world area. Partitions span `x..x + 256` and
+/// `z - 256..z`, since the z axis is inverted.
+struct Grid {
+    xs: Vec<i32>,
+    zs: Vec<i32>,
+}
+
+impl Grid {
+    fn new(area: &Area) -> Self {
+        let xs = RangeI32::new(area.left, area.right, SIZE).collect();
+        // because the virtual partition z size is negative, this is inverted!
+        let zs = RangeI32::new(area.bottom, area.top, -SIZE).collect();
+        Self { xs, zs }
+    }
+
+    fn x_overlaps(&self, min: f32, max: f32) -> impl Iterator<Item = usize> + '_ {
+        let min = min - INCLUSION_TOL_LOW;
+        let max = max + INCLUSION_TOL_HIGH;
+        self.xs.iter().enumerate().filter_map(move |(i, &x)| {
+            let x = x as f32;
+            (min < x + SIZE_F && max >= x).then_some(i)
+        })
+    }
+
+    fn z_overlaps(&self, min: f32, max: f32) -> impl Iterator<Item = usize> + '_ {
+        let min = min - INCLUSION_TOL_LOW;
+        let max = max + INCLUSION_TOL_HIGH;
+        self.zs.iter().enumerate().filter_map(move |(i, &z)| {
+            let z = z as f32;
+            (min < z && max >= z - SIZE_F).then_some(i)
+        })
+    }
+
+    /// The partition containing the point, or the closest partition.
+    fn containing(&self, x: f32, z: f32) -> (usize, usize) {
+        let left = self.xs.first().copied().unwrap_or(0) as f32;
+        let bottom = self.zs.first().copied().unwrap_or(0) as f32;
+        let x_idx = ((x - left) / SIZE_F).floor();
+        let z_idx = ((bottom - z) / SIZE_F).ceil() - 1.0;
+        (
+            clamp_index(x_idx, self.xs.len()),
+            clamp_index(z_idx, self.zs.len()),
+        )
+    }
+
+    /// The area partition of a node, which contains the bounding box center.
+    fn area_partition(&self, min: Vec3, max: Vec3) -> Result<Partition> {
+        let center_x = (min.x + max.x) * 0.5;
+        let center_z = (min.z + max.z) * 0.5;
+        let (x_idx, z_idx) = self.containing(center_x, center_z);
+        let (Ok(x), Ok(z)) = (u8::try_from(x_idx), u8::try_from(z_idx)) else {
+            return Err(err!(
+                "Expected partition x {} and z {} in 0..={}",
+                x_idx,
+                z_idx,
+                u8::MAX
+            ));
+        };
+        Ok(Partition { x, z })
+    }
+}
+
+fn use_values(game: GameType) -> bool {
+    match game {
+        GameType::PM | GameType::CS => true,
+        GameType::MW | GameType::RC => false,
+    }
+}
+
+fn clamp_index(value: f32, len: usize) -> usize {
+    let max = len.saturating_sub(1);
+    if value <= 0.0 {
+        0
+    } else {
+        // saturating cast
+        (value as usize).min(max)
+    }
+}
+
+fn bbox_bounds(bbox: &BoundingBox) -> (Vec3, Vec3) {
+    let min = Vec3 {
+        x: bbox.a.x.min(bbox.b.x),
+        y: bbox.a.y.min(bbox.b.y),
+        z: bbox.a.z.min(bbox.b.z),
+    };
+    let max = Vec3 {
+        x: bbox.a.x.max(bbox.b.x),
+        y: bbox.a.y.max(bbox.b.y),
+        z: bbox.a.z.max(bbox.b.z),
+    };
+    (min, max)
+}
+
+/// The world node's index and area, which must be a multiple of the
+/// partition size.
+fn world_area(nodes: &[Node]) -> Result<(usize, Area)> {
+    let Some(world_index) = nodes
+        .iter()
+        .position(|node| matches!(node.data, NodeData::World(_)))
+    else {
+        return Err(err!("Expected a world node for the partitions"));
+    };
+
+    let area = match &nodes[world_index].data {
+        NodeData::World(world) => world.area.clone(),
+        _ => unreachable!(),
+    };
+    if (area.right - area.left) % SIZE != 0 || (area.bottom - area.top) % SIZE != 0 {
+        return Err(err!(
+            "Expected the world area to be a multiple of {} (left: {}, top: {}, right: {}, bottom: {})",
+            SIZE,
+            area.left,
+            area.top,
+            area.right,
+            area.bottom
+        ));
+    }
+    Ok((world_index, area))
+}
+
+/// Recompute the world node's area partitions, and each node's area
+/// partition, from the node bounding boxes and the world area.
+///
+/// In PM and CS, partitions hold values with the y bounds of each node,
+/// otherwise they hold node indices. Existing node pointers are kept for
+/// partitions that aren't empty.
+pub fn recompute_partitions(nodes: &mut [Node], game: GameType) -> Result<()> {
+    let (world_index, area) = world_area(nodes)?;
+    let grid = Grid::new(&area);
+    let x_len = grid.xs.len();
+
+    let mut members: Vec<Vec<Member>> = std::iter::repeat_with(Vec::new)
+        .take(x_len * grid.zs.len())
+        .collect();
+
+    for (index, node) in nodes.iter_mut().enumerate() {
+        if node.area_partition.is_none() {
+            continue;
+        }
+        let Some(node_index) = IndexR::from_usize(index) else {
+            return Err(err!("Too many nodes to recompute partitions ({})", index));
+        };
+        let (min, max) = bbox_bounds(&node.node_bbox);
+
+        for z_idx in grid.z_overlaps(min.z, max.z) {
+            for x_idx in grid.x_overlaps(min.x, max.x) {
+                members[z_idx * x_len + x_idx].push(Member {
+                    node_index,
+                    y_min: min.y,
+                    y_max: max.y,
+                });
+            }
+        }
+
+        node.area_partition = Some(grid.area_partition(min, max)?);
+    }
+
+    let use_values = use_values(game);
+
+    let NodeData::World(world) = &mut nodes[world_index].data else {
+        unreachable!()
+    };
+
+    let mut members = members.into_iter();
+    let partitions = grid
+        .zs
+        .iter()
+        .map(|&z| {
+            grid.xs
+                .iter()
+                .map(|&x| {
+                    // cannot fail, since there is a member list per partition
+                    let members = members.next().unwrap();
+                    let previous = world
+                        .partitions
+                        .iter()
+                        .flatten()
+                        .find(|partition| partition.x == x && partition.z == z);
+                    recompute_partition(x, z, members, previous, use_values)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    debug!("Recomputed {}x{} partitions", grid.xs.len(), grid.zs.len());
+    world.partitions = partitions;
+    Ok(())
+}
+
+/// Nodes to add to the world node's existing area partitions, which were
+/// checked without changing any nodes.
+pub(crate) struct PartitionAdditions {
+    world_index: usize,
+    /// The node index, and its area partition.
+    nodes: Vec<(usize, Partition)>,
+    /// The world partition's row and column, and the member.
+    members: Vec<(usize, usize, Member)>,
+}
+
+/// Check nodes can be added to the world node's existing area partitions.
+///
+/// The added nodes are given with the index they have (or will have), so they
+/// can be checked before they are in `nodes`.
+pub(crate) fn plan_partitions<'a>(
+    nodes: &[Node],
+    added: impl IntoIterator<Item = (usize, &'a Node)>,
+) -> Result<PartitionAdditions> {
+    let (world_index, area) = world_area(nodes)?;
+    let grid = Grid::new(&area);
+    let NodeData::World(world) = &nodes[world_index].data else {
+        unreachable!()
+    };
+
+    let mut additions = PartitionAdditions {
+        world_index,
+        nodes: Vec::new(),
+        members: Vec::new(),
+    };
+    for (index, node) in added {
+        if node.area_partition.is_none() {
+            continue;
+        }
+        let Some(node_index) = IndexR::from_usize(index) else {
+            return Err(err!("Too many nodes to add to partitions ({})", index));
+        };
+        let (min, max) = bbox_bounds(&node.node_bbox);
+        additions
+            .nodes
+            .push((index, grid.area_partition(min, max)?));
+
+        for z_idx in grid.z_overlaps(min.z, max.z) {
+            for x_idx in grid.x_overlaps(min.x, max.x) {
+                let (x, z) = (grid.xs[x_idx], grid.zs[z_idx]);
+                let Some((row, col)) = world.partitions.iter().enumerate().find_map(|(row, ps)| {
+                    ps.iter()
+                        .position(|partition| partition.x == x && partition.z == z)
+                        .map(|col| (row, col))
+                }) else {
+                    return Err(err!("Expected a world partition at x {} and z {}", x, z));
+                };
+                let member = Member {
+                    node_index,
+                    y_min: min.y,
+                    y_max: max.y,
+                };
+                additions.members.push((row, col, member));
+            }
+        }
+    }
+    Ok(additions)
+}
+
+impl PartitionAdditions {
+    /// Add the nodes to the partitions, and set their area partition. The
+    /// nodes must have been checked against the same world node.
+    pub(crate) fn apply(self, nodes: &mut [Node], game: GameType) {
+        let use_values = use_values(game);
+        for (index, partition) in self.nodes {
+            nodes[index].area_partition = Some(partition);
+        }
+
+        let NodeData::World(world) = &mut nodes[self.world_index].data else {
+            unreachable!()
+        };
+        for (row, col, member) in self.members {
+            let partition = &mut world.partitions[row][col];
+            let empty = partition.node_indices.is_empty() && partition.values.is_empty();
+            if empty {
+                partition.min.y = member.y_min;
+                partition.max.y = member.y_max;
+                partition.nodes_ptr = Ptr::NON_NULL.0;
+            } else {
+                partition.min.y = partition.min.y.min(member.y_min);
+                partition.max.y = partition.max.y.max(member.y_max);
+            }
+            if use_values {
+                partition.values.push(WorldPartitionValue {
+                    node_index: member.node_index,
+                    y_min: member.y_min,
+                    y_max: member.y_max,
+                });
+            } else {
+                partition.node_indices.push(member.node_index);
+            }
+        }
+    }
+}
+
+/// Add nodes to the world node's existing area partitions, and set their area
+/// partition, without recomputing the other nodes' partitions.
+///
+/// Like [`recompute_partitions`], only nodes that already have an area
+/// partition are considered. The y bounds of partitions are extended to span
+/// the added nodes. If a node can't be added, no nodes are changed.
+pub fn add_to_partitions(nodes: &mut [Node], indices: &[usize], game: GameType) -> Result<()> {
+    let mut added = Vec::new();
+    for &index in indices {
+        let Some(node) = nodes.get(index) else {
+            return Err(err!("Expected node index {} < {}", index, nodes.len()));
+        };
+        added.push((index, node));
+    }
+    let additions = plan_partitions(nodes, added)?;
+    additions.apply(nodes, game);
+    Ok(())
+}
+
+fn recompute_partition(
+    x: i32,
+    z: i32,
+    members: Vec<Member>,
+    previous: Option<&WorldPartition>,
+    use_values: bool,
+) -> WorldPartition {
+    let (y_min, y_max) = if members.is_empty() {
+        // keep the previous bounds, since there are no nodes to derive them from
+        previous
+            .map(|partition| (partition.min.y, partition.max.y))
+            .unwrap_or((0.0, 0.0))
+    } else {
+        members.iter().fold((f32::MAX, f32::MIN), |(lo, hi), m| {
+            (lo.min(m.y_min), hi.max(m.y_max))
+        })
+    };
+
+    let nodes_ptr = if members.is_empty() {
+        Ptr::NULL.0
+    } else {
+        previous
+            .map(|partition| partition.nodes_ptr)
+            .filter(|ptr| *ptr != Ptr::NULL.0)
+            .unwrap_or(Ptr::NON_NULL.0)
+    };
+
+    let (node_indices, values) = if use_values {
+        let values = members
+            .into_iter()
+            .map(|m| WorldPartitionValue {
+                node_index: m.node_index,
+                y_min: m.y_min,
+                y_max: m.y_max,
+            })
+            .collect();
+        (Vec::new(), values)
+    } else {
+        let node_indices = members.into_iter().map(|m| m.node_index).collect();
+        (node_indices, Vec::new())
+    };
+
+    let xf = x as f32;
+    let zf = z as f32;
+    WorldPartition {
+        x,
+        z,
+        min: Vec3 {
+            x: xf,
+            y: y_min,
+            z: zf - SIZE_F,
+        },
+        max: Vec3 {
+            x: xf + SIZE_F,
+            y: y_max,
+            z: zf,
+        },
+        node_indices,
+        values,
+        nodes_ptr,
+    }
+}
+
+#[cfg(test)]
+mod tests;
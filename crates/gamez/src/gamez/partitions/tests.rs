@@ -0,0 +1,300 @@
+use super::*;
+use mech3ax_api_types::gamez::nodes::{
+    ActiveBoundingBox, FogType, NodeFlags, World, WorldFog, WorldPtrs,
+};
+use mech3ax_api_types::{Color, IndexO, Range};
+
+fn node(name: &str, data: NodeData, bbox: BoundingBox, partition: Option<Partition>) -> Node {
+    Node {
+        name: name.to_string(),
+        flags: NodeFlags::empty(),
+        update_flags: 0,
+        zone_id: 0,
+        model_index: IndexO::NONE,
+        area_partition: partition,
+        virtual_partition: None,
+        parent_indices: Vec::new(),
+        child_indices: Vec::new(),
+        active_bbox: ActiveBoundingBox::Node,
+        node_bbox: bbox,
+        model_bbox: BoundingBox::EMPTY,
+        child_bbox: BoundingBox::EMPTY,
+        field192: 0,
+        field196: 0,
+        field200: 0,
+        field204: 0,
+        data,
+        data_ptr: 0,
+        parent_array_ptr: 0,
+        child_array_ptr: 0,
+        index: 0,
+    }
+}
+
+fn world() -> Node {
+    let world = World {
+        fog: WorldFog {
+            fog_type: FogType::Off,
+            fog_color: Color::BLACK,
+            fog_range: Range::DEFAULT,
+            fog_altitude: Range::DEFAULT,
+            fog_density: 0.0,
+        },
+        // 2x2 partitions
+        area: Area {
+            left: 0,
+            top: 0,
+            right: 512,
+            bottom: 512,
+        },
+        virtual_partition: false,
+        partition_max_dec_feature_count: 16,
+        light_indices: Vec::new(),
+        sound_indices: Vec::new(),
+        partitions: Vec::new(),
+        unk: 0,
+        ptrs: WorldPtrs {
+            area_partition_ptr: 1,
+            virt_partition_ptr: 1,
+            light_nodes_ptr: 0,
+            light_data_ptr: 0,
+            sound_nodes_ptr: 0,
+            sound_data_ptr: 0,
+        },
+    };
+    node("world1", NodeData::World(world), BoundingBox::EMPTY, None)
+}
+
+fn bbox(x0: f32, y0: f32, z0: f32, x1: f32, y1: f32, z1: f32) -> BoundingBox {
+    BoundingBox {
+        a: Vec3 {
+            x: x0,
+            y: y0,
+            z: z0,
+        },
+        b: Vec3 {
+            x: x1,
+            y: y1,
+            z: z1,
+        },
+    }
+}
+
+const STALE: Option<Partition> = Some(Partition { x: 9, z: 9 });
+
+fn partitions(nodes: &[Node]) -> &Vec<Vec<WorldPartition>> {
+    match &nodes[0].data {
+        NodeData::World(world) => &world.partitions,
+        _ => panic!("expected world"),
+    }
+}
+
+fn idx(index: usize) -> IndexR {
+    IndexR::from_usize(index).unwrap()
+}
+
+#[test]
+fn grid_layout() {
+    let mut nodes = vec![world()];
+    recompute_partitions(&mut nodes, GameType::MW).unwrap();
+    let partitions = partitions(&nodes);
+
+    let positions = partitions
+        .iter()
+        .map(|row| row.iter().map(|p| (p.x, p.z)).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        positions,
+        vec![vec![(0, 512), (256, 512)], vec![(0, 256), (256, 256)]]
+    );
+    let p = &partitions[1][1];
+    assert_eq!(
+        (p.min.x, p.min.z, p.max.x, p.max.z),
+        (256.0, 0.0, 512.0, 256.0)
+    );
+    assert_eq!(p.nodes_ptr, 0);
+}
+
+#[test]
+fn membership_mw() {
+    let mut nodes = vec![
+        world(),
+        // inside the first partition only
+        node(
+            "a",
+            NodeData::Empty,
+            bbox(10.0, -5.0, 300.0, 20.0, 5.0, 310.0),
+            STALE,
+        ),
+        // spans the two partitions in x
+        node(
+            "b",
+            NodeData::Empty,
+            bbox(200.0, 0.0, 400.0, 300.0, 50.0, 410.0),
+            STALE,
+        ),
+        // not partitioned
+        node(
+            "c",
+            NodeData::Empty,
+            bbox(10.0, 0.0, 300.0, 20.0, 0.0, 310.0),
+            None,
+        ),
+    ];
+    recompute_partitions(&mut nodes, GameType::MW).unwrap();
+
+    assert_eq!(nodes[1].area_partition, Some(Partition { x: 0, z: 0 }));
+    // the center is at x 250
+    assert_eq!(nodes[2].area_partition, Some(Partition { x: 0, z: 0 }));
+    assert_eq!(nodes[3].area_partition, None);
+
+    let partitions = partitions(&nodes);
+    let first = &partitions[0][0];
+    assert_eq!(first.node_indices, vec![idx(1), idx(2)]);
+    assert!(first.values.is_empty());
+    assert_eq!((first.min.y, first.max.y), (-5.0, 50.0));
+    assert_eq!(first.nodes_ptr, Ptr::NON_NULL.0);
+
+    let second = &partitions[0][1];
+    assert_eq!(second.node_indices, vec![idx(2)]);
+    assert_eq!((second.min.y, second.max.y), (0.0, 50.0));
+
+    assert!(partitions[1][0].node_indices.is_empty());
+    assert!(partitions[1][1].node_indices.is_empty());
+}
+
+#[test]
+fn membership_pm_values() {
+    let mut nodes = vec![
+        world(),
+        node(
+            "a",
+            NodeData::Empty,
+            bbox(300.0, 1.0, 10.0, 310.0, 2.0, 20.0),
+            STALE,
+        ),
+    ];
+    recompute_partitions(&mut nodes, GameType::PM).unwrap();
+
+    assert_eq!(nodes[1].area_partition, Some(Partition { x: 1, z: 1 }));
+    let p = &partitions(&nodes)[1][1];
+    assert!(p.node_indices.is_empty());
+    assert_eq!(
+        p.values,
+        vec![WorldPartitionValue {
+            node_index: idx(1),
+            y_min: 1.0,
+            y_max: 2.0,
+        }]
+    );
+}
+
+#[test]
+fn inclusion_tolerance() {
+    let mut nodes = vec![
+        world(),
+        // within the tolerance of the second partition in x
+        node(
+            "a",
+            NodeData::Empty,
+            bbox(200.0, 0.0, 300.0, 253.5, 1.0, 310.0),
+            STALE,
+        ),
+        // outside the tolerance
+        node(
+            "b",
+            NodeData::Empty,
+            bbox(200.0, 0.0, 300.0, 252.5, 1.0, 310.0),
+            STALE,
+        ),
+    ];
+    recompute_partitions(&mut nodes, GameType::MW).unwrap();
+
+    let partitions = partitions(&nodes);
+    assert_eq!(partitions[0][0].node_indices, vec![idx(1), idx(2)]);
+    assert_eq!(partitions[0][1].node_indices, vec![idx(1)]);
+}
+
+#[test]
+fn previous_pointers_and_bounds_kept() {
+    let mut nodes = vec![world()];
+    recompute_partitions(&mut nodes, GameType::MW).unwrap();
+    if let NodeData::World(world) = &mut nodes[0].data {
+        world.partitions[0][0].nodes_ptr = 0x1234;
+        world.partitions[1][1].min.y = -7.0;
+        world.partitions[1][1].max.y = 7.0;
+    }
+    nodes.push(node(
+        "a",
+        NodeData::Empty,
+        bbox(10.0, 0.0, 300.0, 20.0, 1.0, 310.0),
+        STALE,
+    ));
+    recompute_partitions(&mut nodes, GameType::MW).unwrap();
+
+    let partitions = partitions(&nodes);
+    assert_eq!(partitions[0][0].nodes_ptr, 0x1234);
+    let empty = &partitions[1][1];
+    assert_eq!((empty.min.y, empty.max.y), (-7.0, 7.0));
+    assert_eq!(empty.nodes_ptr, 0);
+}
+
+#[test]
+fn outside_area_is_clamped() {
+    let mut nodes = vec![
+        world(),
+        node(
+            "a",
+            NodeData::Empty,
+            bbox(-90.0, 0.0, 900.0, -80.0, 0.0, 910.0),
+            STALE,
+        ),
+    ];
+    recompute_partitions(&mut nodes, GameType::MW).unwrap();
+    assert_eq!(nodes[1].area_partition, Some(Partition { x: 0, z: 0 }));
+    let partitions = partitions(&nodes);
+    assert!(
+        partitions
+            .iter()
+            .flatten()
+            .all(|p| p.node_indices.is_empty())
+    );
+}
+
+#[test]
+fn missing_world_fails() {
+    let mut nodes = vec![node("a", NodeData::Empty, BoundingBox::EMPTY, STALE)];
+    assert!(recompute_partitions(&mut nodes, GameType::MW).is_err());
+}
+
+#[test]
+fn added_nodes_match_recompute() {
+    for game in [GameType::MW, GameType::PM] {
+        let mut nodes = vec![
+            world(),
+            node(
+                "a",
+                NodeData::Empty,
+                bbox(10.0, -5.0, 300.0, 20.0, 5.0, 310.0),
+                STALE,
+            ),
+        ];
+        recompute_partitions(&mut nodes, game).unwrap();
+        nodes.push(node(
+            "b",
+            NodeData::Empty,
+            bbox(200.0, 0.0, 400.0, 300.0, 50.0, 410.0),
+            STALE,
+        ));
+        nodes.push(node(
+            "c",
+            NodeData::Empty,
+            bbox(10.0, 0.0, 300.0, 20.0, 0.0, 310.0),
+            None,
+        ));
+        let mut expected = nodes.clone();
+        add_to_partitions(&mut nodes, &[2, 3], game).unwrap();
+        recompute_partitions(&mut expected, game).unwrap();
+        assert_eq!(nodes, expected);
+    }
+}
@@ -1,6 +1,7 @@
 mod data;
-mod models;
-mod nodes;
+// CS shares the PM model and node formats
+pub(super) mod models;
+pub(super) mod nodes;
 
 use super::common::{
     NODE_INDEX_BOT_MASK, NODE_INDEX_TOP, NODE_INDEX_TOP_MASK, SIGNATURE, VERSION_PM, texture_count,
@@ -15,22 +16,23 @@ use mech3ax_api_types::gamez::{GameZ, GameZMetadata};
 use mech3ax_common::io_ext::{CountingReader, CountingWriter};
 use mech3ax_common::{Result, assert_that, chk, len};
 use mech3ax_timestamp::unix::{from_timestamp, to_timestamp};
-use mech3ax_types::{AsBytes as _, Offsets, impl_as_bytes, u32_to_usize};
+use mech3ax_types::{AsBytes as _, Offsets, Ptr, impl_as_bytes, u32_to_usize};
 use std::io::{Read, Write};
 
+/// The header is shared with CS, which only differs in the version.
 #[derive(Debug, Clone, Copy, PartialEq, NoUninit, AnyBitPattern, Offsets)]
 #[repr(C)]
-struct HeaderPmC {
-    signature: u32,           // 00
-    version: u32,             // 04
-    timestamp: u32,           // 08
-    texture_count: Count32,   // 12
-    textures_offset: u32,     // 16
-    materials_offset: u32,    // 20
-    models_offset: u32,       // 24
-    node_array_size: Count32, // 28
-    node_last_free: i32,      // 32
-    nodes_offset: u32,        // 36
+pub(super) struct HeaderPmC {
+    pub(super) signature: u32,           // 00
+    pub(super) version: u32,             // 04
+    pub(super) timestamp: u32,           // 08
+    pub(super) texture_count: Count32,   // 12
+    pub(super) textures_offset: u32,     // 16
+    pub(super) materials_offset: u32,    // 20
+    pub(super) models_offset: u32,       // 24
+    pub(super) node_array_size: Count32, // 28
+    pub(super) node_last_free: i32,      // 32
+    pub(super) nodes_offset: u32,        // 36
 }
 impl_as_bytes!(HeaderPmC, 40);
 
@@ -88,6 +90,7 @@ pub fn read_gamez(read: &mut CountingReader<impl Read>) -> Result<GameZ> {
         model_array_size,
         node_array_size,
         node_last_free: header.node_last_free,
+        image_ptrs: None,
     };
     Ok(GameZ {
         textures,
@@ -108,6 +111,7 @@ pub fn write_gamez(write: &mut CountingWriter<impl Write>, gamez: &GameZ) -> Res
         model_array_size,
         node_array_size: _,
         node_last_free,
+        image_ptrs: _,
     } = gamez.metadata;
 
     let textures_offset = HeaderPmC::SIZE;
@@ -138,12 +142,16 @@ pub fn write_gamez(write: &mut CountingWriter<impl Write>, gamez: &GameZ) -> Res
     let campaign = Campaign::from_header(&header);
     trace!("Campaign: {:?}", campaign);
 
-    textures::write_texture_directory(
-        write,
-        &gamez.textures,
-        texture_count,
-        campaign.image_ptrs(),
-    )?;
+    // textures that were added (or are from an unknown campaign) are assumed
+    // to be assigned
+    let image_ptrs: Vec<u32> = campaign
+        .image_ptrs()
+        .iter()
+        .copied()
+        .chain(std::iter::repeat(Ptr::NON_NULL.0))
+        .take(gamez.textures.len())
+        .collect();
+    textures::write_texture_directory(write, &gamez.textures, texture_count, &image_ptrs)?;
     materials::write_materials_ng(write, &gamez.materials, material_array_size, texture_count)?;
     models::write_models(write, &models, model_array_size)?;
     nodes::write_nodes(write, &gamez.nodes)?;
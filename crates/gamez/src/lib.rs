@@ -1,8 +1,13 @@
 #![warn(clippy::all, clippy::cargo)]
 #![allow(clippy::identity_op)]
+pub mod bounds;
+pub mod compact;
 pub mod gamez;
 mod materials;
 pub mod mechlib;
 mod model;
 mod nodes;
+pub mod planes;
+pub mod render;
+pub mod scene;
 mod textures;
@@ -1,5 +1,5 @@
 #[inline]
-fn approx_sqrt(value: f32) -> f32 {
+pub(crate) fn approx_sqrt(value: f32) -> f32 {
     let cast = i32::from_ne_bytes(value.to_ne_bytes());
     let approx = (cast >> 1) + 0x1FC00000;
     f32::from_ne_bytes(approx.to_ne_bytes())
@@ -4,7 +4,7 @@ pub(crate) mod display;
 pub(crate) mod helpers;
 pub(crate) mod light;
 pub(crate) mod lod;
-mod math;
+pub(crate) mod math;
 pub(crate) mod node;
 pub(crate) mod object3d;
 pub(crate) mod range;
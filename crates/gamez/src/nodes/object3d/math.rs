@@ -1,7 +1,8 @@
 use super::Object3dC;
+use mech3ax_api_types::gamez::nodes::{Node, NodeData, Transform};
 use mech3ax_api_types::{AffineMatrix, Vec3};
 
-pub(super) fn object_matrix(rotate: Vec3, scale: Vec3, translate: Vec3) -> AffineMatrix {
+pub(crate) fn object_matrix(rotate: Vec3, scale: Vec3, translate: Vec3) -> AffineMatrix {
     let (sin_x, cos_x) = rotate.x.sin_cos();
     let (sin_y, cos_y) = rotate.y.sin_cos();
     let (sin_z, cos_z) = rotate.z.sin_cos();
@@ -43,6 +44,49 @@ pub(super) fn object_matrix(rotate: Vec3, scale: Vec3, translate: Vec3) -> Affin
     m
 }
 
+/// Apply `local`, then `parent`.
+pub(crate) fn combine(local: &AffineMatrix, parent: &AffineMatrix) -> AffineMatrix {
+    let l = local;
+    let p = parent;
+    AffineMatrix {
+        r00: l.r00 * p.r00 + l.r01 * p.r10 + l.r02 * p.r20,
+        r01: l.r00 * p.r01 + l.r01 * p.r11 + l.r02 * p.r21,
+        r02: l.r00 * p.r02 + l.r01 * p.r12 + l.r02 * p.r22,
+        r10: l.r10 * p.r00 + l.r11 * p.r10 + l.r12 * p.r20,
+        r11: l.r10 * p.r01 + l.r11 * p.r11 + l.r12 * p.r21,
+        r12: l.r10 * p.r02 + l.r11 * p.r12 + l.r12 * p.r22,
+        r20: l.r20 * p.r00 + l.r21 * p.r10 + l.r22 * p.r20,
+        r21: l.r20 * p.r01 + l.r21 * p.r11 + l.r22 * p.r21,
+        r22: l.r20 * p.r02 + l.r21 * p.r12 + l.r22 * p.r22,
+        r30: l.r30 * p.r00 + l.r31 * p.r10 + l.r32 * p.r20 + p.r30,
+        r31: l.r30 * p.r01 + l.r31 * p.r11 + l.r32 * p.r21 + p.r31,
+        r32: l.r30 * p.r02 + l.r31 * p.r12 + l.r32 * p.r22 + p.r32,
+    }
+}
+
+/// Transform a point (as a row vector).
+pub(crate) fn transform_point(m: &AffineMatrix, p: Vec3) -> Vec3 {
+    Vec3 {
+        x: p.x * m.r00 + p.y * m.r10 + p.z * m.r20 + m.r30,
+        y: p.x * m.r01 + p.y * m.r11 + p.z * m.r21 + m.r31,
+        z: p.x * m.r02 + p.y * m.r12 + p.z * m.r22 + m.r32,
+    }
+}
+
+/// The local transform of an object 3D node.
+pub(crate) fn node_matrix(node: &Node) -> Option<AffineMatrix> {
+    match &node.data {
+        NodeData::Object3d(object3d) => Some(match &object3d.transform {
+            Transform::Initial => AffineMatrix::IDENTITY,
+            Transform::Matrix(m) => *m,
+            Transform::RotateTranslateScale(rts) => rts
+                .original
+                .unwrap_or_else(|| object_matrix(rts.rotate, rts.scale, rts.translate)),
+        }),
+        _ => None,
+    }
+}
+
 const NEG_ZERO: u32 = 0x8000_0000;
 const POS_ZERO: u32 = 0x0000_0000;
 
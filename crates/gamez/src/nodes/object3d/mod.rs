@@ -1,4 +1,4 @@
-mod math;
+pub(crate) mod math;
 mod read;
 mod write;
 
@@ -0,0 +1,19 @@
+//! CS `planes.zbd` support.
+//!
+//! The planes archive is a CS GameZ file, so it is read and written as one.
+use crate::gamez::cs;
+use mech3ax_api_types::gamez::GameZ;
+use mech3ax_common::Result;
+use mech3ax_common::io_ext::{CountingReader, CountingWriter};
+use std::io::{Read, Write};
+
+pub fn read_planes(read: &mut CountingReader<impl Read>) -> Result<GameZ> {
+    cs::read_gamez(read)
+}
+
+pub fn write_planes(write: &mut CountingWriter<impl Write>, planes: &GameZ) -> Result<()> {
+    cs::write_gamez(write, planes)
+}
+
+#[cfg(test)]
+mod tests;
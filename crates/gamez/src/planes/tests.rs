@@ -0,0 +1,39 @@
+use super::*;
+use mech3ax_api_types::gamez::{GameZMetadata, Texture};
+use mech3ax_api_types::{Count, IndexO};
+use mech3ax_timestamp::unix::from_timestamp;
+use std::io::Cursor;
+
+fn texture(name: &str) -> Texture {
+    Texture {
+        name: name.to_string(),
+        mip_index: IndexO::NONE,
+    }
+}
+
+#[test]
+fn duplicate_textures_roundtrip() {
+    // the planes archive has duplicate texture names
+    let expected = GameZ {
+        textures: vec![texture("prop"), texture("prop"), texture("wing")],
+        materials: Vec::new(),
+        models: Vec::new(),
+        nodes: Vec::new(),
+        metadata: GameZMetadata {
+            datetime: from_timestamp(0x3A000000),
+            material_array_size: Count::from_i16(1).unwrap(),
+            model_array_size: Count::from_i16(1).unwrap(),
+            node_array_size: Count::EMPTY,
+            node_last_free: 0,
+            image_ptrs: Some(vec![0x0012_3456, 0, 0x0012_3789]),
+        },
+    };
+
+    let mut write = CountingWriter::new(Cursor::new(Vec::new()), 0);
+    write_planes(&mut write, &expected).unwrap();
+    let buf = write.into_inner().into_inner();
+
+    let mut read = CountingReader::new(Cursor::new(buf));
+    let actual = read_planes(&mut read).unwrap();
+    assert_eq!(actual, expected);
+}
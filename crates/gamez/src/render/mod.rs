@@ -0,0 +1,261 @@
+//! Render top-down (orthographic) images of a GameZ world, on the CPU.
+//!
+//! Nodes are placed using their world transforms, and model polygons are
+//! split into triangles. Looking down the y axis, the image x axis is the
+//! world x axis, and the image y axis is the world z axis, so the world area's
+//! left and top are the image's left and top. Where triangles overlap, the
+//! highest point wins.
+mod overview;
+mod soil;
+
+use crate::nodes::object3d::math::transform_point;
+use crate::scene::world_transform;
+use log::trace;
+use mech3ax_api_types::Vec3;
+use mech3ax_api_types::gamez::GameZ;
+use mech3ax_api_types::gamez::model::{ModelType, PolygonFlags};
+use mech3ax_api_types::gamez::nodes::{Node, NodeData};
+use mech3ax_common::{Result, err};
+
+pub use overview::{OverviewOptions, render_overview};
+pub use soil::{
+    SOIL_BACKGROUND, SoilCoverage, SoilMap, SoilMapOptions, render_soil_map, soil_color,
+};
+
+/// A model polygon's triangle, in world space.
+#[derive(Debug, Clone)]
+struct Triangle {
+    model_index: usize,
+    polygon_index: usize,
+    /// The indices into the polygon's vertex list (not the model's
+    /// vertices), for looking up UV coordinates.
+    corners: [usize; 3],
+    points: [Vec3; 3],
+}
+
+/// Split a polygon with `count` vertices into triangles, as a strip or a fan.
+fn triangulate(count: usize, strip: bool) -> impl Iterator<Item = [usize; 3]> {
+    (0..count.saturating_sub(2)).map(move |i| {
+        if !strip {
+            [0, i + 1, i + 2]
+        } else if i % 2 == 0 {
+            [i, i + 1, i + 2]
+        } else {
+            [i + 1, i, i + 2]
+        }
+    })
+}
+
+/// The world-space triangles of the models of all nodes `include` accepts.
+///
+/// Facade and point models are skipped, since their geometry depends on the
+/// camera.
+fn world_triangles(gamez: &GameZ, include: impl Fn(&Node) -> bool) -> Result<Vec<Triangle>> {
+    let mut triangles = Vec::new();
+    for (node_index, node) in gamez.nodes.iter().enumerate() {
+        if !include(node) {
+            continue;
+        }
+        let Some(model_index) = node.model_index.to_usize() else {
+            continue;
+        };
+        let Some(model) = gamez.models.get(model_index) else {
+            return Err(err!(
+                "Expected node {} model index {} < {}",
+                node_index,
+                model_index,
+                gamez.models.len()
+            ));
+        };
+        if model.model_type != ModelType::Default {
+            continue;
+        }
+
+        let matrix = world_transform(&gamez.nodes, node_index);
+        let vertices: Vec<Vec3> = model
+            .vertices
+            .iter()
+            .map(|&vertex| transform_point(&matrix, vertex))
+            .collect();
+
+        for (polygon_index, polygon) in model.polygons.iter().enumerate() {
+            let mut points = Vec::with_capacity(polygon.vertex_indices.len());
+            for &vertex_index in &polygon.vertex_indices {
+                let Some(&point) = vertices.get(vertex_index as usize) else {
+                    return Err(err!(
+                        "Expected model {} polygon {} vertex index {} < {}",
+                        model_index,
+                        polygon_index,
+                        vertex_index,
+                        vertices.len()
+                    ));
+                };
+                points.push(point);
+            }
+            let strip = polygon.flags.contains(PolygonFlags::TRI_STRIP);
+            triangles.extend(triangulate(points.len(), strip).map(|corners| Triangle {
+                model_index,
+                polygon_index,
+                corners,
+                points: corners.map(|corner| points[corner]),
+            }));
+        }
+    }
+    Ok(triangles)
+}
+
+/// The world x and z extents that are rendered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Extents {
+    left: f32,
+    top: f32,
+    right: f32,
+    bottom: f32,
+}
+
+/// The extents of the world node's area, or if there is no world node, the
+/// extents of the triangles.
+fn world_extents(gamez: &GameZ, triangles: &[Triangle]) -> Result<Extents> {
+    let area = gamez.nodes.iter().find_map(|node| match &node.data {
+        NodeData::World(world) => Some(&world.area),
+        _ => None,
+    });
+    let extents = match area {
+        Some(area) => Extents {
+            left: area.left as f32,
+            top: area.top as f32,
+            right: area.right as f32,
+            bottom: area.bottom as f32,
+        },
+        None => triangles.iter().flat_map(|t| &t.points).fold(
+            Extents {
+                left: f32::INFINITY,
+                top: f32::INFINITY,
+                right: f32::NEG_INFINITY,
+                bottom: f32::NEG_INFINITY,
+            },
+            |e, p| Extents {
+                left: e.left.min(p.x),
+                top: e.top.min(p.z),
+                right: e.right.max(p.x),
+                bottom: e.bottom.max(p.z),
+            },
+        ),
+    };
+    if !(extents.right > extents.left && extents.bottom > extents.top) {
+        return Err(err!(
+            "Expected world extents to be non-empty (left: {}, top: {}, right: {}, bottom: {})",
+            extents.left,
+            extents.top,
+            extents.right,
+            extents.bottom
+        ));
+    }
+    Ok(extents)
+}
+
+/// Maps world x and z to image coordinates, preserving the aspect ratio.
+#[derive(Debug, Clone, Copy)]
+struct View {
+    extents: Extents,
+    scale: f32,
+    width: u32,
+    height: u32,
+}
+
+impl View {
+    fn new(extents: Extents, size: u32) -> Result<Self> {
+        if size == 0 {
+            return Err(err!("Expected image size to be > 0"));
+        }
+        let range_x = extents.right - extents.left;
+        let range_z = extents.bottom - extents.top;
+        let scale = size as f32 / range_x.max(range_z);
+        // saturating casts
+        let width = ((range_x * scale).round() as u32).clamp(1, size);
+        let height = ((range_z * scale).round() as u32).clamp(1, size);
+        trace!("Rendering world with {}x{} pixels", width, height);
+        Ok(Self {
+            extents,
+            scale,
+            width,
+            height,
+        })
+    }
+
+    #[inline]
+    fn project(&self, point: Vec3) -> (f32, f32) {
+        (
+            (point.x - self.extents.left) * self.scale,
+            (point.z - self.extents.top) * self.scale,
+        )
+    }
+}
+
+#[inline]
+fn edge(a: (f32, f32), b: (f32, f32), p: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+/// Rasterise a triangle. For each pixel whose center is inside the triangle,
+/// `shade` is called with the pixel coordinates and the barycentric weights
+/// of the corners. Triangles that are edge-on (e.g. walls) cover no pixels.
+fn rasterize(view: &View, points: [Vec3; 3], mut shade: impl FnMut(u32, u32, [f32; 3])) {
+    let [a, b, c] = points.map(|point| view.project(point));
+    let area = edge(a, b, c);
+    if !area.is_normal() {
+        return;
+    }
+    let min_x = a.0.min(b.0).min(c.0).floor().max(0.0) as u32;
+    let min_y = a.1.min(b.1).min(c.1).floor().max(0.0) as u32;
+    let max_x = (a.0.max(b.0).max(c.0).ceil().max(0.0) as u32).min(view.width);
+    let max_y = (a.1.max(b.1).max(c.1).ceil().max(0.0) as u32).min(view.height);
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = (x as f32 + 0.5, y as f32 + 0.5);
+            let weights = [
+                edge(b, c, p) / area,
+                edge(c, a, p) / area,
+                edge(a, b, p) / area,
+            ];
+            if weights.iter().all(|&w| w >= 0.0) {
+                shade(x, y, weights);
+            }
+        }
+    }
+}
+
+/// The world y of the highest point drawn at each pixel.
+struct HeightBuffer {
+    heights: Vec<f32>,
+    width: u32,
+}
+
+impl HeightBuffer {
+    fn new(view: &View) -> Self {
+        Self {
+            heights: vec![f32::NEG_INFINITY; view.width as usize * view.height as usize],
+            width: view.width,
+        }
+    }
+
+    /// Returns the pixel offset if the height is above what was drawn.
+    #[inline]
+    fn test(&mut self, x: u32, y: u32, height: f32) -> Option<usize> {
+        let offset = y as usize * self.width as usize + x as usize;
+        let current = &mut self.heights[offset];
+        (height > *current).then(|| {
+            *current = height;
+            offset
+        })
+    }
+}
+
+/// Interpolate the corners' world y.
+#[inline]
+fn height(points: &[Vec3; 3], weights: [f32; 3]) -> f32 {
+    points[0].y * weights[0] + points[1].y * weights[1] + points[2].y * weights[2]
+}
+
+#[cfg(test)]
+mod tests;
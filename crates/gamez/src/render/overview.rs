@@ -0,0 +1,168 @@
+use super::{HeightBuffer, Triangle, View, height, rasterize, world_extents, world_triangles};
+use image::{Rgb, RgbImage, RgbaImage};
+use log::{debug, warn};
+use mech3ax_api_types::Color;
+use mech3ax_api_types::gamez::GameZ;
+use mech3ax_api_types::gamez::materials::Material;
+use mech3ax_api_types::gamez::model::UvCoord;
+use mech3ax_common::{Result, err};
+use std::collections::{HashMap, HashSet};
+
+/// The color of polygons whose texture wasn't provided.
+const MISSING_TEXTURE: [u8; 3] = [255, 0, 255];
+/// Texels with a lower alpha are cut out, so what is below shows through.
+const ALPHA_CUTOFF: u8 = 128;
+
+/// Options for rendering a level overview.
+#[derive(Debug, Clone, Copy)]
+pub struct OverviewOptions {
+    /// The size of the longest side of the image, in pixels.
+    pub size: u32,
+}
+
+impl Default for OverviewOptions {
+    fn default() -> Self {
+        Self { size: 1024 }
+    }
+}
+
+enum Fill<'a> {
+    Color([u8; 3]),
+    Texture(&'a RgbaImage, [UvCoord; 3]),
+}
+
+impl Fill<'_> {
+    fn sample(&self, weights: [f32; 3]) -> Option<[u8; 3]> {
+        match self {
+            Self::Color(color) => Some(*color),
+            Self::Texture(image, uvs) => {
+                let u = uvs[0].u * weights[0] + uvs[1].u * weights[1] + uvs[2].u * weights[2];
+                let v = uvs[0].v * weights[0] + uvs[1].v * weights[1] + uvs[2].v * weights[2];
+                let (width, height) = image.dimensions();
+                // nearest texel, with wrapping (saturating casts)
+                let x = ((u * width as f32).floor() as i64).rem_euclid(width as i64);
+                let y = ((v * height as f32).floor() as i64).rem_euclid(height as i64);
+                let [r, g, b, a] = image.get_pixel(x as u32, y as u32).0;
+                (a >= ALPHA_CUTOFF).then_some([r, g, b])
+            }
+        }
+    }
+}
+
+fn color_rgb(color: &Color) -> [u8; 3] {
+    // saturating casts
+    [color.r as u8, color.g as u8, color.b as u8]
+}
+
+fn triangle_fill<'a>(
+    gamez: &GameZ,
+    textures: &'a HashMap<String, RgbaImage>,
+    missing: &mut HashSet<usize>,
+    triangle: &Triangle,
+) -> Result<Option<Fill<'a>>> {
+    let polygon = &gamez.models[triangle.model_index].polygons[triangle.polygon_index];
+    let Some(polygon_material) = polygon.materials.first() else {
+        return Ok(None);
+    };
+    let material_index = polygon_material.material_index.to_usize();
+    let textured = match gamez.materials.get(material_index) {
+        Some(Material::Colored(colored)) => {
+            if colored.alpha == 0 {
+                return Ok(None);
+            }
+            return Ok(Some(Fill::Color(color_rgb(&colored.color))));
+        }
+        Some(Material::Textured(textured)) => textured,
+        None => {
+            return Err(err!(
+                "Expected model {} polygon {} material index {} < {}",
+                triangle.model_index,
+                triangle.polygon_index,
+                material_index,
+                gamez.materials.len()
+            ));
+        }
+    };
+
+    let texture_index = textured.texture_index.to_usize();
+    let Some(texture) = gamez.textures.get(texture_index) else {
+        return Err(err!(
+            "Expected material {} texture index {} < {}",
+            material_index,
+            texture_index,
+            gamez.textures.len()
+        ));
+    };
+    let Some(image) = textures
+        .get(&texture.name)
+        .filter(|image| !image.is_empty())
+    else {
+        if missing.insert(texture_index) {
+            warn!("Texture `{}` not found, drawing it flat", texture.name);
+        }
+        return Ok(Some(Fill::Color(MISSING_TEXTURE)));
+    };
+
+    let uvs = match &polygon_material.uv_coords {
+        Some(uv_coords) => {
+            let mut uvs = [UvCoord { u: 0.0, v: 0.0 }; 3];
+            for (uv, &corner) in uvs.iter_mut().zip(&triangle.corners) {
+                let Some(coord) = uv_coords.get(corner) else {
+                    return Err(err!(
+                        "Expected model {} polygon {} to have UV coordinates for each vertex",
+                        triangle.model_index,
+                        triangle.polygon_index
+                    ));
+                };
+                *uv = *coord;
+            }
+            uvs
+        }
+        None => [UvCoord { u: 0.0, v: 0.0 }; 3],
+    };
+    Ok(Some(Fill::Texture(image, uvs)))
+}
+
+/// Render a top-down overview of the level onto a black background.
+///
+/// All models are drawn, using the first material of each polygon. Textures
+/// are looked up by name, and sampled without filtering; texture cycles show
+/// their first texture. Polygons whose texture isn't provided are drawn in
+/// magenta. Lighting and vertex colors are ignored. The world area is used as
+/// the image extents (see the [module documentation](super)).
+pub fn render_overview(
+    gamez: &GameZ,
+    textures: &HashMap<String, RgbaImage>,
+    opts: &OverviewOptions,
+) -> Result<RgbImage> {
+    let triangles = world_triangles(gamez, |_| true)?;
+    let view = View::new(world_extents(gamez, &triangles)?, opts.size)?;
+
+    let mut heights = HeightBuffer::new(&view);
+    let mut image = RgbImage::new(view.width, view.height);
+    let mut missing = HashSet::new();
+    for triangle in &triangles {
+        let Some(fill) = triangle_fill(gamez, textures, &mut missing, triangle)? else {
+            continue;
+        };
+        rasterize(&view, triangle.points, |x, y, weights| {
+            let Some(color) = fill.sample(weights) else {
+                return;
+            };
+            if heights
+                .test(x, y, height(&triangle.points, weights))
+                .is_some()
+            {
+                image.put_pixel(x, y, Rgb(color));
+            }
+        });
+    }
+    debug!(
+        "Rendered {} triangles with {}x{} pixels ({} textures missing)",
+        triangles.len(),
+        view.width,
+        view.height,
+        missing.len()
+    );
+    Ok(image)
+}
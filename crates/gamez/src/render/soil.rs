@@ -0,0 +1,123 @@
+use super::{HeightBuffer, View, height, rasterize, world_extents, world_triangles};
+use image::{Rgb, RgbImage};
+use log::debug;
+use mech3ax_api_types::gamez::GameZ;
+use mech3ax_api_types::gamez::materials::{Material, Soil};
+use mech3ax_api_types::gamez::nodes::NodeFlags;
+use mech3ax_common::{Result, err};
+
+/// The color of pixels without terrain.
+pub const SOIL_BACKGROUND: [u8; 3] = [0, 0, 0];
+
+/// Options for rasterising a soil map.
+#[derive(Debug, Clone, Copy)]
+pub struct SoilMapOptions {
+    /// The size of the longest side of the image, in pixels.
+    pub size: u32,
+}
+
+impl Default for SoilMapOptions {
+    fn default() -> Self {
+        Self { size: 1024 }
+    }
+}
+
+/// How much of the soil map a soil type covers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoilCoverage {
+    pub soil: Soil,
+    pub color: [u8; 3],
+    pub pixels: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SoilMap {
+    pub image: RgbImage,
+    /// Every soil type, in order, even if it doesn't cover any pixels.
+    pub legend: Vec<SoilCoverage>,
+}
+
+/// The color of a soil type on the soil map.
+pub const fn soil_color(soil: Soil) -> [u8; 3] {
+    match soil {
+        Soil::Default => [128, 128, 128],
+        Soil::Water => [40, 90, 200],
+        Soil::Seafloor => [30, 60, 110],
+        Soil::Quicksand => [220, 200, 120],
+        Soil::Lava => [230, 70, 20],
+        Soil::Fire => [255, 160, 0],
+        Soil::Dirt => [130, 90, 50],
+        Soil::Mud => [90, 60, 35],
+        Soil::Grass => [60, 150, 50],
+        Soil::Concrete => [180, 180, 170],
+        Soil::Snow => [245, 245, 250],
+        Soil::Mech => [200, 50, 200],
+        Soil::Silt => [150, 140, 110],
+        Soil::NoSlip => [240, 230, 60],
+    }
+}
+
+fn soils() -> impl Iterator<Item = Soil> {
+    (0..).map_while(Soil::from_bits)
+}
+
+/// Rasterise the terrain onto a black background, colored by soil type.
+///
+/// Terrain is the models of nodes with the terrain flag. Each polygon is
+/// colored by the soil of its first material, and where polygons overlap, the
+/// highest wins. The world area is used as the image extents (see the
+/// [module documentation](super)).
+pub fn render_soil_map(gamez: &GameZ, opts: &SoilMapOptions) -> Result<SoilMap> {
+    let triangles = world_triangles(gamez, |node| node.flags.contains(NodeFlags::TERRAIN))?;
+    let view = View::new(world_extents(gamez, &triangles)?, opts.size)?;
+
+    let mut heights = HeightBuffer::new(&view);
+    let mut pixels: Vec<Option<Soil>> = vec![None; heights.heights.len()];
+    for triangle in &triangles {
+        let polygon = &gamez.models[triangle.model_index].polygons[triangle.polygon_index];
+        let Some(material) = polygon.materials.first() else {
+            continue;
+        };
+        let material_index = material.material_index.to_usize();
+        let soil = match gamez.materials.get(material_index) {
+            Some(Material::Textured(textured)) => textured.soil,
+            Some(Material::Colored(colored)) => colored.soil,
+            None => {
+                return Err(err!(
+                    "Expected model {} polygon {} material index {} < {}",
+                    triangle.model_index,
+                    triangle.polygon_index,
+                    material_index,
+                    gamez.materials.len()
+                ));
+            }
+        };
+        rasterize(&view, triangle.points, |x, y, weights| {
+            if let Some(offset) = heights.test(x, y, height(&triangle.points, weights)) {
+                pixels[offset] = Some(soil);
+            }
+        });
+    }
+
+    let mut legend: Vec<SoilCoverage> = soils()
+        .map(|soil| SoilCoverage {
+            soil,
+            color: soil_color(soil),
+            pixels: 0,
+        })
+        .collect();
+    let mut image = RgbImage::from_pixel(view.width, view.height, Rgb(SOIL_BACKGROUND));
+    for (pixel, soil) in image.pixels_mut().zip(pixels) {
+        if let Some(soil) = soil {
+            *pixel = Rgb(soil_color(soil));
+            legend[soil as usize].pixels += 1;
+        }
+    }
+    debug!(
+        "Rendered {} terrain triangles with {}x{} pixels",
+        triangles.len(),
+        view.width,
+        view.height
+    );
+    Ok(SoilMap { image, legend })
+}
@@ -0,0 +1,280 @@
+use super::*;
+use image::{Rgba, RgbaImage};
+use mech3ax_api_types::gamez::materials::{ColoredMaterial, Material, Soil, TexturedMaterial};
+use mech3ax_api_types::gamez::model::{
+    FacadeMode, Model, ModelFlags, Polygon, PolygonMaterial, UvCoord,
+};
+use mech3ax_api_types::gamez::nodes::{ActiveBoundingBox, BoundingBox, NodeFlags};
+use mech3ax_api_types::gamez::{GameZ, GameZMetadata, Texture};
+use mech3ax_api_types::{Color, Count, IndexO, IndexR};
+use mech3ax_timestamp::unix::from_timestamp;
+use std::collections::HashMap;
+
+fn v(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+fn colored(soil: Soil) -> Material {
+    Material::Colored(ColoredMaterial {
+        color: Color::BLACK,
+        alpha: 255,
+        soil,
+    })
+}
+
+fn polygon(vertex_indices: Vec<u32>, flags: PolygonFlags, material_index: usize) -> Polygon {
+    Polygon {
+        flags,
+        priority: 0,
+        zone_set: Vec::new(),
+        vertex_indices,
+        normal_indices: None,
+        vertex_colors: Vec::new(),
+        materials: vec![PolygonMaterial {
+            material_index: IndexR::from_usize(material_index).unwrap(),
+            uv_coords: None,
+        }],
+        vertex_indices_ptr: 0,
+        normal_indices_ptr: 0,
+        uvs_ptr: 0,
+        vertex_colors_ptr: 0,
+        matl_refs_ptr: 0,
+        materials_ptr: 0,
+    }
+}
+
+/// A quad from `(x0, z0)` to `(x1, z1)` at height `y`, as a fan.
+fn quad(x0: f32, z0: f32, x1: f32, z1: f32, y: f32, material_index: usize) -> Model {
+    model(
+        vec![v(x0, y, z0), v(x1, y, z0), v(x1, y, z1), v(x0, y, z1)],
+        vec![polygon(
+            vec![0, 1, 2, 3],
+            PolygonFlags::empty(),
+            material_index,
+        )],
+    )
+}
+
+fn model(vertices: Vec<Vec3>, polygons: Vec<Polygon>) -> Model {
+    Model {
+        model_type: ModelType::Default,
+        facade_mode: FacadeMode::CylindricalY,
+        flags: ModelFlags::empty(),
+        parent_count: 1,
+        vertices,
+        normals: Vec::new(),
+        morphs: Vec::new(),
+        lights: Vec::new(),
+        polygons,
+        texture_scroll: UvCoord { u: 0.0, v: 0.0 },
+        bbox_mid: Vec3::DEFAULT,
+        bbox_diag: 0.0,
+        polygons_ptr: 0,
+        vertices_ptr: 0,
+        normals_ptr: 0,
+        lights_ptr: 0,
+        morphs_ptr: 0,
+        material_refs_ptr: 0,
+    }
+}
+
+fn node(model_index: usize, flags: NodeFlags) -> Node {
+    Node {
+        name: "node".to_string(),
+        flags,
+        update_flags: 0,
+        zone_id: 0,
+        model_index: IndexO::from_usize(model_index).unwrap(),
+        area_partition: None,
+        virtual_partition: None,
+        parent_indices: Vec::new(),
+        child_indices: Vec::new(),
+        active_bbox: ActiveBoundingBox::Node,
+        node_bbox: BoundingBox::EMPTY,
+        model_bbox: BoundingBox::EMPTY,
+        child_bbox: BoundingBox::EMPTY,
+        field192: 0,
+        field196: 0,
+        field200: 0,
+        field204: 0,
+        data: NodeData::Empty,
+        data_ptr: 0,
+        parent_array_ptr: 0,
+        child_array_ptr: 0,
+        index: 0,
+    }
+}
+
+fn gamez(models: Vec<Model>, nodes: Vec<Node>) -> GameZ {
+    GameZ {
+        textures: Vec::new(),
+        materials: vec![
+            colored(Soil::Water),
+            colored(Soil::Lava),
+            colored(Soil::Grass),
+        ],
+        models,
+        nodes,
+        metadata: GameZMetadata {
+            datetime: from_timestamp(0x3A000000),
+            material_array_size: Count::from_i16(8).unwrap(),
+            model_array_size: Count::from_i16(8).unwrap(),
+            node_array_size: Count::from_i16(8).unwrap(),
+            node_last_free: 0,
+            image_ptrs: None,
+        },
+    }
+}
+
+fn coverage(map: &SoilMap, soil: Soil) -> u64 {
+    map.legend[soil as usize].pixels
+}
+
+#[test]
+fn triangulate_fan_and_strip() {
+    let fan: Vec<_> = triangulate(5, false).collect();
+    assert_eq!(fan, vec![[0, 1, 2], [0, 2, 3], [0, 3, 4]]);
+    let strip: Vec<_> = triangulate(5, true).collect();
+    assert_eq!(strip, vec![[0, 1, 2], [2, 1, 3], [2, 3, 4]]);
+    assert_eq!(triangulate(2, false).count(), 0);
+}
+
+#[test]
+fn soil_map_highest_terrain_wins() {
+    let gamez = gamez(
+        vec![
+            quad(0.0, 0.0, 20.0, 10.0, 0.0, 0),
+            quad(10.0, 0.0, 20.0, 10.0, 5.0, 1),
+            // not terrain, so ignored even though it's highest
+            quad(0.0, 0.0, 20.0, 10.0, 10.0, 2),
+        ],
+        vec![
+            node(0, NodeFlags::TERRAIN),
+            node(1, NodeFlags::TERRAIN),
+            node(2, NodeFlags::empty()),
+        ],
+    );
+    let map = render_soil_map(&gamez, &SoilMapOptions { size: 20 }).unwrap();
+    assert_eq!(map.image.dimensions(), (20, 10));
+    assert_eq!(map.image.get_pixel(2, 5).0, soil_color(Soil::Water));
+    assert_eq!(map.image.get_pixel(17, 5).0, soil_color(Soil::Lava));
+
+    assert_eq!(map.legend.len(), 14);
+    assert_eq!(coverage(&map, Soil::Water), 100);
+    assert_eq!(coverage(&map, Soil::Lava), 100);
+    assert_eq!(coverage(&map, Soil::Grass), 0);
+}
+
+#[test]
+fn soil_map_skips_walls() {
+    let wall = model(
+        vec![
+            v(0.0, 0.0, 5.0),
+            v(10.0, 0.0, 5.0),
+            v(10.0, 20.0, 5.0),
+            v(0.0, 20.0, 5.0),
+        ],
+        vec![polygon(vec![0, 1, 3, 2], PolygonFlags::TRI_STRIP, 1)],
+    );
+    let gamez = gamez(
+        vec![quad(0.0, 0.0, 10.0, 10.0, 0.0, 0), wall],
+        vec![node(0, NodeFlags::TERRAIN), node(1, NodeFlags::TERRAIN)],
+    );
+    let map = render_soil_map(&gamez, &SoilMapOptions { size: 10 }).unwrap();
+    assert_eq!(coverage(&map, Soil::Water), 100);
+    assert_eq!(coverage(&map, Soil::Lava), 0);
+}
+
+#[test]
+fn soil_map_invalid_indices_fail() {
+    let mut gamez = gamez(
+        vec![quad(0.0, 0.0, 10.0, 10.0, 0.0, 5)],
+        vec![node(0, NodeFlags::TERRAIN)],
+    );
+    assert!(render_soil_map(&gamez, &SoilMapOptions::default()).is_err());
+    gamez.models[0].polygons[0].vertex_indices[0] = 9;
+    assert!(render_soil_map(&gamez, &SoilMapOptions::default()).is_err());
+}
+
+fn textured(texture_index: usize) -> Material {
+    Material::Textured(TexturedMaterial {
+        texture_index: IndexR::from_usize(texture_index).unwrap(),
+        soil: Soil::Default,
+        cycle: None,
+        flag: false,
+    })
+}
+
+fn overview_gamez(models: Vec<Model>) -> GameZ {
+    let nodes = (0..models.len())
+        .map(|index| node(index, NodeFlags::empty()))
+        .collect();
+    let mut gamez = gamez(models, nodes);
+    gamez.textures = vec![
+        Texture {
+            name: "stripes".to_string(),
+            mip_index: IndexO::NONE,
+        },
+        Texture {
+            name: "missing".to_string(),
+            mip_index: IndexO::NONE,
+        },
+    ];
+    gamez.materials = vec![
+        Material::Colored(ColoredMaterial {
+            color: Color {
+                r: 0.0,
+                g: 255.0,
+                b: 0.0,
+            },
+            alpha: 255,
+            soil: Soil::Default,
+        }),
+        textured(0),
+        textured(1),
+    ];
+    gamez
+}
+
+/// A 2x1 texture, red on the left, and transparent on the right.
+fn textures() -> HashMap<String, RgbaImage> {
+    let mut image = RgbaImage::new(2, 1);
+    image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+    image.put_pixel(1, 0, Rgba([0, 0, 255, 0]));
+    HashMap::from([("stripes".to_string(), image)])
+}
+
+fn with_uvs(mut model: Model) -> Model {
+    let uvs = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+    model.polygons[0].materials[0].uv_coords =
+        Some(uvs.iter().map(|&(u, v)| UvCoord { u, v }).collect());
+    model
+}
+
+#[test]
+fn overview_draws_textures_and_colors() {
+    let gamez = overview_gamez(vec![
+        quad(0.0, 0.0, 20.0, 10.0, 0.0, 0),
+        with_uvs(quad(0.0, 0.0, 10.0, 10.0, 5.0, 1)),
+        quad(10.0, 0.0, 20.0, 5.0, 5.0, 2),
+    ]);
+    let image = render_overview(&gamez, &textures(), &OverviewOptions { size: 20 }).unwrap();
+    assert_eq!(image.dimensions(), (20, 10));
+    // the left half of the texture is red
+    assert_eq!(image.get_pixel(2, 5).0, [255, 0, 0]);
+    // the right half is transparent, so the colored quad below shows
+    assert_eq!(image.get_pixel(7, 5).0, [0, 255, 0]);
+    // the texture wasn't provided
+    assert_eq!(image.get_pixel(15, 2).0, [255, 0, 255]);
+    assert_eq!(image.get_pixel(15, 7).0, [0, 255, 0]);
+}
+
+#[test]
+fn overview_requires_uvs_for_each_vertex() {
+    let mut model = with_uvs(quad(0.0, 0.0, 10.0, 10.0, 0.0, 1));
+    if let Some(uvs) = &mut model.polygons[0].materials[0].uv_coords {
+        uvs.truncate(2);
+    }
+    let gamez = overview_gamez(vec![model]);
+    assert!(render_overview(&gamez, &textures(), &OverviewOptions::default()).is_err());
+}
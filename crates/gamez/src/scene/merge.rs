@@ -0,0 +1,323 @@
+use super::{SceneGraph, fix_ptr, remap_node, to_index};
+use crate::gamez::partitions::plan_partitions;
+use log::{debug, warn};
+use mech3ax_api_types::gamez::GameZ;
+use mech3ax_api_types::gamez::materials::Material;
+use mech3ax_api_types::gamez::nodes::NodeData;
+use mech3ax_api_types::{Count, IndexO, IndexR};
+use mech3ax_common::{GameType, Result, err};
+use mech3ax_types::Ptr;
+
+/// Maps source indices to target indices, in order of first use.
+struct IndexMap {
+    map: Vec<Option<usize>>,
+    used: Vec<usize>,
+}
+
+impl IndexMap {
+    fn new(len: usize) -> Self {
+        Self {
+            map: vec![None; len],
+            used: Vec::new(),
+        }
+    }
+
+    fn use_index(&mut self, index: usize, what: &str) -> Result<()> {
+        let len = self.map.len();
+        let Some(entry) = self.map.get_mut(index) else {
+            return Err(err!("Expected source {} index {} < {}", what, index, len));
+        };
+        if entry.is_none() {
+            // placeholder until the target index is known
+            *entry = Some(usize::MAX);
+            self.used.push(index);
+        }
+        Ok(())
+    }
+
+    fn get(&self, index: usize) -> Result<IndexR> {
+        let Some(target) = self.map[index].filter(|&target| target != usize::MAX) else {
+            return Err(err!("Expected source index {} to be mapped", index));
+        };
+        to_index(target)
+    }
+}
+
+impl SceneGraph {
+    /// Copies a node and its descendants from another GameZ, along with the
+    /// models, materials, and textures they use, and returns the index of the
+    /// copied node.
+    ///
+    /// Textures are deduplicated by name; a texture with the same name in the
+    /// target is used instead of copying it. New textures are logged, since
+    /// their images must also be added to the target's texture packages.
+    /// Materials and models are always copied. References between copied
+    /// nodes point to the copies, while references to source nodes outside
+    /// the subtree are dropped. Copied nodes that have an area partition are
+    /// added to the target world's partitions (see
+    /// [`add_to_partitions`](crate::gamez::partitions::add_to_partitions)), and
+    /// copied nodes in the source world's light or sound lists are added to
+    /// the target world's lists.
+    pub fn merge_subtree(
+        &mut self,
+        source: &SceneGraph,
+        index: usize,
+        parent: Option<usize>,
+        game: GameType,
+    ) -> Result<usize> {
+        source.check_index(index)?;
+        if let Some(parent) = parent {
+            self.check_index(parent)?;
+        }
+        let src = &source.gamez;
+        let subtree = source.descendants(index);
+
+        // gather what is used, so nothing is copied if the source is invalid
+        let mut models = IndexMap::new(src.models.len());
+        for &i in &subtree {
+            if let Some(model_index) = src.nodes[i].model_index.to_usize() {
+                models.use_index(model_index, "model")?;
+            }
+        }
+        let mut materials = IndexMap::new(src.materials.len());
+        for &model_index in &models.used {
+            let polygons = &src.models[model_index].polygons;
+            for material in polygons.iter().flat_map(|polygon| &polygon.materials) {
+                materials.use_index(material.material_index.to_usize(), "material")?;
+            }
+        }
+        let mut textures = IndexMap::new(src.textures.len());
+        for &material_index in &materials.used {
+            if let Material::Textured(textured) = &src.materials[material_index] {
+                textures.use_index(textured.texture_index.to_usize(), "texture")?;
+                for texture_index in textured.cycle.iter().flat_map(|c| &c.texture_indices) {
+                    textures.use_index(texture_index.to_usize(), "texture")?;
+                }
+            }
+        }
+        // mip textures may use further textures
+        let mut i = 0;
+        while let Some(&texture_index) = textures.used.get(i) {
+            if let Some(mip_index) = src.textures[texture_index].mip_index.to_usize() {
+                textures.use_index(mip_index, "texture")?;
+            }
+            i += 1;
+        }
+
+        // check everything that can fail, so the target isn't half-merged
+        let node_offset = self.len();
+        to_index(node_offset + subtree.len() - 1)?;
+        let target = &self.gamez;
+        let texture_len = target.textures.len() + textures.used.len();
+        if IndexR::from_usize(texture_len.saturating_sub(1)).is_none() {
+            return Err(err!("Too many GameZ textures ({})", texture_len));
+        }
+        let material_array_size = array_size(
+            target.metadata.material_array_size,
+            target.materials.len() + materials.used.len(),
+            "materials",
+        )?;
+        let model_array_size = array_size(
+            target.metadata.model_array_size,
+            target.models.len() + models.used.len(),
+            "models",
+        )?;
+
+        let mut node_map: Vec<Option<usize>> = vec![None; src.nodes.len()];
+        for (i, &old) in subtree.iter().enumerate() {
+            node_map[old] = Some(node_offset + i);
+        }
+        let lights_sounds = plan_lights_sounds(target, src, &node_map)?;
+        // the target only needs a world if copied nodes are partitioned
+        let partitioned = subtree
+            .iter()
+            .any(|&old| src.nodes[old].area_partition.is_some());
+        let additions = if partitioned {
+            let added = subtree
+                .iter()
+                .enumerate()
+                .map(|(i, &old)| (node_offset + i, &src.nodes[old]));
+            Some(plan_partitions(&target.nodes, added)?)
+        } else {
+            None
+        };
+
+        let target = &mut self.gamez;
+        let texture_offset = target.textures.len();
+        let mut texture_count = 0;
+        for &texture_index in &textures.used {
+            let name = &src.textures[texture_index].name;
+            let existing = target.textures.iter().position(|t| &t.name == name);
+            let mapped = existing.unwrap_or_else(|| {
+                warn!(
+                    "WARN: texture `{}` isn't in the target, and must be added to its texture packages",
+                    name
+                );
+                let mut texture = src.textures[texture_index].clone();
+                texture.mip_index = IndexO::NONE;
+                target.textures.push(texture);
+                // CS stores an image pointer for each texture
+                if let Some(image_ptrs) = &mut target.metadata.image_ptrs {
+                    let image_ptr = src
+                        .metadata
+                        .image_ptrs
+                        .as_ref()
+                        .and_then(|image_ptrs| image_ptrs.get(texture_index))
+                        .copied()
+                        .unwrap_or(Ptr::NON_NULL.0);
+                    image_ptrs.push(image_ptr);
+                }
+                texture_count += 1;
+                target.textures.len() - 1
+            });
+            textures.map[texture_index] = Some(mapped);
+        }
+        for &texture_index in &textures.used {
+            let mapped = textures.map[texture_index].unwrap_or(usize::MAX);
+            if mapped < texture_offset {
+                continue;
+            }
+            if let Some(mip_index) = src.textures[texture_index].mip_index.to_usize() {
+                let mip = textures.get(mip_index)?.to_usize();
+                target.textures[mapped].mip_index = IndexO::from_usize(mip).unwrap_or(IndexO::NONE);
+            }
+        }
+
+        for &material_index in &materials.used {
+            let mut material = src.materials[material_index].clone();
+            if let Material::Textured(textured) = &mut material {
+                textured.texture_index = textures.get(textured.texture_index.to_usize())?;
+                if let Some(cycle) = &mut textured.cycle {
+                    for texture_index in &mut cycle.texture_indices {
+                        *texture_index = textures.get(texture_index.to_usize())?;
+                    }
+                }
+            }
+            target.materials.push(material);
+            materials.map[material_index] = Some(target.materials.len() - 1);
+        }
+
+        for &model_index in &models.used {
+            let mut model = src.models[model_index].clone();
+            // counted when the nodes are added
+            model.parent_count = 0;
+            for polygon in &mut model.polygons {
+                for material in &mut polygon.materials {
+                    material.material_index = materials.get(material.material_index.to_usize())?;
+                }
+            }
+            target.models.push(model);
+            models.map[model_index] = Some(target.models.len() - 1);
+        }
+        target.metadata.material_array_size = material_array_size;
+        target.metadata.model_array_size = model_array_size;
+
+        for &old in &subtree {
+            let mut node = src.nodes[old].clone();
+            node.parent_indices
+                .retain(|p| node_map[p.to_usize()].is_some());
+            remap_node(&mut node, |i| node_map[i]);
+            if let Some(model_index) = node.model_index.to_usize() {
+                let mapped = models.get(model_index)?.to_usize();
+                node.model_index = IndexO::from_usize(mapped).unwrap_or(IndexO::NONE);
+            }
+            self.acquire_model(node.model_index);
+            self.gamez.nodes.push(node);
+        }
+        self.dirty = true;
+
+        if let Some(lights_sounds) = lights_sounds {
+            lights_sounds.apply(&mut self.gamez);
+        }
+        if let Some(parent) = parent {
+            self.link(parent, to_index(node_offset)?)?;
+        }
+        if let Some(additions) = additions {
+            additions.apply(&mut self.gamez.nodes, game);
+        }
+
+        debug!(
+            "Merged {} nodes, {} models, {} materials, {} new textures",
+            subtree.len(),
+            models.used.len(),
+            materials.used.len(),
+            texture_count
+        );
+        Ok(node_offset)
+    }
+}
+
+/// Copied nodes to add to the target world's light and sound lists.
+struct LightsSounds {
+    world_index: usize,
+    lights: Vec<IndexR>,
+    sounds: Vec<IndexR>,
+}
+
+/// Finds copied nodes in the source world's light and sound lists, and the
+/// target world to add them to.
+fn plan_lights_sounds(
+    target: &GameZ,
+    source: &GameZ,
+    node_map: &[Option<usize>],
+) -> Result<Option<LightsSounds>> {
+    let Some(src_world) = source.nodes.iter().find_map(|node| match &node.data {
+        NodeData::World(world) => Some(world),
+        _ => None,
+    }) else {
+        return Ok(None);
+    };
+    let mapped = |indices: &[IndexR]| -> Result<Vec<IndexR>> {
+        indices
+            .iter()
+            .filter_map(|i| node_map.get(i.to_usize()).copied().flatten())
+            .map(to_index)
+            .collect()
+    };
+    let lights = mapped(&src_world.light_indices)?;
+    let sounds = mapped(&src_world.sound_indices)?;
+    if lights.is_empty() && sounds.is_empty() {
+        return Ok(None);
+    }
+
+    let Some(world_index) = target
+        .nodes
+        .iter()
+        .position(|node| matches!(node.data, NodeData::World(_)))
+    else {
+        return Err(err!(
+            "Expected a target world node for the lights and sounds"
+        ));
+    };
+    Ok(Some(LightsSounds {
+        world_index,
+        lights,
+        sounds,
+    }))
+}
+
+impl LightsSounds {
+    fn apply(self, target: &mut GameZ) {
+        let NodeData::World(world) = &mut target.nodes[self.world_index].data else {
+            unreachable!()
+        };
+        world.light_indices.extend(self.lights);
+        world.sound_indices.extend(self.sounds);
+        let ptrs = &mut world.ptrs;
+        fix_ptr(&mut ptrs.light_nodes_ptr, world.light_indices.len());
+        fix_ptr(&mut ptrs.light_data_ptr, world.light_indices.len());
+        fix_ptr(&mut ptrs.sound_nodes_ptr, world.sound_indices.len());
+        fix_ptr(&mut ptrs.sound_data_ptr, world.sound_indices.len());
+    }
+}
+
+/// The model and material arrays must be larger than the number of entries.
+fn array_size(array_size: Count, len: usize, what: &str) -> Result<Count> {
+    if array_size.to_usize() > len {
+        return Ok(array_size);
+    }
+    let Some(size) = Count::from_usize(len + 1) else {
+        return Err(err!("Too many GameZ {} ({})", what, len));
+    };
+    Ok(size)
+}
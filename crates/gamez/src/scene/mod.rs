@@ -0,0 +1,587 @@
+//! Navigate and edit GameZ nodes, while keeping references consistent.
+//!
+//! Nodes refer to each other by index, not only via their parent and child
+//! indices, but also from cameras, lights, and the world's light, sound, and
+//! partition lists. Nodes also increase their model's parent count. The scene
+//! graph updates all of these when nodes are inserted, removed, reparented, or
+//! cloned, so the edited GameZ can be written back.
+mod merge;
+#[cfg(test)]
+mod tests;
+
+use crate::nodes::object3d::math::{combine, node_matrix};
+use mech3ax_api_types::gamez::GameZ;
+use mech3ax_api_types::gamez::nodes::{Node, NodeData};
+use mech3ax_api_types::{AffineMatrix, Count, IndexO, IndexR};
+use mech3ax_common::{GameType, Result, err};
+use mech3ax_types::Ptr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    Active,
+    Done,
+}
+
+/// A GameZ file's nodes as a graph.
+#[derive(Debug, Clone)]
+pub struct SceneGraph {
+    gamez: GameZ,
+    dirty: bool,
+}
+
+impl SceneGraph {
+    /// Validates all node and model indices, and that no node is its own
+    /// ancestor.
+    pub fn new(gamez: GameZ) -> Result<Self> {
+        let node_count = gamez.nodes.len();
+        let model_count = gamez.models.len();
+        for (index, node) in gamez.nodes.iter().enumerate() {
+            if let Some(model_index) = node.model_index.to_usize()
+                && model_index >= model_count
+            {
+                return Err(err!(
+                    "Expected node {} model index {} < {}",
+                    index,
+                    model_index,
+                    model_count
+                ));
+            }
+            for reference in node_refs(node) {
+                if reference >= node_count {
+                    return Err(err!(
+                        "Expected node {} reference {} < {}",
+                        index,
+                        reference,
+                        node_count
+                    ));
+                }
+            }
+        }
+        let mut visit = vec![Visit::New; node_count];
+        for index in 0..node_count {
+            check_cycles(&gamez.nodes, &mut visit, index)?;
+        }
+        Ok(Self {
+            gamez,
+            dirty: false,
+        })
+    }
+
+    #[inline]
+    pub fn gamez(&self) -> &GameZ {
+        &self.gamez
+    }
+
+    /// Returns the GameZ data. If nodes were added or removed, the node
+    /// array metadata is updated to fit the nodes.
+    ///
+    /// In MW and RC, the last free node is set to be recalculated when
+    /// writing. How PM and CS calculate it isn't known, so the previous value
+    /// is kept, and may be stale.
+    pub fn into_gamez(self, game: GameType) -> GameZ {
+        let mut gamez = self.gamez;
+        if self.dirty {
+            let len = gamez.nodes.len();
+            let metadata = &mut gamez.metadata;
+            if metadata.node_array_size.to_usize() < len {
+                // the node count was checked when linking or inserting
+                metadata.node_array_size = Count::from_usize(len).unwrap_or(Count::EMPTY);
+            }
+            match game {
+                GameType::MW | GameType::RC => metadata.node_last_free = -1,
+                GameType::PM | GameType::CS => {}
+            }
+        }
+        gamez
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.gamez.nodes.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.gamez.nodes.is_empty()
+    }
+
+    #[inline]
+    pub fn node(&self, index: usize) -> Option<&Node> {
+        self.gamez.nodes.get(index)
+    }
+
+    /// Edit a node's data. To keep the graph consistent, use the scene graph
+    /// methods to change the node's parents, children, or model instead.
+    #[inline]
+    pub fn node_mut(&mut self, index: usize) -> Option<&mut Node> {
+        self.gamez.nodes.get_mut(index)
+    }
+
+    /// The index of the first node with this name.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.gamez.nodes.iter().position(|node| node.name == name)
+    }
+
+    /// The indices of all nodes with this name.
+    pub fn find_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.gamez
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(move |(_, node)| node.name == name)
+            .map(|(index, _)| index)
+    }
+
+    /// The indices of all nodes without parents.
+    pub fn roots(&self) -> impl Iterator<Item = usize> + '_ {
+        self.gamez
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.parent_indices.is_empty())
+            .map(|(index, _)| index)
+    }
+
+    pub fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.gamez
+            .nodes
+            .get(index)
+            .into_iter()
+            .flat_map(|node| node.child_indices.iter().map(|child| child.to_usize()))
+    }
+
+    pub fn parents(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.gamez
+            .nodes
+            .get(index)
+            .into_iter()
+            .flat_map(|node| node.parent_indices.iter().map(|parent| parent.to_usize()))
+    }
+
+    /// The node's ancestors via each node's first parent, nearest first.
+    pub fn ancestors(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        ancestors(&self.gamez.nodes, index)
+    }
+
+    /// The node and its descendants via the child indices, in pre-order.
+    /// Nodes reachable via several paths are only listed once.
+    pub fn descendants(&self, index: usize) -> Vec<usize> {
+        let nodes = &self.gamez.nodes;
+        let mut seen = vec![false; nodes.len()];
+        let mut result = Vec::new();
+        let mut stack = vec![index];
+        while let Some(current) = stack.pop() {
+            if current >= nodes.len() || seen[current] {
+                continue;
+            }
+            seen[current] = true;
+            result.push(current);
+            stack.extend(
+                nodes[current]
+                    .child_indices
+                    .iter()
+                    .rev()
+                    .map(|child| child.to_usize()),
+            );
+        }
+        result
+    }
+
+    /// The node's world-space transform, combining the `Object3d` transforms
+    /// of the node and its ancestors (via each node's first parent).
+    pub fn world_transform(&self, index: usize) -> Result<AffineMatrix> {
+        self.check_index(index)?;
+        Ok(world_transform(&self.gamez.nodes, index))
+    }
+
+    /// Appends a node, optionally as a child of `parent`, and returns its
+    /// index. The node must not have parent or child indices yet; use
+    /// [`SceneGraph::reparent`] to link it further.
+    pub fn insert(&mut self, mut node: Node, parent: Option<usize>) -> Result<usize> {
+        if !node.parent_indices.is_empty() || !node.child_indices.is_empty() {
+            return Err(err!(
+                "Expected node `{}` to have no parent or child indices",
+                node.name
+            ));
+        }
+        let index = self.len();
+        for reference in node_refs(&node) {
+            if reference >= index {
+                return Err(err!(
+                    "Expected node `{}` reference {} < {}",
+                    node.name,
+                    reference,
+                    index
+                ));
+            }
+        }
+        if let Some(parent) = parent {
+            self.check_index(parent)?;
+        }
+        let new_index = to_index(index)?;
+        let model_index = node.model_index;
+        self.check_model(model_index)?;
+        fix_node_ptrs(&mut node);
+
+        self.gamez.nodes.push(node);
+        self.acquire_model(model_index);
+        if let Some(parent) = parent {
+            self.link(parent, new_index)?;
+        }
+        self.dirty = true;
+        Ok(index)
+    }
+
+    /// Removes the node and its descendants, and returns how many nodes were
+    /// removed. References to removed nodes from other nodes' parent, child,
+    /// light, and world lists are dropped, and the remaining indices are
+    /// shifted down. Fails if a remaining camera references a removed node.
+    ///
+    /// Models are kept, even if no node references them any more.
+    pub fn remove(&mut self, index: usize) -> Result<usize> {
+        self.check_index(index)?;
+        let removed = self.descendants(index);
+        let mut keep = vec![true; self.len()];
+        for &i in &removed {
+            keep[i] = false;
+        }
+
+        for (i, node) in self.gamez.nodes.iter().enumerate() {
+            if !keep[i] {
+                continue;
+            }
+            if let NodeData::Camera(camera) = &node.data {
+                let refs = [
+                    camera.world_index,
+                    camera.window_index,
+                    camera.focus_node_xy,
+                    camera.focus_node_xz,
+                ];
+                for reference in refs.into_iter().filter_map(IndexO::to_usize) {
+                    if !keep[reference] {
+                        return Err(err!(
+                            "Expected node {} to not be referenced by camera node {}",
+                            reference,
+                            i
+                        ));
+                    }
+                }
+            }
+        }
+
+        for &i in &removed {
+            let model_index = self.gamez.nodes[i].model_index;
+            self.release_model(model_index);
+        }
+
+        let mut map = Vec::with_capacity(keep.len());
+        let mut next = 0;
+        for &k in &keep {
+            if k {
+                map.push(Some(next));
+                next += 1;
+            } else {
+                map.push(None);
+            }
+        }
+
+        let mut keep_iter = keep.iter();
+        self.gamez
+            .nodes
+            .retain(|_| keep_iter.next().copied().unwrap_or(true));
+        for node in &mut self.gamez.nodes {
+            remap_node(node, |i| map[i]);
+        }
+        self.dirty = true;
+        Ok(removed.len())
+    }
+
+    /// Detaches the node from all its parents, and optionally attaches it to
+    /// `parent`. Fails if `parent` is the node or one of its descendants.
+    pub fn reparent(&mut self, index: usize, parent: Option<usize>) -> Result<()> {
+        self.check_index(index)?;
+        if let Some(parent) = parent {
+            self.check_index(parent)?;
+            if self.descendants(index).contains(&parent) {
+                return Err(err!(
+                    "Expected node {} to not be the parent of its ancestor {}",
+                    parent,
+                    index
+                ));
+            }
+        }
+        let child = to_index(index)?;
+
+        let old_parents = std::mem::take(&mut self.gamez.nodes[index].parent_indices);
+        for old_parent in old_parents {
+            let node = &mut self.gamez.nodes[old_parent.to_usize()];
+            node.child_indices.retain(|&c| c != child);
+            fix_node_ptrs(node);
+        }
+        fix_node_ptrs(&mut self.gamez.nodes[index]);
+
+        if let Some(parent) = parent {
+            self.link(parent, child)?;
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Sets the node's model, updating the models' parent counts.
+    pub fn set_model(&mut self, index: usize, model_index: IndexO) -> Result<()> {
+        self.check_index(index)?;
+        self.check_model(model_index)?;
+        let previous = std::mem::replace(&mut self.gamez.nodes[index].model_index, model_index);
+        self.release_model(previous);
+        self.acquire_model(model_index);
+        Ok(())
+    }
+
+    /// Appends a copy of the node and its descendants, optionally as a child
+    /// of `parent`, and returns the index of the copied node.
+    ///
+    /// References between copied nodes point to the copies. Other references,
+    /// e.g. a camera's world, are kept, except for parents outside the
+    /// subtree.
+    pub fn clone_subtree(&mut self, index: usize, parent: Option<usize>) -> Result<usize> {
+        self.check_index(index)?;
+        if let Some(parent) = parent {
+            self.check_index(parent)?;
+        }
+        let subtree = self.descendants(index);
+        let offset = self.len();
+        let mut map: Vec<Option<usize>> = vec![None; offset];
+        for (i, &old) in subtree.iter().enumerate() {
+            map[old] = Some(offset + i);
+        }
+        // check the largest new index up front, so no partial copy is left
+        to_index(offset + subtree.len() - 1)?;
+
+        for &old in &subtree {
+            let mut node = self.gamez.nodes[old].clone();
+            node.parent_indices.retain(|p| map[p.to_usize()].is_some());
+            remap_node(&mut node, |i| Some(map[i].unwrap_or(i)));
+            self.acquire_model(node.model_index);
+            self.gamez.nodes.push(node);
+        }
+        if let Some(parent) = parent {
+            self.link(parent, to_index(offset)?)?;
+        }
+        self.dirty = true;
+        Ok(offset)
+    }
+
+    fn check_index(&self, index: usize) -> Result<()> {
+        if index < self.len() {
+            Ok(())
+        } else {
+            Err(err!("Expected node index {} < {}", index, self.len()))
+        }
+    }
+
+    fn check_model(&self, model_index: IndexO) -> Result<()> {
+        match model_index.to_usize() {
+            Some(i) if i >= self.gamez.models.len() => Err(err!(
+                "Expected model index {} < {}",
+                i,
+                self.gamez.models.len()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn acquire_model(&mut self, model_index: IndexO) {
+        if let Some(model) = model_index
+            .to_usize()
+            .and_then(|i| self.gamez.models.get_mut(i))
+        {
+            model.parent_count += 1;
+        }
+    }
+
+    fn release_model(&mut self, model_index: IndexO) {
+        if let Some(model) = model_index
+            .to_usize()
+            .and_then(|i| self.gamez.models.get_mut(i))
+        {
+            model.parent_count = model.parent_count.saturating_sub(1);
+        }
+    }
+
+    fn link(&mut self, parent: usize, child: IndexR) -> Result<()> {
+        let parent_index = to_index(parent)?;
+        let node = &mut self.gamez.nodes[parent];
+        node.child_indices.push(child);
+        fix_node_ptrs(node);
+        let node = &mut self.gamez.nodes[child.to_usize()];
+        node.parent_indices.push(parent_index);
+        fix_node_ptrs(node);
+        Ok(())
+    }
+}
+
+fn ancestors(nodes: &[Node], index: usize) -> impl Iterator<Item = usize> + '_ {
+    let mut current = index;
+    // bounded, in case the parent indices are inconsistent with the
+    // (checked) child indices
+    (0..nodes.len()).map_while(move |_| {
+        let parent = nodes.get(current)?.parent_indices.first()?.to_usize();
+        current = parent;
+        Some(parent)
+    })
+}
+
+/// The node's world-space transform. See [`SceneGraph::world_transform`].
+pub(crate) fn world_transform(nodes: &[Node], index: usize) -> AffineMatrix {
+    let mut chain: Vec<usize> = ancestors(nodes, index).collect();
+    chain.reverse();
+    chain.push(index);
+    chain
+        .into_iter()
+        .filter_map(|i| nodes.get(i).and_then(node_matrix))
+        .fold(AffineMatrix::IDENTITY, |world, local| {
+            combine(&local, &world)
+        })
+}
+
+fn to_index(index: usize) -> Result<IndexR> {
+    let Some(index) = IndexR::from_usize(index) else {
+        return Err(err!("Too many GameZ nodes ({})", index + 1));
+    };
+    Ok(index)
+}
+
+fn check_cycles(nodes: &[Node], visit: &mut [Visit], index: usize) -> Result<()> {
+    match visit[index] {
+        Visit::Done => return Ok(()),
+        Visit::Active => {
+            return Err(err!("Expected node {} to not be its own ancestor", index));
+        }
+        Visit::New => {}
+    }
+    visit[index] = Visit::Active;
+    for child in &nodes[index].child_indices {
+        check_cycles(nodes, visit, child.to_usize())?;
+    }
+    visit[index] = Visit::Done;
+    Ok(())
+}
+
+/// All node indices referenced by the node.
+fn node_refs(node: &Node) -> Vec<usize> {
+    let mut refs: Vec<usize> = node
+        .parent_indices
+        .iter()
+        .chain(&node.child_indices)
+        .map(|i| i.to_usize())
+        .collect();
+    match &node.data {
+        NodeData::Camera(camera) => {
+            let indices = [
+                camera.world_index,
+                camera.window_index,
+                camera.focus_node_xy,
+                camera.focus_node_xz,
+            ];
+            refs.extend(indices.into_iter().filter_map(IndexO::to_usize));
+        }
+        NodeData::Light(light) => {
+            refs.extend(light.parent_indices.iter().map(|i| i.to_usize()));
+        }
+        NodeData::World(world) => {
+            refs.extend(
+                world
+                    .light_indices
+                    .iter()
+                    .chain(&world.sound_indices)
+                    .map(|i| i.to_usize()),
+            );
+            for partition in world.partitions.iter().flatten() {
+                refs.extend(partition.node_indices.iter().map(|i| i.to_usize()));
+                refs.extend(partition.values.iter().map(|v| v.node_index.to_usize()));
+            }
+        }
+        NodeData::Display(_)
+        | NodeData::Empty
+        | NodeData::Lod(_)
+        | NodeData::Object3d(_)
+        | NodeData::Window(_) => {}
+    }
+    refs
+}
+
+/// Pointers must be non-null exactly when the count is non-zero.
+fn fix_ptr(ptr: &mut u32, count: usize) {
+    if count == 0 {
+        *ptr = 0;
+    } else if *ptr == 0 {
+        *ptr = Ptr::NON_NULL.0;
+    }
+}
+
+fn fix_node_ptrs(node: &mut Node) {
+    fix_ptr(&mut node.parent_array_ptr, node.parent_indices.len());
+    fix_ptr(&mut node.child_array_ptr, node.child_indices.len());
+}
+
+fn remap_list(indices: &mut Vec<IndexR>, map: &impl Fn(usize) -> Option<usize>) {
+    *indices = indices
+        .iter()
+        .filter_map(|i| map(i.to_usize()).and_then(IndexR::from_usize))
+        .collect();
+}
+
+fn remap_opt(index: &mut IndexO, map: &impl Fn(usize) -> Option<usize>) {
+    if let Some(i) = index.to_usize() {
+        *index = map(i).and_then(IndexO::from_usize).unwrap_or(IndexO::NONE);
+    }
+}
+
+/// Remap all node references, dropping references that map to `None`.
+fn remap_node(node: &mut Node, map: impl Fn(usize) -> Option<usize>) {
+    remap_list(&mut node.parent_indices, &map);
+    remap_list(&mut node.child_indices, &map);
+    fix_node_ptrs(node);
+
+    match &mut node.data {
+        NodeData::Camera(camera) => {
+            remap_opt(&mut camera.world_index, &map);
+            remap_opt(&mut camera.window_index, &map);
+            remap_opt(&mut camera.focus_node_xy, &map);
+            remap_opt(&mut camera.focus_node_xz, &map);
+        }
+        NodeData::Light(light) => {
+            remap_list(&mut light.parent_indices, &map);
+            fix_ptr(&mut light.parent_ptr, light.parent_indices.len());
+        }
+        NodeData::World(world) => {
+            remap_list(&mut world.light_indices, &map);
+            remap_list(&mut world.sound_indices, &map);
+            let ptrs = &mut world.ptrs;
+            fix_ptr(&mut ptrs.light_nodes_ptr, world.light_indices.len());
+            fix_ptr(&mut ptrs.light_data_ptr, world.light_indices.len());
+            fix_ptr(&mut ptrs.sound_nodes_ptr, world.sound_indices.len());
+            fix_ptr(&mut ptrs.sound_data_ptr, world.sound_indices.len());
+            for partition in world.partitions.iter_mut().flatten() {
+                remap_list(&mut partition.node_indices, &map);
+                partition.values.retain_mut(|value| {
+                    match map(value.node_index.to_usize()).and_then(IndexR::from_usize) {
+                        Some(i) => {
+                            value.node_index = i;
+                            true
+                        }
+                        None => false,
+                    }
+                });
+                let count = partition.node_indices.len() + partition.values.len();
+                fix_ptr(&mut partition.nodes_ptr, count);
+            }
+        }
+        NodeData::Display(_)
+        | NodeData::Empty
+        | NodeData::Lod(_)
+        | NodeData::Object3d(_)
+        | NodeData::Window(_) => {}
+    }
+}
@@ -0,0 +1,591 @@
+use super::*;
+use mech3ax_api_types::gamez::materials::{ColoredMaterial, Material, Soil, TexturedMaterial};
+use mech3ax_api_types::gamez::model::{
+    FacadeMode, Model, ModelFlags, ModelType, Polygon, PolygonFlags, PolygonMaterial, UvCoord,
+};
+use mech3ax_api_types::gamez::nodes::{
+    ActiveBoundingBox, Area, BoundingBox, Camera, FogType, Light, LightFlags, NodeFlags, Object3d,
+    Partition, RotateTranslateScale, Transform, World, WorldFog, WorldPtrs,
+};
+use mech3ax_api_types::gamez::{GameZMetadata, Texture};
+use mech3ax_api_types::{Color, Range, Vec3};
+use mech3ax_common::GameType;
+use mech3ax_timestamp::unix::from_timestamp;
+
+fn model() -> Model {
+    Model {
+        model_type: ModelType::Default,
+        facade_mode: FacadeMode::CylindricalY,
+        flags: ModelFlags::empty(),
+        parent_count: 0,
+        vertices: Vec::new(),
+        normals: Vec::new(),
+        morphs: Vec::new(),
+        lights: Vec::new(),
+        polygons: Vec::new(),
+        texture_scroll: UvCoord { u: 0.0, v: 0.0 },
+        bbox_mid: Vec3::DEFAULT,
+        bbox_diag: 0.0,
+        polygons_ptr: 0,
+        vertices_ptr: 0,
+        normals_ptr: 0,
+        lights_ptr: 0,
+        morphs_ptr: 0,
+        material_refs_ptr: 0,
+    }
+}
+
+fn node(name: &str, data: NodeData) -> Node {
+    Node {
+        name: name.to_string(),
+        flags: NodeFlags::empty(),
+        update_flags: 0,
+        zone_id: 0,
+        model_index: IndexO::NONE,
+        area_partition: None,
+        virtual_partition: None,
+        parent_indices: Vec::new(),
+        child_indices: Vec::new(),
+        active_bbox: ActiveBoundingBox::Node,
+        node_bbox: BoundingBox::EMPTY,
+        model_bbox: BoundingBox::EMPTY,
+        child_bbox: BoundingBox::EMPTY,
+        field192: 0,
+        field196: 0,
+        field200: 0,
+        field204: 0,
+        data,
+        data_ptr: 0,
+        parent_array_ptr: 0,
+        child_array_ptr: 0,
+        index: 0,
+    }
+}
+
+fn object3d(name: &str, x: f32) -> Node {
+    let transform = Transform::RotateTranslateScale(RotateTranslateScale {
+        rotate: Vec3::DEFAULT,
+        translate: Vec3 { x, y: 0.0, z: 0.0 },
+        scale: Vec3 {
+            x: 1.0,
+            y: 1.0,
+            z: 1.0,
+        },
+        original: None,
+    });
+    node(
+        name,
+        NodeData::Object3d(Object3d {
+            opacity: None,
+            color: None,
+            unk: 0.0,
+            transform,
+            signs: 0,
+        }),
+    )
+}
+
+fn camera(name: &str, focus: usize) -> Node {
+    node(
+        name,
+        NodeData::Camera(Camera {
+            world_index: IndexO::NONE,
+            window_index: IndexO::NONE,
+            focus_node_xy: IndexO::from_usize(focus).unwrap(),
+            focus_node_xz: IndexO::NONE,
+            clip_near: 1.0,
+            clip_far: 1000.0,
+            lod_multiplier: 1.0,
+            fov_h_scale: 1.0,
+            fov_v_scale: 1.0,
+            fov_h_base: 1.0,
+            fov_v_base: 1.0,
+        }),
+    )
+}
+
+fn idx(index: usize) -> IndexR {
+    IndexR::from_usize(index).unwrap()
+}
+
+fn link(nodes: &mut [Node], parent: usize, child: usize) {
+    nodes[parent].child_indices.push(idx(child));
+    nodes[parent].child_array_ptr = Ptr::NON_NULL.0;
+    nodes[child].parent_indices.push(idx(parent));
+    nodes[child].parent_array_ptr = Ptr::NON_NULL.0;
+}
+
+fn gamez(nodes: Vec<Node>, models: Vec<Model>) -> GameZ {
+    let node_count = nodes.len();
+    GameZ {
+        textures: Vec::new(),
+        materials: Vec::new(),
+        models,
+        nodes,
+        metadata: GameZMetadata {
+            datetime: from_timestamp(0x3A000000),
+            material_array_size: Count::EMPTY,
+            model_array_size: Count::EMPTY,
+            node_array_size: Count::from_usize(node_count).unwrap(),
+            node_last_free: node_count as i32,
+            image_ptrs: None,
+        },
+    }
+}
+
+/// `root` (0) > `a` (1) > `b` (2), and `root` > `c` (3). Both `a` and `b`
+/// use model 0.
+fn scene() -> SceneGraph {
+    let mut nodes = vec![
+        object3d("root", 10.0),
+        object3d("a", 1.0),
+        object3d("b", 2.0),
+        object3d("c", 3.0),
+    ];
+    link(&mut nodes, 0, 1);
+    link(&mut nodes, 1, 2);
+    link(&mut nodes, 0, 3);
+    nodes[1].model_index = IndexO::from_usize(0).unwrap();
+    nodes[2].model_index = IndexO::from_usize(0).unwrap();
+    let mut model = model();
+    model.parent_count = 2;
+    SceneGraph::new(gamez(nodes, vec![model])).unwrap()
+}
+
+#[test]
+fn new_rejects_invalid_indices() {
+    let mut nodes = vec![object3d("root", 0.0)];
+    nodes[0].child_indices.push(idx(1));
+    assert!(SceneGraph::new(gamez(nodes, Vec::new())).is_err());
+
+    let mut nodes = vec![object3d("root", 0.0)];
+    nodes[0].model_index = IndexO::from_usize(0).unwrap();
+    assert!(SceneGraph::new(gamez(nodes, Vec::new())).is_err());
+}
+
+#[test]
+fn new_rejects_cycles() {
+    let mut nodes = vec![object3d("a", 0.0), object3d("b", 0.0)];
+    link(&mut nodes, 0, 1);
+    link(&mut nodes, 1, 0);
+    assert!(SceneGraph::new(gamez(nodes, Vec::new())).is_err());
+}
+
+#[test]
+fn navigation() {
+    let scene = scene();
+    assert_eq!(scene.find("b"), Some(2));
+    assert_eq!(scene.find("missing"), None);
+    assert_eq!(scene.roots().collect::<Vec<_>>(), vec![0]);
+    assert_eq!(scene.children(0).collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(scene.parents(2).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(scene.ancestors(2).collect::<Vec<_>>(), vec![1, 0]);
+    assert_eq!(scene.descendants(0), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn world_transform_combines_ancestors() {
+    let scene = scene();
+    let world = scene.world_transform(2).unwrap();
+    assert_eq!(world.r30, 13.0);
+    assert_eq!(world.r31, 0.0);
+    assert!(scene.world_transform(4).is_err());
+}
+
+#[test]
+fn insert_links_parent_and_model() {
+    let mut scene = scene();
+    let mut new = object3d("d", 0.0);
+    new.model_index = IndexO::from_usize(0).unwrap();
+    let index = scene.insert(new, Some(3)).unwrap();
+    assert_eq!(index, 4);
+    assert_eq!(scene.children(3).collect::<Vec<_>>(), vec![4]);
+    assert_eq!(scene.parents(4).collect::<Vec<_>>(), vec![3]);
+    assert_eq!(scene.node(3).unwrap().child_array_ptr, Ptr::NON_NULL.0);
+    assert_eq!(scene.node(4).unwrap().parent_array_ptr, Ptr::NON_NULL.0);
+    assert_eq!(scene.gamez().models[0].parent_count, 3);
+
+    // how PM and CS calculate the last free node isn't known
+    let gamez = scene.clone().into_gamez(GameType::PM);
+    assert_eq!(gamez.metadata.node_array_size.to_usize(), 5);
+    assert_eq!(gamez.metadata.node_last_free, 4);
+
+    // MW and RC recalculate it when writing
+    let gamez = scene.into_gamez(GameType::MW);
+    assert_eq!(gamez.metadata.node_array_size.to_usize(), 5);
+    assert_eq!(gamez.metadata.node_last_free, -1);
+}
+
+#[test]
+fn insert_rejects_linked_nodes() {
+    let mut scene = scene();
+    let mut new = object3d("d", 0.0);
+    new.parent_indices.push(idx(0));
+    assert!(scene.insert(new, None).is_err());
+    assert_eq!(scene.len(), 4);
+}
+
+#[test]
+fn remove_subtree_remaps_references() {
+    let mut scene = scene();
+    let light = node(
+        "light",
+        NodeData::Light(Light {
+            flags: LightFlags::empty(),
+            orientation: Vec3::DEFAULT,
+            translate: Vec3::DEFAULT,
+            diffuse: 0.0,
+            ambient: 0.0,
+            color: Color::BLACK,
+            color_ambient: Color::BLACK,
+            color_diffuse_mixed: Color::BLACK,
+            color_ambient_mixed: Color::BLACK,
+            color_da_combined: Color::BLACK,
+            range: Range { min: 0.0, max: 0.0 },
+            parent_indices: vec![idx(2), idx(3)],
+            parent_ptr: Ptr::NON_NULL.0,
+        }),
+    );
+    scene.insert(light, None).unwrap();
+    scene.insert(camera("camera", 3), None).unwrap();
+
+    assert_eq!(scene.remove(1).unwrap(), 2);
+    let names: Vec<_> = scene
+        .gamez()
+        .nodes
+        .iter()
+        .map(|n| n.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["root", "c", "light", "camera"]);
+    assert_eq!(scene.children(0).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(scene.parents(1).collect::<Vec<_>>(), vec![0]);
+    assert_eq!(scene.gamez().models[0].parent_count, 0);
+
+    let NodeData::Light(light) = &scene.node(2).unwrap().data else {
+        panic!("expected light");
+    };
+    assert_eq!(light.parent_indices, vec![idx(1)]);
+    let NodeData::Camera(camera) = &scene.node(3).unwrap().data else {
+        panic!("expected camera");
+    };
+    assert_eq!(camera.focus_node_xy, IndexO::from_usize(1).unwrap());
+}
+
+#[test]
+fn remove_rejects_camera_references() {
+    let mut scene = scene();
+    scene.insert(camera("camera", 2), None).unwrap();
+    assert!(scene.remove(1).is_err());
+    assert_eq!(scene.len(), 5);
+    assert_eq!(scene.gamez().models[0].parent_count, 2);
+}
+
+#[test]
+fn remove_clears_empty_array_ptrs() {
+    let mut scene = scene();
+    scene.remove(2).unwrap();
+    let a = scene.node(1).unwrap();
+    assert!(a.child_indices.is_empty());
+    assert_eq!(a.child_array_ptr, 0);
+}
+
+#[test]
+fn reparent_moves_node() {
+    let mut scene = scene();
+    scene.reparent(2, Some(3)).unwrap();
+    assert_eq!(scene.children(1).count(), 0);
+    assert_eq!(scene.node(1).unwrap().child_array_ptr, 0);
+    assert_eq!(scene.children(3).collect::<Vec<_>>(), vec![2]);
+    assert_eq!(scene.parents(2).collect::<Vec<_>>(), vec![3]);
+
+    scene.reparent(2, None).unwrap();
+    assert_eq!(scene.roots().collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(scene.node(2).unwrap().parent_array_ptr, 0);
+}
+
+#[test]
+fn reparent_rejects_cycles() {
+    let mut scene = scene();
+    assert!(scene.reparent(0, Some(2)).is_err());
+    assert!(scene.reparent(1, Some(1)).is_err());
+    assert_eq!(scene.parents(0).count(), 0);
+}
+
+#[test]
+fn set_model_updates_parent_counts() {
+    let mut gamez = scene().into_gamez(GameType::MW);
+    gamez.models.push(model());
+    let mut scene = SceneGraph::new(gamez).unwrap();
+    scene.set_model(1, IndexO::from_usize(1).unwrap()).unwrap();
+    assert_eq!(scene.gamez().models[0].parent_count, 1);
+    assert_eq!(scene.gamez().models[1].parent_count, 1);
+    assert!(scene.set_model(1, IndexO::from_usize(2).unwrap()).is_err());
+}
+
+#[test]
+fn clone_subtree_copies_nodes() {
+    let mut scene = scene();
+    let index = scene.clone_subtree(1, Some(3)).unwrap();
+    assert_eq!(index, 4);
+    assert_eq!(scene.len(), 6);
+    assert_eq!(scene.node(4).unwrap().name, "a");
+    assert_eq!(scene.node(5).unwrap().name, "b");
+    assert_eq!(scene.children(4).collect::<Vec<_>>(), vec![5]);
+    assert_eq!(scene.parents(4).collect::<Vec<_>>(), vec![3]);
+    assert_eq!(scene.parents(5).collect::<Vec<_>>(), vec![4]);
+    assert_eq!(scene.children(3).collect::<Vec<_>>(), vec![4]);
+    // the original is unchanged
+    assert_eq!(scene.children(0).collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(scene.gamez().models[0].parent_count, 4);
+
+    let world = scene.world_transform(5).unwrap();
+    assert_eq!(world.r30, 16.0);
+}
+
+#[test]
+fn unchanged_metadata_is_kept() {
+    let scene = scene();
+    let expected = scene.gamez().metadata.clone();
+    assert_eq!(scene.into_gamez(GameType::MW).metadata, expected);
+}
+
+fn texture(name: &str) -> Texture {
+    Texture {
+        name: name.to_string(),
+        mip_index: IndexO::NONE,
+    }
+}
+
+fn textured(texture_index: usize) -> Material {
+    Material::Textured(TexturedMaterial {
+        texture_index: idx(texture_index),
+        soil: Soil::Default,
+        cycle: None,
+        flag: false,
+    })
+}
+
+fn polygon(material_index: usize) -> Polygon {
+    Polygon {
+        flags: PolygonFlags::empty(),
+        priority: 0,
+        zone_set: Vec::new(),
+        vertex_indices: Vec::new(),
+        normal_indices: None,
+        vertex_colors: Vec::new(),
+        materials: vec![PolygonMaterial {
+            material_index: idx(material_index),
+            uv_coords: None,
+        }],
+        vertex_indices_ptr: 0,
+        normal_indices_ptr: 0,
+        uvs_ptr: 0,
+        vertex_colors_ptr: 0,
+        matl_refs_ptr: 0,
+        materials_ptr: 0,
+    }
+}
+
+/// `other` (0), and `house` (1) > `door` (2). `house` uses model 1, which
+/// uses material 1 and texture 1 (`brick`).
+fn source() -> SceneGraph {
+    let mut nodes = vec![
+        object3d("other", 0.0),
+        object3d("house", 100.0),
+        object3d("door", 1.0),
+    ];
+    link(&mut nodes, 0, 1);
+    link(&mut nodes, 1, 2);
+    nodes[0].model_index = IndexO::from_usize(0).unwrap();
+    nodes[1].model_index = IndexO::from_usize(1).unwrap();
+
+    let mut unused = model();
+    unused.parent_count = 1;
+    unused.polygons.push(polygon(0));
+    let mut house = model();
+    house.parent_count = 1;
+    house.polygons.push(polygon(1));
+    house.polygons.push(polygon(2));
+
+    let mut gamez = gamez(nodes, vec![unused, house]);
+    gamez.textures = vec![texture("grass"), texture("brick")];
+    gamez.materials = vec![
+        textured(0),
+        textured(1),
+        Material::Colored(ColoredMaterial {
+            color: Color::BLACK,
+            alpha: 255,
+            soil: Soil::Default,
+        }),
+    ];
+    gamez.metadata.material_array_size = Count::from_usize(4).unwrap();
+    gamez.metadata.model_array_size = Count::from_usize(4).unwrap();
+    SceneGraph::new(gamez).unwrap()
+}
+
+#[test]
+fn merge_subtree_copies_dependencies() {
+    let source = source();
+    let mut target = scene();
+    {
+        let mut gamez = target.into_gamez(GameType::MW);
+        gamez.textures = vec![texture("brick")];
+        gamez.materials = vec![textured(0)];
+        gamez.metadata.material_array_size = Count::from_usize(2).unwrap();
+        gamez.metadata.model_array_size = Count::from_usize(2).unwrap();
+        target = SceneGraph::new(gamez).unwrap();
+    }
+
+    let house = source.find("house").unwrap();
+    let index = target
+        .merge_subtree(&source, house, Some(3), GameType::MW)
+        .unwrap();
+    assert_eq!(index, 4);
+    assert_eq!(target.children(4).collect::<Vec<_>>(), vec![5]);
+    assert_eq!(target.parents(4).collect::<Vec<_>>(), vec![3]);
+    assert_eq!(target.node(5).unwrap().name, "door");
+
+    let gamez = target.into_gamez(GameType::MW);
+    // `brick` already exists, `grass` isn't used
+    assert_eq!(gamez.textures, vec![texture("brick")]);
+    assert_eq!(gamez.materials.len(), 3);
+    assert_eq!(gamez.materials[1], textured(0));
+    assert_eq!(gamez.models.len(), 2);
+    let model = &gamez.models[1];
+    assert_eq!(model.parent_count, 1);
+    let indices: Vec<_> = model
+        .polygons
+        .iter()
+        .map(|p| p.materials[0].material_index)
+        .collect();
+    assert_eq!(indices, vec![idx(1), idx(2)]);
+    assert_eq!(gamez.nodes[4].model_index, IndexO::from_usize(1).unwrap());
+
+    // the arrays must be larger than the count
+    assert_eq!(gamez.metadata.material_array_size.to_usize(), 4);
+    assert_eq!(gamez.metadata.model_array_size.to_usize(), 3);
+    assert_eq!(gamez.metadata.node_last_free, -1);
+}
+
+#[test]
+fn merge_subtree_appends_new_textures() {
+    let source = source();
+    let mut target = scene();
+    let house = source.find("house").unwrap();
+    target
+        .merge_subtree(&source, house, None, GameType::MW)
+        .unwrap();
+    let gamez = target.gamez();
+    assert_eq!(gamez.textures, vec![texture("brick")]);
+    assert_eq!(gamez.materials[0], textured(0));
+    assert_eq!(target.roots().collect::<Vec<_>>(), vec![0, 4]);
+}
+
+fn world(area: Area) -> Node {
+    node(
+        "world1",
+        NodeData::World(World {
+            fog: WorldFog {
+                fog_type: FogType::Off,
+                fog_color: Color::BLACK,
+                fog_range: Range::DEFAULT,
+                fog_altitude: Range::DEFAULT,
+                fog_density: 0.0,
+            },
+            area,
+            virtual_partition: false,
+            partition_max_dec_feature_count: 16,
+            light_indices: Vec::new(),
+            sound_indices: Vec::new(),
+            partitions: Vec::new(),
+            unk: 0,
+            ptrs: WorldPtrs {
+                area_partition_ptr: 1,
+                virt_partition_ptr: 1,
+                light_nodes_ptr: 0,
+                light_data_ptr: 0,
+                sound_nodes_ptr: 0,
+                sound_data_ptr: 0,
+            },
+        }),
+    )
+}
+
+#[test]
+fn merge_subtree_registers_lights_and_sounds() {
+    let area = Area {
+        left: 0,
+        top: 0,
+        right: 256,
+        bottom: 256,
+    };
+    let mut nodes = vec![
+        world(area.clone()),
+        object3d("lamp", 0.0),
+        object3d("speaker", 0.0),
+        object3d("other", 0.0),
+    ];
+    link(&mut nodes, 1, 2);
+    if let NodeData::World(world) = &mut nodes[0].data {
+        world.light_indices = vec![idx(1), idx(3)];
+        world.sound_indices = vec![idx(2)];
+    }
+    let source = SceneGraph::new(gamez(nodes, Vec::new())).unwrap();
+    let nodes = vec![world(area)];
+    let mut target = SceneGraph::new(gamez(nodes, Vec::new())).unwrap();
+
+    let index = target
+        .merge_subtree(&source, 1, None, GameType::MW)
+        .unwrap();
+    assert_eq!(index, 1);
+    let NodeData::World(world) = &target.node(0).unwrap().data else {
+        panic!("expected world");
+    };
+    // `other` wasn't copied
+    assert_eq!(world.light_indices, vec![idx(1)]);
+    assert_eq!(world.sound_indices, vec![idx(2)]);
+    assert_eq!(world.ptrs.light_nodes_ptr, Ptr::NON_NULL.0);
+    assert_eq!(world.ptrs.sound_data_ptr, Ptr::NON_NULL.0);
+}
+
+#[test]
+fn merge_subtree_failure_leaves_target_unchanged() {
+    let area = Area {
+        left: 0,
+        top: 0,
+        right: 256,
+        bottom: 256,
+    };
+    let mut nodes = vec![world(area), object3d("lamp", 0.0)];
+    if let NodeData::World(world) = &mut nodes[0].data {
+        world.light_indices = vec![idx(1)];
+    }
+    nodes[1].model_index = IndexO::from_usize(0).unwrap();
+    let mut model = model();
+    model.parent_count = 1;
+    let source = SceneGraph::new(gamez(nodes, vec![model])).unwrap();
+
+    // the target has no world for the light
+    let mut target = scene();
+    let expected = target.gamez().clone();
+    assert!(
+        target
+            .merge_subtree(&source, 1, Some(0), GameType::MW)
+            .is_err()
+    );
+    assert_eq!(target.gamez(), &expected);
+
+    // the target has no world for the partition
+    let mut source = source.into_gamez(GameType::MW);
+    if let NodeData::World(world) = &mut source.nodes[0].data {
+        world.light_indices.clear();
+    }
+    source.nodes[1].area_partition = Some(Partition { x: 0, z: 0 });
+    let source = SceneGraph::new(source).unwrap();
+    assert!(
+        target
+            .merge_subtree(&source, 1, Some(0), GameType::MW)
+            .is_err()
+    );
+    assert_eq!(target.gamez(), &expected);
+}
@@ -35,6 +35,17 @@ pub(crate) fn read_texture_directory(
     read: &mut CountingReader<impl Read>,
     count: Count,
 ) -> Result<Vec<Texture>> {
+    let (textures, _image_ptrs) = read_texture_directory_ptrs(read, count)?;
+    Ok(textures)
+}
+
+/// Read the texture directory, also returning the image pointers.
+///
+/// PM's image pointers are derived from the campaign, but CS's aren't known.
+pub(crate) fn read_texture_directory_ptrs(
+    read: &mut CountingReader<impl Read>,
+    count: Count,
+) -> Result<(Vec<Texture>, Vec<u32>)> {
     count
         .iter()
         .map(|index| {
@@ -59,22 +70,26 @@ pub(crate) fn read_texture_directory(
             chk!(offset, texture.category == 0)?;
             let mip_index = chk!(offset, mip_index(texture.mip_index, count))?;
 
-            Ok(Texture { name, mip_index })
+            Ok((Texture { name, mip_index }, texture.image_ptr.0))
         })
-        .collect::<Result<Vec<_>>>()
+        .collect::<Result<(Vec<_>, Vec<_>)>>()
 }
 
+/// Write the texture directory. There must be one image pointer per texture.
 pub(crate) fn write_texture_directory(
     write: &mut CountingWriter<impl Write>,
     textures: &[Texture],
     count: Count,
     image_ptrs: &[u32],
 ) -> Result<()> {
-    let ptrs = image_ptrs
-        .iter()
-        .copied()
-        .map(Ptr)
-        .chain(std::iter::repeat(Ptr::NON_NULL));
+    if image_ptrs.len() != textures.len() {
+        return Err(err!(
+            "Expected {} image pointers for the textures, but was {}",
+            textures.len(),
+            image_ptrs.len()
+        ));
+    }
+    let ptrs = image_ptrs.iter().copied().map(Ptr);
 
     for (index, (texture, image_ptr)) in textures.iter().zip(ptrs).enumerate() {
         trace!("Processing texture {}/{}", index, count);
@@ -0,0 +1,79 @@
+use image::RgbaImage;
+use log::debug;
+use mech3ax_api_types::gamez::materials::CycleData;
+use mech3ax_common::{Error, Result, assert_len, assert_with_msg};
+use std::io::Write;
+
+/// The assumed number of engine updates per second. The cycle speed is
+/// assumed to be the number of frames the cycle advances per update. Both are
+/// guesses, and haven't been confirmed from the engine.
+pub const CYCLE_TICKS_PER_SECOND: f32 = 30.0;
+
+fn png_err(e: png::EncodingError) -> Error {
+    std::io::Error::from(e).into()
+}
+
+/// The delay of each frame in milliseconds, for a cycle speed.
+pub fn cycle_frame_delay(speed: f32, ticks_per_second: f32) -> Result<u16> {
+    let frames_per_second = speed * ticks_per_second;
+    if !frames_per_second.is_finite() || frames_per_second <= 0.0 {
+        return Err(assert_with_msg!(
+            "Expected cycle speed {} and ticks per second {} to be > 0",
+            speed,
+            ticks_per_second
+        ));
+    }
+    // saturating cast, and at least 1 ms
+    let delay = (1000.0 / frames_per_second).round() as u16;
+    Ok(delay.max(1))
+}
+
+/// Write a texture cycle as an animated PNG (APNG). The frames are the
+/// cycle's textures, in cycle order, and must all be the same size. Looping
+/// cycles repeat forever, others play once.
+pub fn write_cycle_apng(
+    write: impl Write,
+    cycle: &CycleData,
+    frames: &[RgbaImage],
+    ticks_per_second: f32,
+) -> Result<()> {
+    let Some(first) = frames.first() else {
+        return Err(assert_with_msg!("Expected cycle to have frames"));
+    };
+    let (width, height) = first.dimensions();
+    for (index, frame) in frames.iter().enumerate() {
+        if frame.dimensions() != (width, height) {
+            return Err(assert_with_msg!(
+                "Expected cycle frame {} to be {}x{}, but was {}x{}",
+                index,
+                width,
+                height,
+                frame.width(),
+                frame.height()
+            ));
+        }
+    }
+
+    let frame_count = assert_len!(u32, frames.len(), "cycle frames")?;
+    let delay = cycle_frame_delay(cycle.speed, ticks_per_second)?;
+    let plays = if cycle.looping { 0 } else { 1 };
+    debug!(
+        "Writing {} cycle frames ({}x{}, {} ms, plays: {})",
+        frame_count, width, height, delay, plays
+    );
+
+    let mut encoder = png::Encoder::new(write, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(frame_count, plays).map_err(png_err)?;
+    encoder.set_frame_delay(delay, 1000).map_err(png_err)?;
+
+    let mut writer = encoder.write_header().map_err(png_err)?;
+    for frame in frames {
+        writer.write_image_data(frame.as_raw()).map_err(png_err)?;
+    }
+    writer.finish().map_err(png_err)
+}
+
+#[cfg(test)]
+mod tests;
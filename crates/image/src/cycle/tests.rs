@@ -0,0 +1,79 @@
+use super::*;
+use mech3ax_api_types::IndexR;
+use std::io::Cursor;
+
+fn cycle(looping: bool, speed: f32) -> CycleData {
+    CycleData {
+        texture_indices: vec![IndexR::from_usize(0).unwrap(); 3],
+        looping,
+        speed,
+        current_frame: 0,
+        cycle_ptr: 0,
+        tex_map_ptr: 0,
+    }
+}
+
+fn frames(count: usize) -> Vec<RgbaImage> {
+    vec![RgbaImage::new(4, 2); count]
+}
+
+/// Returns the frame count, plays, and first frame delay of an APNG.
+fn read_apng(buf: Vec<u8>) -> (u32, u32, u16, u16) {
+    let mut reader = png::Decoder::new(Cursor::new(buf)).read_info().unwrap();
+    let info = reader.info();
+    let animation = info.animation_control.unwrap();
+    assert_eq!((info.width, info.height), (4, 2));
+    let frame = reader.next_frame_info().unwrap();
+    (
+        animation.num_frames,
+        animation.num_plays,
+        frame.delay_num,
+        frame.delay_den,
+    )
+}
+
+#[test]
+fn frame_delay() {
+    assert_eq!(cycle_frame_delay(1.0, 30.0).unwrap(), 33);
+    assert_eq!(cycle_frame_delay(0.5, 30.0).unwrap(), 67);
+    assert_eq!(cycle_frame_delay(0.1, 10.0).unwrap(), 1000);
+    // at least 1 ms
+    assert_eq!(cycle_frame_delay(1000.0, 30.0).unwrap(), 1);
+    // saturates
+    assert_eq!(cycle_frame_delay(0.000001, 1.0).unwrap(), u16::MAX);
+}
+
+#[test]
+fn frame_delay_invalid_speed_fails() {
+    assert!(cycle_frame_delay(0.0, 30.0).is_err());
+    assert!(cycle_frame_delay(-1.0, 30.0).is_err());
+    assert!(cycle_frame_delay(f32::NAN, 30.0).is_err());
+    assert!(cycle_frame_delay(1.0, 0.0).is_err());
+}
+
+#[test]
+fn looping_apng() {
+    let mut buf = Vec::new();
+    write_cycle_apng(&mut buf, &cycle(true, 0.5), &frames(3), 30.0).unwrap();
+    assert_eq!(read_apng(buf), (3, 0, 67, 1000));
+}
+
+#[test]
+fn non_looping_apng_plays_once() {
+    let mut buf = Vec::new();
+    write_cycle_apng(&mut buf, &cycle(false, 1.0), &frames(2), 30.0).unwrap();
+    assert_eq!(read_apng(buf), (2, 1, 33, 1000));
+}
+
+#[test]
+fn apng_without_frames_fails() {
+    let mut buf = Vec::new();
+    assert!(write_cycle_apng(&mut buf, &cycle(true, 1.0), &[], 30.0).is_err());
+}
+
+#[test]
+fn apng_with_different_sizes_fails() {
+    let mut buf = Vec::new();
+    let frames = vec![RgbaImage::new(4, 2), RgbaImage::new(2, 4)];
+    assert!(write_cycle_apng(&mut buf, &cycle(true, 1.0), &frames, 30.0).is_err());
+}
@@ -1,9 +1,11 @@
 #![warn(clippy::all, clippy::cargo)]
 #![allow(clippy::identity_op)]
+mod cycle;
 mod read;
 mod write;
 
 use bytemuck::{AnyBitPattern, NoUninit};
+pub use cycle::{CYCLE_TICKS_PER_SECOND, cycle_frame_delay, write_cycle_apng};
 use mech3ax_types::{Ascii, Maybe, bitflags, impl_as_bytes};
 pub use read::read_textures;
 pub use write::write_textures;
@@ -1,15 +1,22 @@
 #![warn(clippy::all, clippy::cargo)]
 #![allow(clippy::identity_op)]
+mod lint;
+mod merge;
 mod read;
 mod write;
 
 use bytemuck::{AnyBitPattern, NoUninit};
+pub use lint::{
+    ArgRange, Command, KnownCommands, LintIssue, LintMessage, is_file_reference, lint_scripts,
+    parse_line, parse_script,
+};
 use mech3ax_types::{Ascii, Hex, impl_as_bytes};
+pub use merge::{SCRIPT_NAME_MAX_LEN, ScriptChange, merge_scripts, validate_script_name};
 pub use read::read_interp;
 pub use write::write_interp;
 
-const SIGNATURE: Hex<u32> = Hex(0x08971119);
-const VERSION: u32 = 7;
+pub const SIGNATURE: Hex<u32> = Hex(0x08971119);
+pub const VERSION: u32 = 7;
 
 #[derive(Debug, Clone, Copy, NoUninit, AnyBitPattern)]
 #[repr(C)]
@@ -0,0 +1,255 @@
+use mech3ax_api_types::interp::Script;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// File extensions of arguments that are checked as file references.
+const FILE_EXTENSIONS: &[&str] = &["zbd", "zrd", "zmap", "wav", "avi", "dll"];
+
+/// A single interpreter command, split into the command name and arguments.
+///
+/// Arguments are separated by exactly one space, so empty arguments are
+/// preserved, and joining the name and arguments with spaces reproduces the
+/// line exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command<'a> {
+    pub name: &'a str,
+    pub args: Vec<&'a str>,
+}
+
+impl fmt::Display for Command<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name)?;
+        for arg in &self.args {
+            f.write_str(" ")?;
+            f.write_str(arg)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_line(line: &str) -> Command<'_> {
+    let mut parts = line.split(' ');
+    // split always yields at least one item
+    let name = parts.next().unwrap_or("");
+    let args = parts.collect();
+    Command { name, args }
+}
+
+pub fn parse_script(script: &Script) -> Vec<Command<'_>> {
+    script.lines.iter().map(|line| parse_line(line)).collect()
+}
+
+/// The number of arguments seen for a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArgRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+/// Known commands and their argument counts, usually learned from the
+/// unmodified scripts of a game.
+#[derive(Debug, Clone, Default)]
+pub struct KnownCommands {
+    commands: HashMap<String, ArgRange>,
+}
+
+impl KnownCommands {
+    pub fn from_scripts(scripts: &[Script]) -> Self {
+        let mut known = Self::default();
+        for script in scripts {
+            for command in parse_script(script) {
+                known.insert(command.name, command.args.len());
+            }
+        }
+        known
+    }
+
+    pub fn insert(&mut self, name: &str, arg_count: usize) {
+        self.commands
+            .entry(name.to_string())
+            .and_modify(|range| {
+                range.min = range.min.min(arg_count);
+                range.max = range.max.max(arg_count);
+            })
+            .or_insert(ArgRange {
+                min: arg_count,
+                max: arg_count,
+            });
+    }
+
+    pub fn get(&self, name: &str) -> Option<ArgRange> {
+        self.commands.get(name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintIssue {
+    DuplicateScript,
+    EmptyLine,
+    EmptyArg {
+        index: usize,
+    },
+    NonAscii,
+    UnknownCommand {
+        name: String,
+    },
+    ArgCount {
+        name: String,
+        count: usize,
+        expected: ArgRange,
+    },
+    MissingFile {
+        path: String,
+    },
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateScript => f.write_str("duplicate script name"),
+            Self::EmptyLine => f.write_str("empty line"),
+            Self::EmptyArg { index } => write!(f, "argument {} is empty (extra space?)", index),
+            Self::NonAscii => f.write_str("line contains non-ASCII characters"),
+            Self::UnknownCommand { name } => write!(f, "unknown command `{}`", name),
+            Self::ArgCount {
+                name,
+                count,
+                expected,
+            } if expected.min == expected.max => write!(
+                f,
+                "command `{}` expects {} arguments, but has {}",
+                name, expected.min, count
+            ),
+            Self::ArgCount {
+                name,
+                count,
+                expected,
+            } => write!(
+                f,
+                "command `{}` expects {}..={} arguments, but has {}",
+                name, expected.min, expected.max, count
+            ),
+            Self::MissingFile { path } => write!(f, "file `{}` not found", path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintMessage {
+    pub script: String,
+    /// The zero-based line index, or `None` for issues with the script itself.
+    pub line: Option<usize>,
+    pub issue: LintIssue,
+}
+
+impl fmt::Display for LintMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.script, line + 1, self.issue),
+            None => write!(f, "{}: {}", self.script, self.issue),
+        }
+    }
+}
+
+pub fn is_file_reference(arg: &str) -> bool {
+    match arg.rsplit_once('.') {
+        Some((stem, ext)) => {
+            !stem.is_empty()
+                && FILE_EXTENSIONS
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+        }
+        None => false,
+    }
+}
+
+/// Check scripts for common mistakes.
+///
+/// If `known` is given, unknown commands and unexpected argument counts are
+/// reported. If `file_exists` is given, it is called for every argument that
+/// looks like a file reference.
+pub fn lint_scripts(
+    scripts: &[Script],
+    known: Option<&KnownCommands>,
+    mut file_exists: Option<&mut dyn FnMut(&str) -> bool>,
+) -> Vec<LintMessage> {
+    let mut messages = Vec::new();
+    let mut seen = HashSet::new();
+
+    for script in scripts {
+        let mut push = |line, issue| {
+            messages.push(LintMessage {
+                script: script.name.clone(),
+                line,
+                issue,
+            })
+        };
+
+        if !seen.insert(script.name.as_str()) {
+            push(None, LintIssue::DuplicateScript);
+        }
+
+        for (index, line) in script.lines.iter().enumerate() {
+            if !line.is_ascii() {
+                push(Some(index), LintIssue::NonAscii);
+            }
+            let command = parse_line(line);
+            if command.name.is_empty() {
+                push(Some(index), LintIssue::EmptyLine);
+                continue;
+            }
+            if let Some(arg_index) = command.args.iter().position(|arg| arg.is_empty()) {
+                push(Some(index), LintIssue::EmptyArg { index: arg_index });
+            }
+
+            if let Some(known) = known {
+                match known.get(command.name) {
+                    None => push(
+                        Some(index),
+                        LintIssue::UnknownCommand {
+                            name: command.name.to_string(),
+                        },
+                    ),
+                    Some(expected) => {
+                        let count = command.args.len();
+                        if count < expected.min || count > expected.max {
+                            push(
+                                Some(index),
+                                LintIssue::ArgCount {
+                                    name: command.name.to_string(),
+                                    count,
+                                    expected,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+
+            if let Some(file_exists) = file_exists.as_mut() {
+                for arg in command.args.iter().filter(|arg| is_file_reference(arg)) {
+                    if !file_exists(arg) {
+                        push(
+                            Some(index),
+                            LintIssue::MissingFile {
+                                path: arg.to_string(),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,73 @@
+use super::*;
+use mech3ax_timestamp::DateTime;
+
+fn script(name: &str, lines: &[&str]) -> Script {
+    Script {
+        name: name.to_string(),
+        datetime: DateTime::UNIX_EPOCH,
+        lines: lines.iter().map(|line| line.to_string()).collect(),
+    }
+}
+
+#[test]
+fn parse_line_roundtrip() {
+    let line = "load  foo.zbd bar";
+    let command = parse_line(line);
+    assert_eq!(command.name, "load");
+    assert_eq!(command.args, vec!["", "foo.zbd", "bar"]);
+    assert_eq!(command.to_string(), line);
+}
+
+#[test]
+fn known_commands_ranges() {
+    let vanilla = [
+        script("a", &["load x.zbd", "print"]),
+        script("b", &["load x.zbd y"]),
+    ];
+    let known = KnownCommands::from_scripts(&vanilla);
+    assert_eq!(known.len(), 2);
+    assert_eq!(known.get("load"), Some(ArgRange { min: 1, max: 2 }));
+    assert_eq!(known.get("print"), Some(ArgRange { min: 0, max: 0 }));
+    assert_eq!(known.get("nope"), None);
+}
+
+#[test]
+fn file_references() {
+    assert!(is_file_reference("gamez.zbd"));
+    assert!(is_file_reference("reader\\c1\\foo.ZRD"));
+    assert!(!is_file_reference(".zbd"));
+    assert!(!is_file_reference("1.5"));
+    assert!(!is_file_reference("foo"));
+}
+
+#[test]
+fn lint_issues() {
+    let vanilla = [script("a", &["load x.zbd", "print"])];
+    let known = KnownCommands::from_scripts(&vanilla);
+
+    let modded = [
+        script("a", &["load x.zbd", "load missing.zbd", "print  "]),
+        script("a", &["", "lod x.zbd", "load"]),
+    ];
+    let mut exists = |path: &str| path == "x.zbd";
+    let messages = lint_scripts(&modded, Some(&known), Some(&mut exists));
+    let actual: Vec<_> = messages.iter().map(|m| m.to_string()).collect();
+    assert_eq!(
+        actual,
+        vec![
+            "a:2: file `missing.zbd` not found",
+            "a:3: argument 0 is empty (extra space?)",
+            "a:3: command `print` expects 0 arguments, but has 2",
+            "a: duplicate script name",
+            "a:1: empty line",
+            "a:2: unknown command `lod`",
+            "a:3: command `load` expects 1 arguments, but has 0",
+        ]
+    );
+}
+
+#[test]
+fn lint_without_reference() {
+    let modded = [script("a", &["anything goes.wav"])];
+    assert!(lint_scripts(&modded, None, None).is_empty());
+}
@@ -0,0 +1,143 @@
+//! Add, remove, and reorder scripts while keeping unchanged entries intact.
+use log::debug;
+use mech3ax_api_types::interp::Script;
+use mech3ax_common::{Result, assert_with_msg};
+use mech3ax_timestamp::DateTime;
+use mech3ax_timestamp::unix::try_to_timestamp;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+/// The maximum length of a script name in bytes. The entry name field is
+/// 120 bytes, and must be zero-terminated.
+pub const SCRIPT_NAME_MAX_LEN: usize = 120 - 1;
+
+/// Check a script name fits in the fixed-size entry name field.
+pub fn validate_script_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(assert_with_msg!("Script name must not be empty"));
+    }
+    if !name.is_ascii() {
+        return Err(assert_with_msg!("Script name `{}` must be ASCII", name));
+    }
+    if name.len() > SCRIPT_NAME_MAX_LEN {
+        return Err(assert_with_msg!(
+            "Script name `{}` is too long: must be <= {} bytes, but was {}",
+            name,
+            SCRIPT_NAME_MAX_LEN,
+            name.len(),
+        ));
+    }
+    Ok(())
+}
+
+/// A script in the desired output order.
+///
+/// If `lines` is omitted, the existing script of the same name is kept
+/// unchanged. If `lines` is given and identical to the existing script, the
+/// existing script is also kept unchanged (including its datetime), unless a
+/// different `datetime` is given. Otherwise, the script is new or modified,
+/// and is stamped with `datetime`, or the merge datetime if omitted.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScriptChange {
+    pub name: String,
+    #[serde(default)]
+    pub datetime: Option<DateTime>,
+    #[serde(default)]
+    pub lines: Option<Vec<String>>,
+}
+
+fn validate_datetime(name: &str, datetime: &DateTime) -> Result<()> {
+    if try_to_timestamp(datetime).is_none() {
+        return Err(assert_with_msg!(
+            "Script `{}` datetime {:?} cannot be represented as a UNIX timestamp",
+            name,
+            datetime,
+        ));
+    }
+    Ok(())
+}
+
+/// Merge changes into existing scripts.
+///
+/// The output contains exactly the scripts named in `changes`, in that order.
+/// Existing scripts not named are removed. New or modified scripts without an
+/// explicit datetime are stamped with `stamp`.
+pub fn merge_scripts(
+    existing: Vec<Script>,
+    changes: Vec<ScriptChange>,
+    stamp: DateTime,
+) -> Result<Vec<Script>> {
+    validate_datetime("<stamp>", &stamp)?;
+
+    let mut seen = HashSet::with_capacity(changes.len());
+    for change in &changes {
+        validate_script_name(&change.name)?;
+        if !seen.insert(change.name.as_str()) {
+            return Err(assert_with_msg!(
+                "Script `{}` is specified more than once",
+                change.name
+            ));
+        }
+        if let Some(datetime) = &change.datetime {
+            validate_datetime(&change.name, datetime)?;
+        }
+        for (index, line) in change.lines.iter().flatten().enumerate() {
+            if !line.is_ascii() {
+                return Err(assert_with_msg!(
+                    "Script `{}` line {} must be ASCII",
+                    change.name,
+                    index + 1,
+                ));
+            }
+        }
+    }
+
+    // if the existing scripts contain duplicate names, the first one wins
+    let mut lookup: HashMap<String, Script> = HashMap::with_capacity(existing.len());
+    for script in existing {
+        if !seen.contains(script.name.as_str()) {
+            debug!("Removing script `{}`", script.name);
+            continue;
+        }
+        lookup.entry(script.name.clone()).or_insert(script);
+    }
+
+    changes
+        .into_iter()
+        .map(|change| {
+            let ScriptChange {
+                name,
+                datetime,
+                lines,
+            } = change;
+            let current = lookup.remove(&name);
+            match (current, lines) {
+                (Some(script), None) => Ok(script),
+                (None, None) => Err(assert_with_msg!(
+                    "Script `{}` does not exist, and no lines were given",
+                    name
+                )),
+                (Some(script), Some(lines))
+                    if script.lines == lines && datetime.is_none_or(|dt| dt == script.datetime) =>
+                {
+                    Ok(script)
+                }
+                (current, Some(lines)) => {
+                    if current.is_some() {
+                        debug!("Modifying script `{}`", name);
+                    } else {
+                        debug!("Adding script `{}`", name);
+                    }
+                    Ok(Script {
+                        name,
+                        datetime: datetime.unwrap_or(stamp),
+                        lines,
+                    })
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
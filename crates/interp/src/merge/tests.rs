@@ -0,0 +1,72 @@
+use super::*;
+use mech3ax_timestamp::unix::from_timestamp;
+
+fn script(name: &str, ts: u32, lines: &[&str]) -> Script {
+    Script {
+        name: name.to_string(),
+        datetime: from_timestamp(ts),
+        lines: lines.iter().map(|line| line.to_string()).collect(),
+    }
+}
+
+fn change(name: &str, lines: Option<&[&str]>) -> ScriptChange {
+    ScriptChange {
+        name: name.to_string(),
+        datetime: None,
+        lines: lines.map(|lines| lines.iter().map(|line| line.to_string()).collect()),
+    }
+}
+
+fn existing() -> Vec<Script> {
+    vec![
+        script("a", 100, &["load a.zbd"]),
+        script("b", 200, &["load b.zbd"]),
+        script("c", 300, &["load c.zbd"]),
+    ]
+}
+
+#[test]
+fn merge_reorder_and_remove() {
+    let changes = vec![change("c", None), change("a", None)];
+    let merged = merge_scripts(existing(), changes, from_timestamp(999)).unwrap();
+    let expected = vec![
+        script("c", 300, &["load c.zbd"]),
+        script("a", 100, &["load a.zbd"]),
+    ];
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn merge_add_and_modify() {
+    let mut explicit = change("e", Some(&["load e.zbd"]));
+    explicit.datetime = Some(from_timestamp(500));
+    let changes = vec![
+        change("a", Some(&["load a.zbd"])),
+        change("b", Some(&["load b2.zbd"])),
+        change("d", Some(&["load d.zbd"])),
+        explicit,
+    ];
+    let merged = merge_scripts(existing(), changes, from_timestamp(999)).unwrap();
+    let expected = vec![
+        script("a", 100, &["load a.zbd"]),
+        script("b", 999, &["load b2.zbd"]),
+        script("d", 999, &["load d.zbd"]),
+        script("e", 500, &["load e.zbd"]),
+    ];
+    assert_eq!(merged, expected);
+}
+
+#[test]
+fn merge_invalid() {
+    let stamp = from_timestamp(999);
+    let missing = vec![change("d", None)];
+    assert!(merge_scripts(existing(), missing, stamp).is_err());
+    let duplicate = vec![change("a", None), change("a", None)];
+    assert!(merge_scripts(existing(), duplicate, stamp).is_err());
+    let long = "x".repeat(SCRIPT_NAME_MAX_LEN + 1);
+    let too_long = vec![change(&long, Some(&[]))];
+    assert!(merge_scripts(existing(), too_long, stamp).is_err());
+    let long = "x".repeat(SCRIPT_NAME_MAX_LEN);
+    let max_len = vec![change(&long, Some(&[]))];
+    assert!(merge_scripts(existing(), max_len, stamp).is_ok());
+}
@@ -1,4 +1,4 @@
-use super::{InterpEntryC, InterpHeaderC, SIGNATURE, VERSION};
+use super::{InterpEntryC, InterpHeaderC, SIGNATURE, VERSION, validate_script_name};
 use log::trace;
 use mech3ax_api_types::interp::Script;
 use mech3ax_common::io_ext::CountingWriter;
@@ -19,6 +19,7 @@ pub fn write_interp(write: &mut CountingWriter<impl Write>, scripts: &[Script])
     let mut offset = 12 + count * InterpEntryC::SIZE;
     for (index, script) in scripts.iter().enumerate() {
         trace!("Writing interp entry {}", index);
+        validate_script_name(&script.name)?;
         let name = Ascii::from_str_padded(&script.name);
         let timestamp = to_timestamp(&script.datetime);
         let entry = InterpEntryC {
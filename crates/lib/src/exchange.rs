@@ -0,0 +1,22 @@
+//! Exchange data with a schema header, so the caller can check that it was
+//! generated from the same types.
+use mech3ax_exchange::Result;
+use mech3ax_metadata_types::{DerivedMetadata, schema_hash};
+use serde::{Deserialize, Serialize};
+
+/// Serialize a value, with a schema header for its type.
+pub(crate) fn to_vec<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: Serialize + DerivedMetadata,
+{
+    mech3ax_exchange::to_vec_with_schema(value, schema_hash::<T>())
+}
+
+/// Deserialize a value, checking the schema hash of its type if the data has a
+/// schema header.
+pub(crate) fn from_slice<'a, T>(slice: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a> + DerivedMetadata,
+{
+    mech3ax_exchange::from_slice_with_schema(slice, schema_hash::<T>())
+}
@@ -3,6 +3,7 @@
 mod buffer;
 mod callbacks;
 mod error;
+mod exchange;
 mod panic;
 mod read;
 mod wave;
@@ -37,7 +37,7 @@ pub extern "C" fn read_interp(
         let mut read = CountingReader::new(input);
         let scripts =
             mech3ax_interp::read_interp(&mut read).context("Failed to read interpreter data")?;
-        let data = mech3ax_exchange::to_vec(&scripts)?;
+        let data = crate::exchange::to_vec(&scripts)?;
         callback(data.as_ptr(), data.len());
         Ok(())
     })
@@ -54,7 +54,7 @@ pub extern "C" fn read_messages(
         let mut read = buf_reader(filename)?;
         let messages = mech3ax_messages::read_messages(&mut read, game)
             .context("Failed to read message data")?;
-        let data = mech3ax_exchange::to_vec(&messages)?;
+        let data = crate::exchange::to_vec(&messages)?;
         callback(data.as_ptr(), data.len());
         Ok(())
     })
@@ -78,7 +78,7 @@ fn read_archive(
     )?;
 
     let name = "manifest.bin";
-    let data = mech3ax_exchange::to_vec(&entries)?;
+    let data = crate::exchange::to_vec(&entries)?;
     buffer_callback(callback, name, &data)
 }
 
@@ -125,7 +125,7 @@ fn read_motion_transform(name: &str, data: Vec<u8>, offset: usize) -> Result<Vec
     read.offset = offset;
     let root = mech3ax_motion::read_motion(&mut read)
         .with_context(|| format!("Failed to read motion data for `{}`", name))?;
-    Ok(mech3ax_exchange::to_vec(&root)?)
+    Ok(crate::exchange::to_vec(&root)?)
 }
 
 // callback filename will not end in .json!
@@ -166,12 +166,12 @@ fn read_mechlib_transform_mw(name: &str, data: Vec<u8>, offset: usize) -> Result
         "materials" => {
             let materials = mech3ax_gamez::mechlib::read_materials(&mut read)
                 .context("Failed to read mechlib format data")?;
-            Ok(mech3ax_exchange::to_vec(&materials)?)
+            Ok(crate::exchange::to_vec(&materials)?)
         }
         _ => {
             let root = mech3ax_gamez::mechlib::mw::read_model(&mut read)
                 .with_context(|| format!("Failed to read model data for `{}`", name))?;
-            Ok(mech3ax_exchange::to_vec(&root)?)
+            Ok(crate::exchange::to_vec(&root)?)
         }
     }
 }
@@ -195,12 +195,12 @@ fn read_mechlib_transform_pm(name: &str, data: Vec<u8>, offset: usize) -> Result
         "materials" => {
             let materials = mech3ax_gamez::mechlib::read_materials(&mut read)
                 .context("Failed to read mechlib format data")?;
-            Ok(mech3ax_exchange::to_vec(&materials)?)
+            Ok(crate::exchange::to_vec(&materials)?)
         }
         _ => {
             let root = mech3ax_gamez::mechlib::pm::read_model(&mut read)
                 .with_context(|| format!("Failed to read model data for `{}`", name))?;
-            Ok(mech3ax_exchange::to_vec(&root)?)
+            Ok(crate::exchange::to_vec(&root)?)
         }
     }
 }
@@ -248,7 +248,7 @@ pub extern "C" fn read_images(
             buffer_callback(callback, name, data.get_ref())
         })?;
 
-        let data = mech3ax_exchange::to_vec(&manifest)?;
+        let data = crate::exchange::to_vec(&manifest)?;
         let name = "manifest.bin";
         buffer_callback(callback, name, &data)
     })
@@ -266,23 +266,29 @@ pub extern "C" fn read_gamez(filename: *const c_char, game_type_id: i32, callbac
                     mech3ax_gamez::gamez::mw::read_gamez(&mut read)
                         .context("Failed to read gamez data")
                 }?;
-                mech3ax_exchange::to_vec(&gamez)?
+                crate::exchange::to_vec(&gamez)?
             }
             GameType::PM => {
                 let gamez = {
                     mech3ax_gamez::gamez::pm::read_gamez(&mut read)
                         .context("Failed to read gamez data")
                 }?;
-                mech3ax_exchange::to_vec(&gamez)?
+                crate::exchange::to_vec(&gamez)?
             }
             GameType::RC => {
                 let gamez = {
                     mech3ax_gamez::gamez::rc::read_gamez(&mut read)
                         .context("Failed to read gamez data")
                 }?;
-                mech3ax_exchange::to_vec(&gamez)?
+                crate::exchange::to_vec(&gamez)?
+            }
+            GameType::CS => {
+                let gamez = {
+                    mech3ax_gamez::gamez::cs::read_gamez(&mut read)
+                        .context("Failed to read gamez data")
+                }?;
+                crate::exchange::to_vec(&gamez)?
             }
-            GameType::CS => bail!("Crimson Skies support for GameZ isn't implemented any more"),
         };
         callback(data.as_ptr(), data.len());
         Ok(())
@@ -311,10 +317,10 @@ pub extern "C" fn read_anim(
             let name = item.name();
             let data = match item {
                 mech3ax_anim::SaveItem::AnimDef { anim_def, .. } => {
-                    mech3ax_exchange::to_vec(anim_def)
+                    crate::exchange::to_vec(anim_def)
                 }
                 mech3ax_anim::SaveItem::SiScript { si_script, .. } => {
-                    mech3ax_exchange::to_vec(si_script)
+                    crate::exchange::to_vec(si_script)
                 }
             }?;
 
@@ -329,7 +335,7 @@ pub extern "C" fn read_anim(
         }
         .context("Failed to read anim data")?;
 
-        let data = mech3ax_exchange::to_vec(&metadata)?;
+        let data = crate::exchange::to_vec(&metadata)?;
         let name = "metadata.bin";
         buffer_callback(callback, name, &data)
     })
@@ -407,7 +413,7 @@ pub extern "C" fn read_zmap(filename: *const c_char, game_type_id: i32, callback
         let input = buf_reader(filename)?;
         let mut read = CountingReader::new(input);
         let map = mech3ax_zmap::read_map(&mut read).context("Failed to read zmap data")?;
-        let data = mech3ax_exchange::to_vec(&map)?;
+        let data = crate::exchange::to_vec(&map)?;
         callback(data.as_ptr(), data.len());
         Ok(())
     })
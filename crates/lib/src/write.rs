@@ -48,7 +48,7 @@ pub extern "C" fn write_interp(
         }
         let buf = unsafe { std::slice::from_raw_parts(data, len) };
         let scripts: Vec<Script> =
-            mech3ax_exchange::from_slice(buf).context("Failed to parse interpreter data")?;
+            crate::exchange::from_slice(buf).context("Failed to parse interpreter data")?;
 
         let mut write = buf_writer(filename)?;
         mech3ax_interp::write_interp(&mut write, &scripts)
@@ -61,7 +61,7 @@ fn parse_entries(ptr: *const u8, len: usize) -> Result<Vec<ArchiveEntry>> {
         bail!("entries is null");
     }
     let buf = unsafe { std::slice::from_raw_parts(ptr, len) };
-    mech3ax_exchange::from_slice(buf).context("entries is invalid")
+    crate::exchange::from_slice(buf).context("entries is invalid")
 }
 
 fn write_archive(
@@ -140,7 +140,7 @@ pub extern "C" fn write_reader(
 }
 
 fn write_motion_transform(name: &str, data: Vec<u8>) -> Result<Vec<u8>> {
-    let motion: Motion = mech3ax_exchange::from_slice(&data)
+    let motion: Motion = crate::exchange::from_slice(&data)
         .with_context(|| format!("Motion data for `{}` is invalid", name))?;
 
     let mut buf = CountingWriter::new(Vec::new(), 0);
@@ -182,7 +182,7 @@ fn write_mechlib_transform_mw(name: &str, data: Vec<u8>) -> Result<Vec<u8>> {
         "version" => Ok(data),
         "materials" => {
             let materials: Vec<MechlibMaterial> =
-                mech3ax_exchange::from_slice(&data).context("Materials data is invalid")?;
+                crate::exchange::from_slice(&data).context("Materials data is invalid")?;
 
             let mut buf = CountingWriter::new(Vec::new(), 0);
             mech3ax_gamez::mechlib::write_materials(&mut buf, &materials)
@@ -190,7 +190,7 @@ fn write_mechlib_transform_mw(name: &str, data: Vec<u8>) -> Result<Vec<u8>> {
             Ok(buf.into_inner())
         }
         original => {
-            let model: MechlibModel = mech3ax_exchange::from_slice(&data)
+            let model: MechlibModel = crate::exchange::from_slice(&data)
                 .with_context(|| format!("Model data for `{}` is invalid", original))?;
 
             let mut buf = CountingWriter::new(Vec::new(), 0);
@@ -207,7 +207,7 @@ fn write_mechlib_transform_pm(name: &str, data: Vec<u8>) -> Result<Vec<u8>> {
         "version" => Ok(data),
         "materials" => {
             let materials: Vec<MechlibMaterial> =
-                mech3ax_exchange::from_slice(&data).context("Materials data is invalid")?;
+                crate::exchange::from_slice(&data).context("Materials data is invalid")?;
 
             let mut buf = CountingWriter::new(Vec::new(), 0);
             mech3ax_gamez::mechlib::write_materials(&mut buf, &materials)
@@ -215,7 +215,7 @@ fn write_mechlib_transform_pm(name: &str, data: Vec<u8>) -> Result<Vec<u8>> {
             Ok(buf.into_inner())
         }
         original => {
-            let model: MechlibModel = mech3ax_exchange::from_slice(&data)
+            let model: MechlibModel = crate::exchange::from_slice(&data)
                 .with_context(|| format!("Model data for `{}` is invalid", original))?;
 
             let mut buf = CountingWriter::new(Vec::new(), 0);
@@ -263,7 +263,7 @@ fn parse_manifest(ptr: *const u8, len: usize) -> Result<TextureManifest> {
         bail!("image manifest is null");
     }
     let buf = unsafe { std::slice::from_raw_parts(ptr, len) };
-    mech3ax_exchange::from_slice(buf).context("image manifest is invalid")
+    crate::exchange::from_slice(buf).context("image manifest is invalid")
 }
 
 #[unsafe(no_mangle)]
@@ -309,7 +309,7 @@ pub extern "C" fn write_gamez(
         let game = i32_to_game(game_type_id)?;
         let buf = unsafe { std::slice::from_raw_parts(data, len) };
         let gamez: GameZ =
-            mech3ax_exchange::from_slice(buf).context("Failed to parse GameZ data")?;
+            crate::exchange::from_slice(buf).context("Failed to parse GameZ data")?;
 
         let mut write = buf_writer(filename)?;
         match game {
@@ -319,7 +319,8 @@ pub extern "C" fn write_gamez(
                 .context("Failed to write GameZ data"),
             GameType::RC => mech3ax_gamez::gamez::rc::write_gamez(&mut write, &gamez)
                 .context("Failed to write GameZ data"),
-            GameType::CS => bail!("Crimson Skies support for GameZ isn't implemented any more"),
+            GameType::CS => mech3ax_gamez::gamez::cs::write_gamez(&mut write, &gamez)
+                .context("Failed to write GameZ data"),
         }
     })
 }
@@ -329,7 +330,7 @@ fn parse_metadata(ptr: *const u8, len: usize) -> Result<AnimMetadata> {
         bail!("anim metadata is null");
     }
     let buf = unsafe { std::slice::from_raw_parts(ptr, len) };
-    mech3ax_exchange::from_slice(buf).context("anim metadata is invalid")
+    crate::exchange::from_slice(buf).context("anim metadata is invalid")
 }
 
 #[unsafe(no_mangle)]
@@ -357,13 +358,11 @@ pub extern "C" fn write_anim(
                 let data = buffer_callback(callback, name)?;
 
                 match item_name {
-                    mech3ax_anim::LoadItemName::AnimDef(name) => {
-                        mech3ax_exchange::from_slice(&data)
-                            .map(mech3ax_anim::LoadItem::AnimDef)
-                            .with_context(|| format!("Anim def for `{}` is invalid", name))
-                    }
+                    mech3ax_anim::LoadItemName::AnimDef(name) => crate::exchange::from_slice(&data)
+                        .map(|anim_def| mech3ax_anim::LoadItem::AnimDef(Box::new(anim_def)))
+                        .with_context(|| format!("Anim def for `{}` is invalid", name)),
                     mech3ax_anim::LoadItemName::SiScript(name) => {
-                        mech3ax_exchange::from_slice(&data)
+                        crate::exchange::from_slice(&data)
                             .map(mech3ax_anim::LoadItem::SiScript)
                             .with_context(|| format!("SI script for `{}` is invalid", name))
                     }
@@ -400,7 +399,7 @@ pub extern "C" fn write_zmap(
             bail!("data is null");
         }
         let buf = unsafe { std::slice::from_raw_parts(data, len) };
-        let map: Zmap = mech3ax_exchange::from_slice(buf).context("Failed to parse zmap data")?;
+        let map: Zmap = crate::exchange::from_slice(buf).context("Failed to parse zmap data")?;
 
         let mut write = buf_writer(filename)?;
         mech3ax_zmap::write_map(&mut write, &map).context("Failed to write zmap data")
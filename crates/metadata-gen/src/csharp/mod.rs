@@ -4,6 +4,7 @@ mod fields;
 mod flags;
 mod module_path;
 mod resolver;
+mod schema;
 mod structs;
 mod templates;
 mod unions;
@@ -17,7 +18,7 @@ macro_rules! write {
     };
 }
 
-pub(crate) fn write(resolver: TypeResolver) {
+pub(crate) fn write(resolver: TypeResolver, roots: &[(&str, u64)]) {
     let resolver::TypeResolverValues {
         enums,
         structs,
@@ -52,4 +53,7 @@ pub(crate) fn write(resolver: TypeResolver) {
         let contents = item.render_impl(&env).unwrap();
         write!(item.path, contents);
     }
+
+    let (path, contents) = schema::render_schema_hashes(roots);
+    write!(path, contents);
 }
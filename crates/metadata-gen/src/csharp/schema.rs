@@ -0,0 +1,34 @@
+use super::module_path::path_mod_root;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// The schema hashes of the root types exchanged through the C API, as
+/// constants.
+pub(crate) fn render_schema_hashes(roots: &[(&str, u64)]) -> (PathBuf, String) {
+    let mut contents = String::from(
+        "// auto-generated by mech3ax-metadata-gen
+namespace Mech3DotNet
+{
+    /// <summary>
+    /// The schema hashes of the types exchanged with the library. The library
+    /// writes these in the exchange header, and checks them if a header is
+    /// present.
+    /// </summary>
+    public static class SchemaHashes
+    {
+",
+    );
+    for (name, hash) in roots {
+        // writing to a string can't fail
+        let _ = writeln!(
+            contents,
+            "        public const ulong {} = 0x{:016X};",
+            name, hash
+        );
+    }
+    contents.push_str("    }\n}\n");
+
+    let mut path = path_mod_root();
+    path.push("SchemaHashes.cs");
+    (path, contents)
+}
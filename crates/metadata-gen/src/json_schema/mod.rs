@@ -0,0 +1,110 @@
+mod module_path;
+mod resolver;
+mod schema;
+
+pub(crate) use resolver::TypeResolver;
+
+use module_path::{key_to_ref, path_schema_root};
+use schema::Definition;
+use serde_json::{Map, Value, json};
+use std::collections::{BTreeSet, HashMap};
+
+macro_rules! write {
+    ($path:expr, $contents:ident) => {
+        std::fs::write(&$path, $contents)
+            .unwrap_or_else(|e| panic!("failed to write `{}`: {:?}", $path.display(), e));
+    };
+}
+
+const SCHEMA: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Collect all `$ref` keys in a schema.
+fn collect_refs<'a>(value: &'a Value, refs: &mut Vec<&'a str>) {
+    match value {
+        Value::Object(obj) => {
+            for (k, v) in obj {
+                match (k.as_str(), v) {
+                    ("$ref", Value::String(r)) => refs.push(r),
+                    _ => collect_refs(v, refs),
+                }
+            }
+        }
+        Value::Array(arr) => arr.iter().for_each(|v| collect_refs(v, refs)),
+        _ => {}
+    }
+}
+
+/// Find the definitions transitively used by a schema.
+fn reachable<'a>(
+    root: &'a Value,
+    definitions: &HashMap<String, &'a Definition>,
+) -> BTreeSet<&'a str> {
+    let mut seen = BTreeSet::new();
+    let mut pending = Vec::new();
+    collect_refs(root, &mut pending);
+    while let Some(r) = pending.pop() {
+        let Some(def) = definitions.get(r) else {
+            panic!("`{}` not found", r);
+        };
+        if seen.insert(def.key.as_str()) {
+            collect_refs(&def.schema, &mut pending);
+        }
+    }
+    seen
+}
+
+fn render(mut root: Map<String, Value>, defs: Map<String, Value>) -> String {
+    root.insert("$schema".to_string(), json!(SCHEMA));
+    if !defs.is_empty() {
+        root.insert("$defs".to_string(), Value::Object(defs));
+    }
+    let mut contents = serde_json::to_string_pretty(&root).unwrap();
+    contents.push('\n');
+    contents
+}
+
+pub(crate) fn write(resolver: TypeResolver) {
+    let resolver::TypeResolverValues {
+        definitions,
+        documents,
+    } = resolver.into_values();
+
+    let root = path_schema_root();
+    std::fs::create_dir_all(&root)
+        .unwrap_or_else(|e| panic!("failed to create `{}`: {:?}", root.display(), e));
+
+    // a bundle of all definitions, for other tools to reference
+    let defs: Map<String, Value> = definitions
+        .iter()
+        .map(|def| (def.key.clone(), def.schema.clone()))
+        .collect();
+    let contents = render(Map::new(), defs);
+    let path = root.join("mech3ax.schema.json");
+    write!(path, contents);
+
+    // self-contained document schemas, so editors don't need to resolve
+    // references across files
+    let by_ref: HashMap<String, &Definition> = definitions
+        .iter()
+        .map(|def| (key_to_ref(&def.key), def))
+        .collect();
+    for document in documents {
+        let keys = reachable(&document.schema, &by_ref);
+        let defs: Map<String, Value> = definitions
+            .iter()
+            .filter(|def| keys.contains(def.key.as_str()))
+            .map(|def| (def.key.clone(), def.schema.clone()))
+            .collect();
+        let mut schema = match document.schema {
+            Value::Object(obj) => obj,
+            other => panic!("document schema is not an object: {:?}", other),
+        };
+        schema.insert("title".to_string(), json!(document.name));
+        let contents = render(schema, defs);
+        let path = root.join(format!("{}.schema.json", document.name));
+        write!(path, contents);
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+pub(crate) fn path_schema_root() -> PathBuf {
+    PathBuf::from("output/schema/")
+}
+
+/// The definition key for a type, e.g. `gamez.nodes.Node`.
+///
+/// Type names are not unique across modules (e.g. `FogType`), so the module
+/// path is included.
+pub(crate) fn rust_mod_path_to_key(module_path: &'static str, name: &str) -> String {
+    let mut components = module_path.split("::");
+    assert_eq!(components.next(), Some("mech3ax_api_types"));
+
+    let mut key = String::new();
+    for component in components {
+        key.push_str(component);
+        key.push('.');
+    }
+    key.push_str(name);
+    key
+}
+
+pub(crate) fn key_to_ref(key: &str) -> String {
+    format!("#/$defs/{}", key)
+}
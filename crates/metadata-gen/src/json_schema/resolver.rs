@@ -0,0 +1,144 @@
+use super::schema::{
+    Definition, base_schema, byte_vec_schema, enum_definition, flags_definition, struct_definition,
+    union_definition,
+};
+use crate::resolver::ResolveError;
+use mech3ax_metadata_types::{
+    TypeInfo, TypeInfoBase, TypeInfoEnum, TypeInfoFlags, TypeInfoStruct, TypeInfoUnion,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+type ResolveResult = ::std::result::Result<Value, ResolveError>;
+
+/// A JSON document written by `unzbd`, e.g. `interp.json`.
+#[derive(Debug)]
+pub(crate) struct Document {
+    pub(crate) name: &'static str,
+    pub(crate) schema: Value,
+}
+
+#[derive(Debug)]
+pub(crate) struct TypeResolver {
+    definitions: HashMap<(&'static str, &'static str), Definition>,
+    documents: Vec<Document>,
+}
+
+#[derive(Debug)]
+pub(crate) struct TypeResolverValues {
+    pub(crate) definitions: Vec<Definition>,
+    pub(crate) documents: Vec<Document>,
+}
+
+impl crate::resolver::Resolver for TypeResolver {
+    fn push<TI>(&mut self)
+    where
+        TI: mech3ax_metadata_types::DerivedMetadata,
+    {
+        match TI::TYPE_INFO {
+            TypeInfo::Base(bi) => panic!("cannot push base type: {:?}", bi),
+            TypeInfo::Vec(vi) => panic!("cannot push vec type: {:?}", vi),
+            TypeInfo::Option(oi) => panic!("cannot push option type: {:?}", oi),
+            TypeInfo::Enum(ei) => self.push_enum(ei),
+            TypeInfo::Struct(si) => self.push_struct(si),
+            TypeInfo::Union(ui) => self.push_union(ui),
+            TypeInfo::Flags(fi) => self.push_flags(fi),
+        }
+    }
+}
+
+impl TypeResolver {
+    pub(crate) fn new() -> Self {
+        Self {
+            definitions: HashMap::new(),
+            documents: Vec::new(),
+        }
+    }
+
+    fn push_enum(&mut self, ei: &TypeInfoEnum) {
+        let d = enum_definition(ei);
+        self.definitions.insert((ei.module_path, ei.name), d);
+    }
+
+    fn push_flags(&mut self, fi: &TypeInfoFlags) {
+        let d = flags_definition(fi);
+        self.definitions.insert((fi.module_path, fi.name), d);
+    }
+
+    fn push_struct(&mut self, si: &TypeInfoStruct) {
+        let d = struct_definition(self, si);
+        self.definitions.insert((si.module_path, si.name), d);
+    }
+
+    fn push_union(&mut self, ui: &TypeInfoUnion) {
+        let d = union_definition(self, ui);
+        self.definitions.insert((ui.module_path, ui.name), d);
+    }
+
+    /// Add a document schema. All types it uses must have been pushed.
+    pub(crate) fn push_document<TI>(&mut self, name: &'static str)
+    where
+        TI: mech3ax_metadata_types::DerivedMetadata,
+    {
+        let schema = self.resolve(TI::TYPE_INFO, name);
+        self.documents.push(Document { name, schema });
+    }
+
+    pub(crate) fn resolve(&self, ti: &TypeInfo, name: &'static str) -> Value {
+        self.resolve_inner(ti).unwrap_or_else(|e| {
+            let msg = e.push(name).into_string();
+            panic!("`{}` not found", msg);
+        })
+    }
+
+    fn resolve_inner(&self, ti: &TypeInfo) -> ResolveResult {
+        match ti {
+            TypeInfo::Base(bi) => Ok(base_schema(bi)),
+            TypeInfo::Enum(ei) => self.resolve_named(ei.module_path, ei.name),
+            TypeInfo::Vec(inner) => self.resolve_vec(inner),
+            TypeInfo::Option(inner) => self.resolve_option(inner),
+            TypeInfo::Struct(si) => self.resolve_named(si.module_path, si.name),
+            TypeInfo::Union(ui) => self.resolve_named(ui.module_path, ui.name),
+            TypeInfo::Flags(fi) => self.resolve_named(fi.module_path, fi.name),
+        }
+    }
+
+    fn resolve_vec(&self, inner: &TypeInfo) -> ResolveResult {
+        match inner {
+            // remap byte vec
+            TypeInfo::Base(TypeInfoBase::U8) => Ok(byte_vec_schema()),
+            _ => match self.resolve_inner(inner) {
+                Ok(items) => Ok(json!({ "type": "array", "items": items })),
+                Err(e) => Err(e.push("Vec")),
+            },
+        }
+    }
+
+    fn resolve_option(&self, inner: &TypeInfo) -> ResolveResult {
+        match self.resolve_inner(inner) {
+            Ok(inner) => Ok(json!({ "anyOf": [inner, { "type": "null" }] })),
+            Err(e) => Err(e.push("Option")),
+        }
+    }
+
+    fn resolve_named(&self, module_path: &'static str, name: &'static str) -> ResolveResult {
+        // types must be pushed before they can be resolved
+        self.definitions
+            .get(&(module_path, name))
+            .map(Definition::make_ref)
+            .ok_or_else(|| ResolveError::new(module_path, name))
+    }
+
+    pub(crate) fn into_values(self) -> TypeResolverValues {
+        let Self {
+            definitions,
+            documents,
+        } = self;
+        let mut definitions: Vec<Definition> = definitions.into_values().collect();
+        definitions.sort_by(|a, b| a.key.cmp(&b.key));
+        TypeResolverValues {
+            definitions,
+            documents,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use super::module_path::{key_to_ref, rust_mod_path_to_key};
+use super::resolver::TypeResolver;
+use heck::ToSnakeCase as _;
+use mech3ax_metadata_types::{
+    DefaultHandling, TypeInfoBase, TypeInfoEnum, TypeInfoFlags, TypeInfoStruct, TypeInfoUnion,
+};
+use serde_json::{Map, Value, json};
+
+/// A JSON schema definition of a named type.
+#[derive(Debug, Clone)]
+pub(crate) struct Definition {
+    /// The definition's key in `$defs`.
+    pub(crate) key: String,
+    /// The definition's schema.
+    pub(crate) schema: Value,
+}
+
+impl Definition {
+    pub(crate) fn make_ref(&self) -> Value {
+        json!({ "$ref": key_to_ref(&self.key) })
+    }
+}
+
+pub(crate) fn base_schema(bi: &TypeInfoBase) -> Value {
+    use TypeInfoBase::*;
+    match bi {
+        Bool => json!({ "type": "boolean" }),
+        U8 => json!({ "type": "integer", "minimum": u8::MIN, "maximum": u8::MAX }),
+        U16 => json!({ "type": "integer", "minimum": u16::MIN, "maximum": u16::MAX }),
+        U32 => json!({ "type": "integer", "minimum": u32::MIN, "maximum": u32::MAX }),
+        U64 => json!({ "type": "integer", "minimum": u64::MIN, "maximum": u64::MAX }),
+        I8 => json!({ "type": "integer", "minimum": i8::MIN, "maximum": i8::MAX }),
+        I16 => json!({ "type": "integer", "minimum": i16::MIN, "maximum": i16::MAX }),
+        I32 => json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX }),
+        F32 => json!({ "type": "number" }),
+        String => json!({ "type": "string" }),
+        DateTime => json!({ "type": "string", "format": "date-time" }),
+    }
+}
+
+/// Byte vectors are always serialized as base64 strings.
+pub(crate) fn byte_vec_schema() -> Value {
+    json!({ "type": "string", "contentEncoding": "base64" })
+}
+
+/// Enums are serialized as the variant name.
+pub(crate) fn enum_definition(ei: &TypeInfoEnum) -> Definition {
+    let variants: Vec<&str> = ei.variants.iter().map(|(name, _)| *name).collect();
+    Definition {
+        key: rust_mod_path_to_key(ei.module_path, ei.name),
+        schema: json!({
+            "title": ei.name,
+            "type": "string",
+            "enum": variants,
+        }),
+    }
+}
+
+/// Flags are serialized as an object of booleans, with snake case keys.
+///
+/// Some flags can be omitted, and this isn't exposed via the metadata, so
+/// none are required.
+pub(crate) fn flags_definition(fi: &TypeInfoFlags) -> Definition {
+    let properties: Map<String, Value> = fi
+        .variants
+        .iter()
+        .map(|(name, _)| {
+            let schema = json!({ "type": "boolean", "default": false });
+            (name.to_snake_case(), schema)
+        })
+        .collect();
+    Definition {
+        key: rust_mod_path_to_key(fi.module_path, fi.name),
+        schema: json!({
+            "title": fi.name,
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": false,
+        }),
+    }
+}
+
+fn default_value(default: DefaultHandling) -> Option<Value> {
+    match default {
+        DefaultHandling::Normal => None,
+        DefaultHandling::OptionIsNone => Some(Value::Null),
+        DefaultHandling::BoolIsFalse => Some(json!(false)),
+        DefaultHandling::BoolIsTrue => Some(json!(true)),
+        DefaultHandling::PointerIsZero => Some(json!(0)),
+        DefaultHandling::SoilIsDefault => Some(json!("Default")),
+        DefaultHandling::I16IsNegOne | DefaultHandling::I32IsNegOne => Some(json!(-1)),
+    }
+}
+
+/// Structs are serialized as an object, with the field names as keys.
+///
+/// Fields are required, unless serde can omit them during deserialization.
+pub(crate) fn struct_definition(resolver: &TypeResolver, si: &TypeInfoStruct) -> Definition {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in si.fields {
+        let mut schema = resolver.resolve(field.type_info, si.name);
+        if !field.is_optional() {
+            required.push(field.name);
+        } else if let Some(default) = default_value(field.default)
+            && let Value::Object(obj) = &mut schema
+        {
+            obj.insert("default".to_string(), default);
+        }
+        properties.insert(field.name.to_string(), schema);
+    }
+    Definition {
+        key: rust_mod_path_to_key(si.module_path, si.name),
+        schema: json!({
+            "title": si.name,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        }),
+    }
+}
+
+/// Unions are serialized externally tagged, so unit variants are the variant
+/// name, and new type variants are an object with the variant name as the
+/// single key.
+pub(crate) fn union_definition(resolver: &TypeResolver, ui: &TypeInfoUnion) -> Definition {
+    let variants: Vec<Value> = ui
+        .variants
+        .iter()
+        .map(|(name, type_info)| match type_info {
+            None => json!({ "const": name }),
+            Some(type_info) => {
+                let schema = resolver.resolve(type_info, ui.name);
+                json!({
+                    "type": "object",
+                    "properties": { *name: schema },
+                    "required": [name],
+                    "additionalProperties": false,
+                })
+            }
+        })
+        .collect();
+    Definition {
+        key: rust_mod_path_to_key(ui.module_path, ui.name),
+        schema: json!({
+            "title": ui.name,
+            "oneOf": variants,
+        }),
+    }
+}
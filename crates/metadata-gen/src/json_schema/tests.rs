@@ -0,0 +1,102 @@
+use super::TypeResolver;
+use super::schema::struct_definition;
+use crate::resolver::Resolver as _;
+use mech3ax_api_types::gamez::Texture;
+use mech3ax_api_types::gamez::materials::{ColoredMaterial, CycleData, Soil, TexturedMaterial};
+use mech3ax_api_types::nodes::NodeFlags;
+use mech3ax_api_types::{Color, IndexO, IndexR};
+use mech3ax_metadata_types::{DerivedMetadata, TypeInfo};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+fn resolver() -> TypeResolver {
+    let mut resolver = TypeResolver::new();
+    resolver.push::<Color>();
+    resolver.push::<CycleData>();
+    resolver.push::<Soil>();
+    resolver
+}
+
+/// Omit each field in turn, and check the value only deserializes if the
+/// schema doesn't require the field.
+fn check_required<T>(value: &T)
+where
+    T: DerivedMetadata + Serialize + DeserializeOwned,
+{
+    let TypeInfo::Struct(si) = T::TYPE_INFO else {
+        panic!("not a struct");
+    };
+    let definition = struct_definition(&resolver(), si);
+    let required = definition.schema["required"].as_array().unwrap();
+
+    let Value::Object(full) = serde_json::to_value(value).unwrap() else {
+        panic!("not an object");
+    };
+    for field in si.fields {
+        let mut partial = full.clone();
+        assert!(partial.remove(field.name).is_some(), "{}", field.name);
+        let deserialized = serde_json::from_value::<T>(Value::Object(partial)).is_ok();
+        let is_required = required.contains(&Value::from(field.name));
+        assert_eq!(
+            deserialized, !is_required,
+            "{}.{} (required: {})",
+            si.name, field.name, is_required
+        );
+    }
+}
+
+#[test]
+fn textured_material_required() {
+    check_required(&TexturedMaterial {
+        texture_index: IndexR::ZERO,
+        soil: Soil::Default,
+        cycle: Some(CycleData {
+            texture_indices: vec![IndexR::ZERO],
+            looping: false,
+            speed: 0.0,
+            current_frame: 0,
+            cycle_ptr: 0,
+            tex_map_ptr: 0,
+        }),
+        flag: false,
+    });
+}
+
+#[test]
+fn colored_material_required() {
+    check_required(&ColoredMaterial {
+        color: Color::WHITE_FULL,
+        alpha: 255,
+        soil: Soil::Default,
+    });
+}
+
+#[test]
+fn texture_required() {
+    check_required(&Texture {
+        name: "texture".to_string(),
+        mip_index: IndexO::ZERO,
+    });
+}
+
+#[test]
+fn node_flags_required() {
+    check_required(&NodeFlags {
+        active: true,
+        altitude_surface: false,
+        intersect_surface: false,
+        intersect_bbox: false,
+        landmark: false,
+        bbox_node: false,
+        bbox_model: false,
+        bbox_child: false,
+        terrain: false,
+        can_modify: false,
+        clip_to: false,
+        tree_valid: true,
+        id_zone_check: true,
+        unk25: false,
+        unk28: false,
+    });
+}
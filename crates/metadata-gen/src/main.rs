@@ -1,8 +1,11 @@
 mod csharp;
+mod json_schema;
 mod python;
 mod resolver;
+mod typescript;
 
 use mech3ax_api_types as api;
+use mech3ax_metadata_types::schema_hash;
 use resolver::Resolver;
 
 fn add_types(resolver: &mut impl Resolver) {
@@ -77,6 +80,8 @@ fn add_gamez(resolver: &mut impl Resolver) {
     resolver.push::<api::gamez::Texture>();
     resolver.push::<api::gamez::GameZMetadata>();
     resolver.push::<api::gamez::GameZ>();
+    resolver.push::<api::gamez::SoilMapCoverage>();
+    resolver.push::<api::gamez::SoilMapLegend>();
 }
 
 fn add_nodes(resolver: &mut impl Resolver) {
@@ -278,9 +283,62 @@ fn add_anim(resolver: &mut impl Resolver) {
     resolver.push::<api::anim::AnimMetadata>();
 }
 
+fn add_documents(resolver: &mut json_schema::TypeResolver) {
+    // archive-based ZIP files
+    resolver.push_document::<Vec<api::archive::ArchiveEntry>>("archive-manifest");
+    resolver.push_document::<api::motion::Motion>("motion");
+    resolver.push_document::<Vec<api::gamez::MechlibMaterial>>("mechlib-materials");
+    resolver.push_document::<api::gamez::MechlibModel>("mechlib-model");
+
+    resolver.push_document::<Vec<api::interp::Script>>("interp");
+    resolver.push_document::<api::messages::Messages>("messages");
+    resolver.push_document::<api::zmap::Zmap>("zmap");
+    resolver.push_document::<api::image::TextureManifest>("textures-manifest");
+
+    resolver.push_document::<api::gamez::GameZMetadata>("gamez-metadata");
+    resolver.push_document::<Vec<api::gamez::Texture>>("gamez-textures");
+    resolver.push_document::<Vec<api::gamez::materials::Material>>("gamez-materials");
+    resolver.push_document::<Vec<api::gamez::model::Model>>("gamez-models");
+    resolver.push_document::<Vec<api::gamez::nodes::Node>>("gamez-nodes");
+    resolver.push_document::<api::gamez::SoilMapLegend>("soil-map-legend");
+
+    resolver.push_document::<api::anim::AnimMetadata>("anim-metadata");
+    resolver.push_document::<api::anim::AnimDef>("anim-def");
+    resolver.push_document::<api::anim::SiScript>("si-script");
+}
+
+/// The root types exchanged through the C API, and their schema hashes.
+fn exchange_roots() -> Vec<(&'static str, u64)> {
+    vec![
+        (
+            "ArchiveManifest",
+            schema_hash::<Vec<api::archive::ArchiveEntry>>(),
+        ),
+        ("Interp", schema_hash::<Vec<api::interp::Script>>()),
+        ("Messages", schema_hash::<api::messages::Messages>()),
+        ("Motion", schema_hash::<api::motion::Motion>()),
+        (
+            "MechlibMaterials",
+            schema_hash::<Vec<api::gamez::MechlibMaterial>>(),
+        ),
+        ("MechlibModel", schema_hash::<api::gamez::MechlibModel>()),
+        (
+            "TextureManifest",
+            schema_hash::<api::image::TextureManifest>(),
+        ),
+        ("GameZ", schema_hash::<api::gamez::GameZ>()),
+        ("AnimMetadata", schema_hash::<api::anim::AnimMetadata>()),
+        ("AnimDef", schema_hash::<api::anim::AnimDef>()),
+        ("SiScript", schema_hash::<api::anim::SiScript>()),
+        ("Zmap", schema_hash::<api::zmap::Zmap>()),
+    ]
+}
+
 fn main() {
     csharp();
     python();
+    typescript();
+    json_schema();
 }
 
 fn csharp() {
@@ -290,7 +348,7 @@ fn csharp() {
     add_gamez(&mut resolver);
     add_anim(&mut resolver);
 
-    csharp::write(resolver);
+    csharp::write(resolver, &exchange_roots());
 }
 
 fn python() {
@@ -302,3 +360,24 @@ fn python() {
 
     python::write(resolver);
 }
+
+fn typescript() {
+    let mut resolver = typescript::TypeResolver::new();
+
+    add_types(&mut resolver);
+    add_gamez(&mut resolver);
+    add_anim(&mut resolver);
+
+    typescript::write(resolver, &exchange_roots());
+}
+
+fn json_schema() {
+    let mut resolver = json_schema::TypeResolver::new();
+
+    add_types(&mut resolver);
+    add_gamez(&mut resolver);
+    add_anim(&mut resolver);
+    add_documents(&mut resolver);
+
+    json_schema::write(resolver);
+}
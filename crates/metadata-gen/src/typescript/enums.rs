@@ -0,0 +1,98 @@
+use super::module_path::{rust_mod_path_to_path, ts_file_name, ts_runtime_path};
+use super::resolver::TypeResolver;
+use super::ts_type::TsType;
+use mech3ax_metadata_types::TypeInfoEnum;
+use minijinja::{Environment, context};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Variant {
+    /// The enum variant's name.
+    pub(crate) name: &'static str,
+    /// The enum variant's index.
+    pub(crate) index: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Enum {
+    /// The enum's TypeScript type name.
+    pub(crate) name: &'static str,
+    /// The enum's Rust module path.
+    pub(crate) module_path: &'static str,
+    /// The exchange runtime import path.
+    pub(crate) runtime: String,
+    /// The enum's variant names.
+    pub(crate) variants: Vec<Variant>,
+    /// The enum's path on the filesystem.
+    pub(crate) path: PathBuf,
+}
+
+impl Enum {
+    pub(crate) fn make_type(&self) -> TsType {
+        TsType::named(self.module_path, self.name)
+    }
+
+    pub(crate) fn new(resolver: &mut TypeResolver, ei: &TypeInfoEnum) -> Self {
+        // num! enums are serialized as the variant name in JSON, so the
+        // variant names are used verbatim.
+        let variants = ei
+            .variants
+            .iter()
+            .copied()
+            .map(|(name, index)| Variant { name, index })
+            .collect();
+
+        let mut path = rust_mod_path_to_path(ei.module_path);
+        resolver.add_directory(&path);
+        path.push(ts_file_name(ei.name));
+
+        Self {
+            name: ei.name,
+            module_path: ei.module_path,
+            runtime: ts_runtime_path(ei.module_path),
+            variants,
+            path,
+        }
+    }
+
+    pub(crate) fn render_impl(&self, env: &Environment<'_>) -> Result<String, minijinja::Error> {
+        let template = env.get_template("enum_impl.ts")?;
+        template.render(context! { enum => self })
+    }
+}
+
+pub(crate) const ENUM_IMPL: &str = r#"// auto-generated by mech3ax-metadata-gen
+import * as ex from "{{ enum.runtime }}";
+
+export type {{ enum.name }} ={% for variant in enum.variants %}
+  | "{{ variant.name }}"{% endfor %};
+
+export const {{ enum.name }}Variants: readonly {{ enum.name }}[] = [{% for variant in enum.variants %}
+  "{{ variant.name }}",{% endfor %}
+];
+
+const INDICES: Record<{{ enum.name }}, number> = {{ '{' }}{% for variant in enum.variants %}
+  {{ variant.name }}: {{ variant.index }},{% endfor %}
+};
+
+export function decode{{ enum.name }}(r: ex.Reader): {{ enum.name }} {
+  const index = r.unitVariant("{{ enum.name }}");
+  switch (index) {
+{%- for variant in enum.variants %}
+    case {{ variant.index }}:
+      return "{{ variant.name }}";
+{%- endfor %}
+    default:
+      throw new ex.ExchangeError(`unknown variant ${index} for "{{ enum.name }}"`);
+  }
+}
+
+export function encode{{ enum.name }}(w: ex.Writer, v: {{ enum.name }}): void {
+  const index = INDICES[v];
+  if (index === undefined) {
+    throw new ex.ExchangeError(`invalid variant "${String(v)}" for "{{ enum.name }}"`);
+  }
+  w.unitVariant(index);
+}
+"#;
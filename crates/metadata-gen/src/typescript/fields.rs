@@ -0,0 +1,68 @@
+use super::resolver::TypeResolver;
+use super::ts_type::TsImport;
+use mech3ax_metadata_types::{DefaultHandling, TypeInfoStructField};
+use serde::Serialize;
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FieldCodec {
+    pub(crate) encoder: String,
+    pub(crate) decoder: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Field {
+    /// The struct field's JSON key name, which is also the TypeScript
+    /// property name.
+    pub(crate) name: &'static str,
+    /// The struct field's import, if it requires importing.
+    #[serde(skip)]
+    pub(crate) import: Option<TsImport>,
+    /// The struct field's TypeScript type.
+    pub(crate) ty: Cow<'static, str>,
+    /// The struct field's exchange codec information.
+    pub(crate) codec: FieldCodec,
+    /// The struct field's default value, if any.
+    pub(crate) default: Option<&'static str>,
+    /// Whether the struct field can be omitted from JSON.
+    pub(crate) optional: bool,
+}
+
+impl Field {
+    pub(crate) fn new(
+        struct_name: &'static str,
+        field_info: &TypeInfoStructField,
+        resolver: &TypeResolver,
+    ) -> Self {
+        // the JSON field name should match the type name, barring any serde
+        // rename shenanigans.
+        let name = field_info.name;
+
+        let ty = resolver.resolve(field_info.type_info, struct_name);
+
+        let codec = FieldCodec {
+            encoder: ty.codec.make_encoder(),
+            decoder: ty.codec.make_decoder(),
+        };
+
+        let default = match field_info.default {
+            DefaultHandling::Normal => None,
+            DefaultHandling::OptionIsNone => Some("null"),
+            DefaultHandling::BoolIsFalse => Some("false"),
+            DefaultHandling::BoolIsTrue => Some("true"),
+            DefaultHandling::PointerIsZero => Some("0"),
+            // Soil is a string union, so no import is needed
+            DefaultHandling::SoilIsDefault => Some("\"Default\""),
+            DefaultHandling::I16IsNegOne | DefaultHandling::I32IsNegOne => Some("-1"),
+        };
+
+        Self {
+            name,
+            import: ty.import,
+            ty: ty.name.clone(),
+            codec,
+            default,
+            optional: field_info.is_optional(),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use super::module_path::{rust_mod_path_to_path, ts_file_name, ts_runtime_path};
+use super::resolver::TypeResolver;
+use super::ts_type::TsType;
+use heck::ToSnakeCase as _;
+use mech3ax_metadata_types::{TypeInfoFlags, TypeInfoFlagsRepr};
+use minijinja::{Environment, context};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Variant {
+    /// The flags variant's JSON key.
+    pub(crate) name: String,
+    /// The flags variant's index.
+    pub(crate) index: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Flags {
+    /// The flags' TypeScript type name.
+    pub(crate) name: &'static str,
+    /// The flags' Rust module path.
+    pub(crate) module_path: &'static str,
+    /// The exchange runtime import path.
+    pub(crate) runtime: String,
+    /// The flags' exchange (de)serialization type.
+    pub(crate) codec_type: &'static str,
+    /// The flags' variant names.
+    pub(crate) variants: Vec<Variant>,
+    /// The flags' path on the filesystem.
+    pub(crate) path: PathBuf,
+}
+
+impl Flags {
+    pub(crate) fn make_type(&self) -> TsType {
+        TsType::named(self.module_path, self.name)
+    }
+
+    pub(crate) fn new(resolver: &mut TypeResolver, fi: &TypeInfoFlags) -> Self {
+        let codec_type = match fi.repr {
+            TypeInfoFlagsRepr::U8 => "U8",
+            TypeInfoFlagsRepr::U16 => "U16",
+            TypeInfoFlagsRepr::U32 => "U32",
+        };
+
+        // bit! flags are serialized as an object of snake case booleans in
+        // JSON, but as an integer in the exchange format.
+        let variants = fi
+            .variants
+            .iter()
+            .copied()
+            .map(|(name, index)| Variant {
+                name: name.to_snake_case(),
+                index,
+            })
+            .collect();
+
+        let mut path = rust_mod_path_to_path(fi.module_path);
+        resolver.add_directory(&path);
+        path.push(ts_file_name(fi.name));
+
+        Self {
+            name: fi.name,
+            module_path: fi.module_path,
+            runtime: ts_runtime_path(fi.module_path),
+            codec_type,
+            variants,
+            path,
+        }
+    }
+
+    pub(crate) fn render_impl(&self, env: &Environment<'_>) -> Result<String, minijinja::Error> {
+        let template = env.get_template("flags_impl.ts")?;
+        template.render(context! { flags => self })
+    }
+}
+
+pub(crate) const FLAGS_IMPL: &str = r#"// auto-generated by mech3ax-metadata-gen
+import * as ex from "{{ flags.runtime }}";
+
+export interface {{ flags.name }} {{ '{' }}{% for variant in flags.variants %}
+  {{ variant.name }}?: boolean;{% endfor %}
+}
+
+const BITS = {{ '{' }}{% for variant in flags.variants %}
+  {{ variant.name }}: 1 << {{ variant.index }},{% endfor %}
+} as const;
+
+const VALID: number = 0{% for variant in flags.variants %} | BITS.{{ variant.name }}{% endfor %};
+
+export const {{ flags.name }} = {
+  bits: BITS,
+
+  toBits(v: {{ flags.name }}): number {
+    let bits = 0;
+{%- for variant in flags.variants %}
+    if (v.{{ variant.name }}) {
+      bits |= BITS.{{ variant.name }};
+    }
+{%- endfor %}
+    return bits >>> 0;
+  },
+
+  fromBits(bits: number): {{ flags.name }} {
+    if ((bits & ~VALID) !== 0) {
+      throw new ex.ExchangeError(`invalid bits ${bits >>> 0} for "{{ flags.name }}"`);
+    }
+    return {{ '{' }}{% for variant in flags.variants %}
+      {{ variant.name }}: (bits & BITS.{{ variant.name }}) !== 0,{% endfor %}
+    };
+  },
+};
+
+export function decode{{ flags.name }}(r: ex.Reader): {{ flags.name }} {
+  return {{ flags.name }}.fromBits(ex.decode{{ flags.codec_type }}(r));
+}
+
+export function encode{{ flags.name }}(w: ex.Writer, v: {{ flags.name }}): void {
+  ex.encode{{ flags.codec_type }}(w, {{ flags.name }}.toBits(v));
+}
+"#;
@@ -0,0 +1,65 @@
+mod enums;
+mod fields;
+mod flags;
+mod module_path;
+mod resolver;
+mod runtime;
+mod schema;
+mod structs;
+mod templates;
+mod ts_type;
+mod unions;
+
+pub(crate) use resolver::TypeResolver;
+
+macro_rules! write {
+    ($path:expr, $contents:ident) => {
+        std::fs::write(&$path, $contents)
+            .unwrap_or_else(|e| panic!("failed to write `{}`: {:?}", $path.display(), e));
+    };
+}
+
+pub(crate) fn write(resolver: TypeResolver, roots: &[(&str, u64)]) {
+    let resolver::TypeResolverValues {
+        enums,
+        flags,
+        structs,
+        unions,
+        directories,
+    } = resolver.into_values();
+
+    for path in directories {
+        std::fs::create_dir(&path)
+            .unwrap_or_else(|e| panic!("failed to create `{}`: {:?}", path.display(), e));
+    }
+
+    let mut path = module_path::path_mod_root();
+    path.push("exchange.ts");
+    let contents = runtime::EXCHANGE_TS;
+    write!(path, contents);
+
+    let env = templates::make_env();
+
+    for item in enums {
+        let contents = item.render_impl(&env).unwrap();
+        write!(item.path, contents);
+    }
+
+    for item in flags {
+        let contents = item.render_impl(&env).unwrap();
+        write!(item.path, contents);
+    }
+
+    for item in structs {
+        let contents = item.render_impl(&env).unwrap();
+        write!(item.path, contents);
+    }
+
+    for item in unions {
+        let contents = item.render_impl(&env).unwrap();
+        write!(item.path, contents);
+    }
+
+    let (path, contents) = schema::render_schema_hashes(roots);
+    write!(path, contents);
+}
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+pub(crate) fn path_mod_root() -> PathBuf {
+    PathBuf::from("output/mech3ts/")
+}
+
+pub(crate) fn path_mod_types() -> PathBuf {
+    PathBuf::from("output/mech3ts/types/")
+}
+
+fn rust_mod_path_components(module_path: &'static str) -> Vec<&'static str> {
+    let mut components = module_path.split("::");
+    assert_eq!(components.next(), Some("mech3ax_api_types"));
+    components.collect()
+}
+
+pub(crate) fn rust_mod_path_to_path(module_path: &'static str) -> PathBuf {
+    let mut path = path_mod_types();
+    for component in rust_mod_path_components(module_path) {
+        path.push(component);
+    }
+    path
+}
+
+pub(crate) fn ts_file_name(name: &str) -> String {
+    format!("{}.ts", name)
+}
+
+/// The relative import specifier of a type, from a file in `from`.
+pub(crate) fn ts_import_path(
+    from: &'static str,
+    module_path: &'static str,
+    name: &'static str,
+) -> String {
+    let from = rust_mod_path_components(from);
+    let to = rust_mod_path_components(module_path);
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut path = match from.len() - common {
+        0 => "./".to_string(),
+        ups => "../".repeat(ups),
+    };
+    for component in &to[common..] {
+        path.push_str(component);
+        path.push('/');
+    }
+    path.push_str(name);
+    path
+}
+
+/// The relative import specifier of the exchange runtime, from a file in
+/// `from`.
+pub(crate) fn ts_runtime_path(from: &'static str) -> String {
+    // one extra level for the `types` directory
+    let ups = rust_mod_path_components(from).len() + 1;
+    let mut path = "../".repeat(ups);
+    path.push_str("exchange");
+    path
+}
@@ -0,0 +1,584 @@
+/// The exchange format runtime, which the generated codecs are built on.
+pub(crate) const EXCHANGE_TS: &str = r#"// auto-generated by mech3ax-metadata-gen
+// Runtime for the mech3ax exchange binary format.
+
+export const TypeMap = {
+  U8: 10,
+  U16: 11,
+  U32: 12,
+  U64: 13,
+  I8: 20,
+  I16: 21,
+  I32: 22,
+  F32: 30,
+  None: 32,
+  Some: 33,
+  BoolTrue: 40,
+  BoolFalse: 41,
+  Str: 50,
+  Bytes: 51,
+  Seq: 60,
+  Struct: 70,
+  Map: 71,
+  EnumUnit: 80,
+  EnumNewType: 81,
+  Header: 90,
+} as const;
+
+export type Decoder<T> = (r: Reader) => T;
+export type Encoder<T> = (w: Writer, v: T) => void;
+
+export class ExchangeError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = "ExchangeError";
+  }
+}
+
+function typeName(ty: number): string {
+  for (const [name, value] of Object.entries(TypeMap)) {
+    if (value === ty) {
+      return name;
+    }
+  }
+  return `unknown (${ty})`;
+}
+
+const textDecoder = new TextDecoder("utf-8", { fatal: true });
+const textEncoder = new TextEncoder();
+
+export class Fields {
+  constructor(
+    private readonly name: string,
+    private readonly values: Map<string, unknown>,
+  ) {}
+
+  get<T>(key: string): T {
+    if (!this.values.has(key)) {
+      throw new ExchangeError(`missing field "${key}" for "${this.name}"`);
+    }
+    return this.values.get(key) as T;
+  }
+
+  getOr<T>(key: string, fallback: T): T {
+    return this.values.has(key) ? (this.values.get(key) as T) : fallback;
+  }
+}
+
+export class Reader {
+  private readonly view: DataView;
+  private offset = 0;
+
+  constructor(private readonly data: Uint8Array) {
+    this.view = new DataView(data.buffer, data.byteOffset, data.byteLength);
+  }
+
+  get position(): number {
+    return this.offset;
+  }
+
+  private check(len: number): void {
+    if (this.offset + len > this.data.byteLength) {
+      throw new ExchangeError(
+        `unexpected end of data (at ${this.offset}, needed ${len} bytes)`,
+      );
+    }
+  }
+
+  rawU8(): number {
+    this.check(1);
+    const v = this.view.getUint8(this.offset);
+    this.offset += 1;
+    return v;
+  }
+
+  rawU16(): number {
+    this.check(2);
+    const v = this.view.getUint16(this.offset, true);
+    this.offset += 2;
+    return v;
+  }
+
+  rawU32(): number {
+    this.check(4);
+    const v = this.view.getUint32(this.offset, true);
+    this.offset += 4;
+    return v;
+  }
+
+  rawU64(): number {
+    this.check(8);
+    const v = this.view.getBigUint64(this.offset, true);
+    this.offset += 8;
+    if (v > BigInt(Number.MAX_SAFE_INTEGER)) {
+      throw new ExchangeError(`u64 value ${v} is not a safe integer`);
+    }
+    return Number(v);
+  }
+
+  rawI8(): number {
+    this.check(1);
+    const v = this.view.getInt8(this.offset);
+    this.offset += 1;
+    return v;
+  }
+
+  rawI16(): number {
+    this.check(2);
+    const v = this.view.getInt16(this.offset, true);
+    this.offset += 2;
+    return v;
+  }
+
+  rawI32(): number {
+    this.check(4);
+    const v = this.view.getInt32(this.offset, true);
+    this.offset += 4;
+    return v;
+  }
+
+  rawF32(): number {
+    this.check(4);
+    const v = this.view.getFloat32(this.offset, true);
+    this.offset += 4;
+    return v;
+  }
+
+  rawBytes(len: number): Uint8Array {
+    this.check(len);
+    const v = this.data.slice(this.offset, this.offset + len);
+    this.offset += len;
+    return v;
+  }
+
+  /** Read the optional schema header, if present. */
+  header(): bigint | null {
+    if (this.data.byteLength < 4 || this.view.getUint32(0, true) !== TypeMap.Header) {
+      return null;
+    }
+    this.offset = 4;
+    this.check(8);
+    const v = this.view.getBigUint64(this.offset, true);
+    this.offset += 8;
+    return v;
+  }
+
+  readType(): number {
+    return this.rawU32();
+  }
+
+  expectType(expected: number): void {
+    const actual = this.readType();
+    if (actual !== expected) {
+      throw new ExchangeError(
+        `expected ${typeName(expected)}, found ${typeName(actual)} (at ${this.offset - 4})`,
+      );
+    }
+  }
+
+  seq(): number {
+    this.expectType(TypeMap.Seq);
+    return this.rawU64();
+  }
+
+  struct(name: string, decoders: Record<string, Decoder<unknown>>): Fields {
+    this.expectType(TypeMap.Struct);
+    const count = this.rawU64();
+    const values = new Map<string, unknown>();
+    for (let i = 0; i < count; i++) {
+      const key = decodeStr(this);
+      const decoder = Object.prototype.hasOwnProperty.call(decoders, key)
+        ? decoders[key]
+        : undefined;
+      if (decoder === undefined) {
+        throw new ExchangeError(`unknown field "${key}" for "${name}"`);
+      }
+      if (values.has(key)) {
+        throw new ExchangeError(`duplicate field "${key}" for "${name}"`);
+      }
+      values.set(key, decoder(this));
+    }
+    return new Fields(name, values);
+  }
+
+  unitVariant(name: string): number {
+    const [kind, index] = this.variant(name);
+    this.expectUnit(kind, name);
+    return index;
+  }
+
+  variant(name: string): [number, number] {
+    const kind = this.readType();
+    if (kind !== TypeMap.EnumUnit && kind !== TypeMap.EnumNewType) {
+      throw new ExchangeError(
+        `expected enum for "${name}", found ${typeName(kind)} (at ${this.offset - 4})`,
+      );
+    }
+    return [kind, this.rawU32()];
+  }
+
+  expectUnit(kind: number, name: string): void {
+    if (kind !== TypeMap.EnumUnit) {
+      throw new ExchangeError(`expected unit variant for "${name}" (at ${this.offset})`);
+    }
+  }
+
+  expectNewType(kind: number, name: string): void {
+    if (kind !== TypeMap.EnumNewType) {
+      throw new ExchangeError(`expected new type variant for "${name}" (at ${this.offset})`);
+    }
+  }
+
+  assertEnd(): void {
+    if (this.offset !== this.data.byteLength) {
+      throw new ExchangeError(`expected all data to be read (at ${this.offset})`);
+    }
+  }
+}
+
+export class Writer {
+  private buf = new Uint8Array(1024);
+  private view = new DataView(this.buf.buffer);
+  private len = 0;
+
+  private reserve(additional: number): void {
+    const required = this.len + additional;
+    if (required <= this.buf.byteLength) {
+      return;
+    }
+    let capacity = this.buf.byteLength * 2;
+    while (capacity < required) {
+      capacity *= 2;
+    }
+    const buf = new Uint8Array(capacity);
+    buf.set(this.buf.subarray(0, this.len));
+    this.buf = buf;
+    this.view = new DataView(buf.buffer);
+  }
+
+  rawU8(v: number): void {
+    this.reserve(1);
+    this.view.setUint8(this.len, v);
+    this.len += 1;
+  }
+
+  rawU16(v: number): void {
+    this.reserve(2);
+    this.view.setUint16(this.len, v, true);
+    this.len += 2;
+  }
+
+  rawU32(v: number): void {
+    this.reserve(4);
+    this.view.setUint32(this.len, v, true);
+    this.len += 4;
+  }
+
+  rawU64(v: number): void {
+    this.reserve(8);
+    this.view.setBigUint64(this.len, BigInt(v), true);
+    this.len += 8;
+  }
+
+  rawI8(v: number): void {
+    this.reserve(1);
+    this.view.setInt8(this.len, v);
+    this.len += 1;
+  }
+
+  rawI16(v: number): void {
+    this.reserve(2);
+    this.view.setInt16(this.len, v, true);
+    this.len += 2;
+  }
+
+  rawI32(v: number): void {
+    this.reserve(4);
+    this.view.setInt32(this.len, v, true);
+    this.len += 4;
+  }
+
+  rawF32(v: number): void {
+    this.reserve(4);
+    this.view.setFloat32(this.len, v, true);
+    this.len += 4;
+  }
+
+  rawBytes(v: Uint8Array): void {
+    this.reserve(v.byteLength);
+    this.buf.set(v, this.len);
+    this.len += v.byteLength;
+  }
+
+  writeType(ty: number): void {
+    this.rawU32(ty);
+  }
+
+  header(schemaHash: bigint): void {
+    this.writeType(TypeMap.Header);
+    this.reserve(8);
+    this.view.setBigUint64(this.len, schemaHash, true);
+    this.len += 8;
+  }
+
+  seq(len: number): void {
+    this.writeType(TypeMap.Seq);
+    this.rawU64(len);
+  }
+
+  struct(count: number): void {
+    this.writeType(TypeMap.Struct);
+    this.rawU64(count);
+  }
+
+  field<T>(key: string, value: T, encoder: Encoder<T>): void {
+    encodeStr(this, key);
+    encoder(this, value);
+  }
+
+  unitVariant(index: number): void {
+    this.writeType(TypeMap.EnumUnit);
+    this.rawU32(index);
+  }
+
+  newTypeVariant(index: number): void {
+    this.writeType(TypeMap.EnumNewType);
+    this.rawU32(index);
+  }
+
+  finish(): Uint8Array {
+    return this.buf.slice(0, this.len);
+  }
+}
+
+function checkInt(v: number, min: number, max: number, name: string): void {
+  if (!Number.isInteger(v) || v < min || v > max) {
+    throw new ExchangeError(`value ${v} is out of range for ${name}`);
+  }
+}
+
+export function decodeU8(r: Reader): number {
+  r.expectType(TypeMap.U8);
+  return r.rawU8();
+}
+
+export function encodeU8(w: Writer, v: number): void {
+  checkInt(v, 0, 0xff, "u8");
+  w.writeType(TypeMap.U8);
+  w.rawU8(v);
+}
+
+export function decodeU16(r: Reader): number {
+  r.expectType(TypeMap.U16);
+  return r.rawU16();
+}
+
+export function encodeU16(w: Writer, v: number): void {
+  checkInt(v, 0, 0xffff, "u16");
+  w.writeType(TypeMap.U16);
+  w.rawU16(v);
+}
+
+export function decodeU32(r: Reader): number {
+  r.expectType(TypeMap.U32);
+  return r.rawU32();
+}
+
+export function encodeU32(w: Writer, v: number): void {
+  checkInt(v, 0, 0xffffffff, "u32");
+  w.writeType(TypeMap.U32);
+  w.rawU32(v);
+}
+
+export function decodeU64(r: Reader): number {
+  r.expectType(TypeMap.U64);
+  return r.rawU64();
+}
+
+export function encodeU64(w: Writer, v: number): void {
+  checkInt(v, 0, Number.MAX_SAFE_INTEGER, "u64");
+  w.writeType(TypeMap.U64);
+  w.rawU64(v);
+}
+
+export function decodeI8(r: Reader): number {
+  r.expectType(TypeMap.I8);
+  return r.rawI8();
+}
+
+export function encodeI8(w: Writer, v: number): void {
+  checkInt(v, -0x80, 0x7f, "i8");
+  w.writeType(TypeMap.I8);
+  w.rawI8(v);
+}
+
+export function decodeI16(r: Reader): number {
+  r.expectType(TypeMap.I16);
+  return r.rawI16();
+}
+
+export function encodeI16(w: Writer, v: number): void {
+  checkInt(v, -0x8000, 0x7fff, "i16");
+  w.writeType(TypeMap.I16);
+  w.rawI16(v);
+}
+
+export function decodeI32(r: Reader): number {
+  r.expectType(TypeMap.I32);
+  return r.rawI32();
+}
+
+export function encodeI32(w: Writer, v: number): void {
+  checkInt(v, -0x80000000, 0x7fffffff, "i32");
+  w.writeType(TypeMap.I32);
+  w.rawI32(v);
+}
+
+export function decodeF32(r: Reader): number {
+  r.expectType(TypeMap.F32);
+  return r.rawF32();
+}
+
+export function encodeF32(w: Writer, v: number): void {
+  w.writeType(TypeMap.F32);
+  w.rawF32(v);
+}
+
+export function decodeBool(r: Reader): boolean {
+  const ty = r.readType();
+  switch (ty) {
+    case TypeMap.BoolTrue:
+      return true;
+    case TypeMap.BoolFalse:
+      return false;
+    default:
+      throw new ExchangeError(`expected bool, found ${typeName(ty)} (at ${r.position - 4})`);
+  }
+}
+
+export function encodeBool(w: Writer, v: boolean): void {
+  w.writeType(v ? TypeMap.BoolTrue : TypeMap.BoolFalse);
+}
+
+export function decodeStr(r: Reader): string {
+  r.expectType(TypeMap.Str);
+  const len = r.rawU64();
+  return textDecoder.decode(r.rawBytes(len));
+}
+
+export function encodeStr(w: Writer, v: string): void {
+  const bytes = textEncoder.encode(v);
+  w.writeType(TypeMap.Str);
+  w.rawU64(bytes.byteLength);
+  w.rawBytes(bytes);
+}
+
+/** Date times are exchanged as RFC 3339 strings. */
+export const decodeDateTime: Decoder<string> = decodeStr;
+export const encodeDateTime: Encoder<string> = encodeStr;
+
+function bytesToBase64(bytes: Uint8Array): string {
+  let s = "";
+  for (let i = 0; i < bytes.byteLength; i++) {
+    s += String.fromCharCode(bytes[i]);
+  }
+  return btoa(s);
+}
+
+function base64ToBytes(v: string): Uint8Array {
+  const s = atob(v);
+  const bytes = new Uint8Array(s.length);
+  for (let i = 0; i < s.length; i++) {
+    bytes[i] = s.charCodeAt(i);
+  }
+  return bytes;
+}
+
+/** Bytes are base64 strings in JSON, so they are converted to match. */
+export function decodeBytes(r: Reader): string {
+  r.expectType(TypeMap.Bytes);
+  const len = r.rawU64();
+  return bytesToBase64(r.rawBytes(len));
+}
+
+export function encodeBytes(w: Writer, v: string): void {
+  const bytes = base64ToBytes(v);
+  w.writeType(TypeMap.Bytes);
+  w.rawU64(bytes.byteLength);
+  w.rawBytes(bytes);
+}
+
+export function decodeOption<T>(inner: Decoder<T>): Decoder<T | null> {
+  return (r: Reader): T | null => {
+    const ty = r.readType();
+    switch (ty) {
+      case TypeMap.None:
+        return null;
+      case TypeMap.Some:
+        return inner(r);
+      default:
+        throw new ExchangeError(
+          `expected option, found ${typeName(ty)} (at ${r.position - 4})`,
+        );
+    }
+  };
+}
+
+export function encodeOption<T>(inner: Encoder<T>): Encoder<T | null> {
+  return (w: Writer, v: T | null): void => {
+    if (v === null || v === undefined) {
+      w.writeType(TypeMap.None);
+    } else {
+      w.writeType(TypeMap.Some);
+      inner(w, v);
+    }
+  };
+}
+
+export function decodeVec<T>(inner: Decoder<T>): Decoder<T[]> {
+  return (r: Reader): T[] => {
+    const len = r.seq();
+    const values: T[] = [];
+    for (let i = 0; i < len; i++) {
+      values.push(inner(r));
+    }
+    return values;
+  };
+}
+
+export function encodeVec<T>(inner: Encoder<T>): Encoder<T[]> {
+  return (w: Writer, v: T[]): void => {
+    w.seq(v.length);
+    for (const item of v) {
+      inner(w, item);
+    }
+  };
+}
+
+/**
+ * Decode a complete exchange buffer. If a schema hash is given, and the data
+ * has a schema header, the hashes must match.
+ */
+export function fromExchange<T>(data: Uint8Array, decoder: Decoder<T>, schemaHash?: bigint): T {
+  const r = new Reader(data);
+  const actual = r.header();
+  if (schemaHash !== undefined && actual !== null && actual !== schemaHash) {
+    throw new ExchangeError(
+      `schema mismatch: expected ${schemaHash.toString(16)}, found ${actual.toString(16)}`,
+    );
+  }
+  const value = decoder(r);
+  r.assertEnd();
+  return value;
+}
+
+/** Encode a value into an exchange buffer, with an optional schema header. */
+export function toExchange<T>(value: T, encoder: Encoder<T>, schemaHash?: bigint): Uint8Array {
+  const w = new Writer();
+  if (schemaHash !== undefined) {
+    w.header(schemaHash);
+  }
+  encoder(w, value);
+  return w.finish();
+}
+"#;
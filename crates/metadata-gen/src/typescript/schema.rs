@@ -0,0 +1,26 @@
+use super::module_path::path_mod_root;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// The schema hashes of the root types exchanged through the C API, as
+/// constants.
+pub(crate) fn render_schema_hashes(roots: &[(&str, u64)]) -> (PathBuf, String) {
+    let mut contents = String::from(
+        "// auto-generated by mech3ax-metadata-gen
+/**
+ * The schema hashes of the types exchanged with the library, for
+ * `fromExchange` and `toExchange`.
+ */
+export const SchemaHashes = {
+",
+    );
+    for (name, hash) in roots {
+        // writing to a string can't fail
+        let _ = writeln!(contents, "  {}: 0x{:016x}n,", name, hash);
+    }
+    contents.push_str("} as const;\n");
+
+    let mut path = path_mod_root();
+    path.push("schema.ts");
+    (path, contents)
+}
@@ -0,0 +1,94 @@
+use super::fields::Field;
+use super::module_path::{rust_mod_path_to_path, ts_file_name, ts_runtime_path};
+use super::resolver::TypeResolver;
+use super::ts_type::TsType;
+use mech3ax_metadata_types::TypeInfoStruct;
+use minijinja::{Environment, context};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Struct {
+    /// The struct's TypeScript name.
+    pub(crate) name: &'static str,
+    /// The struct's Rust module path.
+    pub(crate) module_path: &'static str,
+    /// The exchange runtime import path.
+    pub(crate) runtime: String,
+    /// The struct's field types imports.
+    pub(crate) imports: Vec<String>,
+    /// The struct's fields.
+    pub(crate) fields: Vec<Field>,
+    /// The structs's path on the filesystem.
+    pub(crate) path: PathBuf,
+}
+
+impl Struct {
+    pub(crate) fn make_type(&self) -> TsType {
+        TsType::named(self.module_path, self.name)
+    }
+
+    pub(crate) fn new(resolver: &mut TypeResolver, si: &TypeInfoStruct) -> Self {
+        let fields: Vec<_> = si
+            .fields
+            .iter()
+            .map(|field_info| Field::new(si.name, field_info, resolver))
+            .collect();
+
+        let imports = TypeResolver::render_imports(
+            si.module_path,
+            si.name,
+            fields.iter().filter_map(|field| field.import),
+        );
+
+        let mut path = rust_mod_path_to_path(si.module_path);
+        resolver.add_directory(&path);
+        path.push(ts_file_name(si.name));
+
+        Self {
+            name: si.name,
+            module_path: si.module_path,
+            runtime: ts_runtime_path(si.module_path),
+            imports,
+            fields,
+            path,
+        }
+    }
+
+    pub(crate) fn render_impl(&self, env: &Environment<'_>) -> Result<String, minijinja::Error> {
+        let template = env.get_template("struct_impl.ts")?;
+        template.render(context! { struct => self })
+    }
+}
+
+pub(crate) const STRUCT_IMPL: &str = r#"// auto-generated by mech3ax-metadata-gen
+import * as ex from "{{ struct.runtime }}";
+{%- for import in struct.imports %}
+{{ import }}
+{%- endfor %}
+
+export interface {{ struct.name }} {{ '{' }}{% for field in struct.fields %}
+  {{ field.name }}{% if field.optional %}?{% endif %}: {{ field.ty }};{% endfor %}
+}
+
+export function decode{{ struct.name }}(r: ex.Reader): {{ struct.name }} {
+{%- if struct.fields %}
+  const f = r.struct("{{ struct.name }}", {{ '{' }}{% for field in struct.fields %}
+    {{ field.name }}: {{ field.codec.decoder }},{% endfor %}
+  });
+  return {{ '{' }}{% for field in struct.fields %}
+    {{ field.name }}: {% if field.default %}f.getOr<{{ field.ty }}>("{{ field.name }}", {{ field.default }}){% else %}f.get<{{ field.ty }}>("{{ field.name }}"){% endif %},{% endfor %}
+  };
+{%- else %}
+  r.struct("{{ struct.name }}", {});
+  return {};
+{%- endif %}
+}
+
+export function encode{{ struct.name }}(w: ex.Writer, v: {{ struct.name }}): void {
+  w.struct({{ struct.fields | length }});
+{%- for field in struct.fields %}
+  w.field("{{ field.name }}", {% if field.default %}v.{{ field.name }} ?? {{ field.default }}{% else %}v.{{ field.name }}{% endif %}, {{ field.codec.encoder }});
+{%- endfor %}
+}
+"#;
@@ -0,0 +1,167 @@
+use mech3ax_metadata_types::TypeInfoBase;
+use std::borrow::Cow;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CodecType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    F32,
+    Bool,
+    DateTime,
+    String,
+    Bytes,
+    Option(Box<CodecType>),
+    Vec(Box<CodecType>),
+    Named(&'static str),
+}
+
+impl CodecType {
+    fn make_codec(&self, s: &mut String, prefix: &str) {
+        use CodecType::*;
+        let base = match self {
+            U8 => "U8",
+            U16 => "U16",
+            U32 => "U32",
+            U64 => "U64",
+            I8 => "I8",
+            I16 => "I16",
+            I32 => "I32",
+            F32 => "F32",
+            Bool => "Bool",
+            DateTime => "DateTime",
+            String => "Str",
+            Bytes => "Bytes",
+            Option(inner) => {
+                s.push_str("ex.");
+                s.push_str(prefix);
+                s.push_str("Option(");
+                inner.make_codec(s, prefix);
+                s.push(')');
+                return;
+            }
+            Vec(inner) => {
+                s.push_str("ex.");
+                s.push_str(prefix);
+                s.push_str("Vec(");
+                inner.make_codec(s, prefix);
+                s.push(')');
+                return;
+            }
+            Named(type_name) => {
+                s.push_str(prefix);
+                s.push_str(type_name);
+                return;
+            }
+        };
+        s.push_str("ex.");
+        s.push_str(prefix);
+        s.push_str(base);
+    }
+
+    pub(crate) fn make_decoder(&self) -> String {
+        let mut s = String::new();
+        self.make_codec(&mut s, "decode");
+        s
+    }
+
+    pub(crate) fn make_encoder(&self) -> String {
+        let mut s = String::new();
+        self.make_codec(&mut s, "encode");
+        s
+    }
+}
+
+/// A named type's import, i.e. its Rust module path and name.
+pub(crate) type TsImport = (&'static str, &'static str);
+
+/// A TypeScript type.
+///
+/// This is used for type resolution, and should be cheap to construct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TsType {
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) import: Option<TsImport>,
+    pub(crate) nullable: bool,
+    pub(crate) codec: CodecType,
+}
+
+impl From<&TypeInfoBase> for TsType {
+    fn from(base: &TypeInfoBase) -> Self {
+        use TypeInfoBase::*;
+        let (name, codec) = match base {
+            Bool => ("boolean", CodecType::Bool),
+            U8 => ("number", CodecType::U8),
+            U16 => ("number", CodecType::U16),
+            U32 => ("number", CodecType::U32),
+            U64 => ("number", CodecType::U64),
+            I8 => ("number", CodecType::I8),
+            I16 => ("number", CodecType::I16),
+            I32 => ("number", CodecType::I32),
+            F32 => ("number", CodecType::F32),
+            String => ("string", CodecType::String),
+            // serialized as an RFC 3339 string
+            DateTime => ("string", CodecType::DateTime),
+        };
+        Self {
+            name: Cow::Borrowed(name),
+            import: None,
+            nullable: false,
+            codec,
+        }
+    }
+}
+
+impl TsType {
+    pub(crate) fn named(module_path: &'static str, name: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            import: Some((module_path, name)),
+            nullable: false,
+            codec: CodecType::Named(name),
+        }
+    }
+
+    pub(crate) fn is_byte(&self) -> bool {
+        self.codec == CodecType::U8
+    }
+
+    /// Byte vectors are serialized as base64 strings in JSON.
+    pub(crate) fn byte_vec() -> Self {
+        Self {
+            name: Cow::Borrowed("string"),
+            import: None,
+            nullable: false,
+            codec: CodecType::Bytes,
+        }
+    }
+
+    /// Convert a type into an option/nullable type.
+    pub(crate) fn option(mut inner: Self) -> Self {
+        match inner.nullable {
+            false => {
+                inner.name = Cow::Owned(format!("{} | null", inner.name));
+                inner.nullable = true;
+                inner.codec = CodecType::Option(Box::new(inner.codec));
+            }
+            true => {
+                eprintln!("WARNING: doubly-nullable type `{}`", inner.name);
+            }
+        };
+        inner
+    }
+
+    /// Convert a type into a vec/array type.
+    pub(crate) fn vec(mut inner: Self) -> Self {
+        // `Array<T>` avoids precedence issues with `T | null`
+        inner.name = Cow::Owned(format!("Array<{}>", inner.name));
+        // an array has it's own nullability, independent of the inner type
+        inner.nullable = false;
+        inner.codec = CodecType::Vec(Box::new(inner.codec));
+        inner
+    }
+}
@@ -0,0 +1,167 @@
+use super::module_path::{rust_mod_path_to_path, ts_file_name, ts_runtime_path};
+use super::resolver::TypeResolver;
+use super::ts_type::TsType;
+use mech3ax_metadata_types::TypeInfoUnion;
+use minijinja::{Environment, context};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct VariantCodec {
+    pub(crate) encoder: String,
+    pub(crate) decoder: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Variant {
+    /// The union variant's name.
+    pub(crate) name: &'static str,
+    /// The union variant's index.
+    pub(crate) index: u32,
+    /// The union variant's TypeScript type, if it isn't a unit variant.
+    pub(crate) ty: Option<String>,
+    /// The union variant's exchange codec information, if it isn't a unit
+    /// variant.
+    pub(crate) codec: Option<VariantCodec>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Union {
+    /// The union's TypeScript type name.
+    pub(crate) name: &'static str,
+    /// The union's Rust module path.
+    pub(crate) module_path: &'static str,
+    /// The exchange runtime import path.
+    pub(crate) runtime: String,
+    /// The union's variant types imports.
+    pub(crate) imports: Vec<String>,
+    /// Whether the union has any unit variants.
+    pub(crate) has_unit: bool,
+    /// The union variant types.
+    pub(crate) variants: Vec<Variant>,
+    /// The union's path on the filesystem.
+    pub(crate) path: PathBuf,
+}
+
+impl Union {
+    pub(crate) fn make_type(&self) -> TsType {
+        TsType::named(self.module_path, self.name)
+    }
+
+    pub(crate) fn new(resolver: &mut TypeResolver, ui: &TypeInfoUnion) -> Self {
+        let mut imports = Vec::new();
+        // sum! unions are externally tagged in JSON, so unit variants are
+        // strings, and new type variants are single-key objects.
+        let variants = ui
+            .variants
+            .iter()
+            .copied()
+            .zip(0u32..)
+            .map(|((name, variant_type), index)| match variant_type {
+                None => Variant {
+                    name,
+                    index,
+                    ty: None,
+                    codec: None,
+                },
+                Some(type_info) => {
+                    let ty = resolver.resolve(type_info, ui.name);
+                    imports.extend(ty.import);
+                    Variant {
+                        name,
+                        index,
+                        ty: Some(ty.name.into_owned()),
+                        codec: Some(VariantCodec {
+                            encoder: ty.codec.make_encoder(),
+                            decoder: ty.codec.make_decoder(),
+                        }),
+                    }
+                }
+            })
+            .collect::<Vec<Variant>>();
+
+        let imports = TypeResolver::render_imports(ui.module_path, ui.name, imports);
+        let has_unit = variants.iter().any(|variant| variant.ty.is_none());
+
+        let mut path = rust_mod_path_to_path(ui.module_path);
+        resolver.add_directory(&path);
+        path.push(ts_file_name(ui.name));
+
+        Self {
+            name: ui.name,
+            module_path: ui.module_path,
+            runtime: ts_runtime_path(ui.module_path),
+            imports,
+            has_unit,
+            variants,
+            path,
+        }
+    }
+
+    pub(crate) fn render_impl(&self, env: &Environment<'_>) -> Result<String, minijinja::Error> {
+        let template = env.get_template("union_impl.ts")?;
+        template.render(context! { union => self })
+    }
+}
+
+pub(crate) const UNION_IMPL: &str = r#"// auto-generated by mech3ax-metadata-gen
+import * as ex from "{{ union.runtime }}";
+{%- for import in union.imports %}
+{{ import }}
+{%- endfor %}
+
+export type {{ union.name }} ={% for variant in union.variants %}
+  | {% if variant.ty %}{ {{ variant.name }}: {{ variant.ty }} }{% else %}"{{ variant.name }}"{% endif %}{% endfor %};
+
+export type {{ union.name }}Variant ={% for variant in union.variants %}
+  | "{{ variant.name }}"{% endfor %};
+
+export const {{ union.name }} = {
+  variant(v: {{ union.name }}): {{ union.name }}Variant {
+    if (typeof v === "string") {
+      return v;
+    }
+    return Object.keys(v)[0] as {{ union.name }}Variant;
+  },
+};
+
+export function decode{{ union.name }}(r: ex.Reader): {{ union.name }} {
+  const [kind, index] = r.variant("{{ union.name }}");
+  switch (index) {
+{%- for variant in union.variants %}
+    case {{ variant.index }}:
+{%- if variant.codec %}
+      r.expectNewType(kind, "{{ union.name }}");
+      return { {{ variant.name }}: {{ variant.codec.decoder }}(r) };
+{%- else %}
+      r.expectUnit(kind, "{{ union.name }}");
+      return "{{ variant.name }}";
+{%- endif %}
+{%- endfor %}
+    default:
+      throw new ex.ExchangeError(`unknown variant ${index} for "{{ union.name }}"`);
+  }
+}
+
+export function encode{{ union.name }}(w: ex.Writer, v: {{ union.name }}): void {
+{%- if union.has_unit %}
+  if (typeof v === "string") {
+    switch (v) {
+{%- for variant in union.variants %}{% if not variant.codec %}
+      case "{{ variant.name }}":
+        w.unitVariant({{ variant.index }});
+        return;
+{%- endif %}{% endfor %}
+    }
+  }
+{%- endif %}
+{%- for variant in union.variants %}{% if variant.codec %}
+  if (typeof v === "object" && "{{ variant.name }}" in v) {
+    w.newTypeVariant({{ variant.index }});
+    {{ variant.codec.encoder }}(w, v.{{ variant.name }});
+    return;
+  }
+{%- endif %}{% endfor %}
+  throw new ex.ExchangeError(`invalid variant for "{{ union.name }}"`);
+}
+"#;
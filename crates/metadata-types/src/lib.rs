@@ -5,6 +5,10 @@
 //! this is specifically C#.
 use mech3ax_timestamp::DateTime;
 
+mod schema_hash;
+
+pub use schema_hash::{schema_hash, schema_hash_of};
+
 /// Base types that can always be used, without special declaration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TypeInfoBase {
@@ -98,11 +102,26 @@ pub enum DefaultHandling {
 }
 
 /// Information for a field on a struct.
+///
+/// `serde_default` is whether the field has a `#[serde(default)]` attribute.
+/// The default value handling alone doesn't make a field optional during
+/// deserialization.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TypeInfoStructField {
     pub name: &'static str,
     pub type_info: &'static TypeInfo,
     pub default: DefaultHandling,
+    pub serde_default: bool,
+}
+
+impl TypeInfoStructField {
+    /// Whether the field can be omitted during deserialization.
+    ///
+    /// Serde implies `None` for missing `Option` fields, and other fields
+    /// need an explicit default.
+    pub fn is_optional(&self) -> bool {
+        self.serde_default || matches!(self.type_info, TypeInfo::Option(_))
+    }
 }
 
 /// Whether the struct should be a reference type (C# `class`) or a
@@ -0,0 +1,148 @@
+use crate::{DerivedMetadata, TypeInfo, TypeInfoBase, TypeInfoFlagsRepr};
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// A 64-bit FNV-1a hasher.
+///
+/// [`std::hash::DefaultHasher`] isn't guaranteed to be stable across Rust
+/// releases, but the schema hash must be the same for all builds.
+struct Fnv(u64);
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn str(&mut self, s: &str) {
+        self.write(s.as_bytes());
+        // terminate strings, so that e.g. `ab`, `c` and `a`, `bc` differ
+        self.write(&[0]);
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.write(&v.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+}
+
+fn base_name(bi: &TypeInfoBase) -> &'static str {
+    match bi {
+        TypeInfoBase::Bool => "bool",
+        TypeInfoBase::U8 => "u8",
+        TypeInfoBase::U16 => "u16",
+        TypeInfoBase::U32 => "u32",
+        TypeInfoBase::U64 => "u64",
+        TypeInfoBase::I8 => "i8",
+        TypeInfoBase::I16 => "i16",
+        TypeInfoBase::I32 => "i32",
+        TypeInfoBase::F32 => "f32",
+        TypeInfoBase::String => "string",
+        TypeInfoBase::DateTime => "datetime",
+    }
+}
+
+fn repr_name(repr: TypeInfoFlagsRepr) -> &'static str {
+    match repr {
+        TypeInfoFlagsRepr::U8 => "u8",
+        TypeInfoFlagsRepr::U16 => "u16",
+        TypeInfoFlagsRepr::U32 => "u32",
+    }
+}
+
+fn hash_type(h: &mut Fnv, ti: &TypeInfo, seen: &mut Vec<(&'static str, &'static str)>) {
+    match ti {
+        TypeInfo::Base(bi) => {
+            h.str("base");
+            h.str(base_name(bi));
+        }
+        TypeInfo::Enum(ei) => {
+            h.str("enum");
+            h.str(ei.name);
+            h.len(ei.variants.len());
+            for (name, value) in ei.variants {
+                h.str(name);
+                h.u32(*value);
+            }
+        }
+        TypeInfo::Flags(fi) => {
+            h.str("flags");
+            h.str(fi.name);
+            h.str(repr_name(fi.repr));
+            h.len(fi.variants.len());
+            for (name, index) in fi.variants {
+                h.str(name);
+                h.u32(*index);
+            }
+        }
+        TypeInfo::Vec(inner) => {
+            h.str("vec");
+            hash_type(h, inner, seen);
+        }
+        TypeInfo::Option(inner) => {
+            h.str("option");
+            hash_type(h, inner, seen);
+        }
+        TypeInfo::Struct(si) => {
+            h.str("struct");
+            h.str(si.name);
+            // only expand each type once, this also guards against recursion
+            let key = (si.module_path, si.name);
+            if seen.contains(&key) {
+                return;
+            }
+            seen.push(key);
+            h.len(si.fields.len());
+            for field in si.fields {
+                h.str(field.name);
+                h.str(&format!("{:?}", field.default));
+                hash_type(h, field.type_info, seen);
+            }
+        }
+        TypeInfo::Union(ui) => {
+            h.str("union");
+            h.str(ui.name);
+            let key = (ui.module_path, ui.name);
+            if seen.contains(&key) {
+                return;
+            }
+            seen.push(key);
+            h.len(ui.variants.len());
+            for (name, variant) in ui.variants {
+                h.str(name);
+                match variant {
+                    None => h.str("unit"),
+                    Some(inner) => hash_type(h, inner, seen),
+                }
+            }
+        }
+    }
+}
+
+/// Calculate a hash of a type's serialized shape, i.e. the names, fields,
+/// variants, and defaults of it and all types it contains.
+///
+/// This can be used as the schema hash for the exchange format, to detect
+/// data written by an incompatible version. Module paths (and so moving
+/// types), and whether a struct is a value or reference type do not change
+/// the hash.
+pub fn schema_hash_of(ti: &TypeInfo) -> u64 {
+    let mut h = Fnv(FNV_OFFSET);
+    let mut seen = Vec::new();
+    hash_type(&mut h, ti, &mut seen);
+    h.0
+}
+
+/// Calculate a hash of a type's serialized shape, see [`schema_hash_of`].
+pub fn schema_hash<T: DerivedMetadata>() -> u64 {
+    schema_hash_of(T::TYPE_INFO)
+}
+
+#[cfg(test)]
+mod tests;
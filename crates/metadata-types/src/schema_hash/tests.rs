@@ -0,0 +1,68 @@
+use super::*;
+use crate::{DefaultHandling, TypeInfoStruct, TypeInfoStructField, TypeSemantic};
+
+const FIELDS_A: &[TypeInfoStructField] = &[
+    TypeInfoStructField {
+        name: "a",
+        type_info: <u32 as DerivedMetadata>::TYPE_INFO,
+        default: DefaultHandling::Normal,
+        serde_default: false,
+    },
+    TypeInfoStructField {
+        name: "b",
+        type_info: <Option<String> as DerivedMetadata>::TYPE_INFO,
+        default: DefaultHandling::OptionIsNone,
+        serde_default: false,
+    },
+];
+
+const FIELDS_B: &[TypeInfoStructField] = &[
+    TypeInfoStructField {
+        name: "a",
+        type_info: <u32 as DerivedMetadata>::TYPE_INFO,
+        default: DefaultHandling::Normal,
+        serde_default: false,
+    },
+    TypeInfoStructField {
+        name: "b",
+        type_info: <Option<String> as DerivedMetadata>::TYPE_INFO,
+        default: DefaultHandling::Normal,
+        serde_default: false,
+    },
+];
+
+const fn make_struct(
+    fields: &'static [TypeInfoStructField],
+    semantic: TypeSemantic,
+    module_path: &'static str,
+) -> TypeInfo {
+    TypeInfo::Struct(TypeInfoStruct {
+        name: "Test",
+        semantic,
+        fields,
+        module_path,
+    })
+}
+
+#[test]
+fn hash_is_stable() {
+    // this must never change, as it would break compatibility checks
+    assert_eq!(schema_hash::<u8>(), 0x6472d1e0dc545ad7);
+}
+
+#[test]
+fn hash_differs_by_shape() {
+    assert_ne!(schema_hash::<u32>(), schema_hash::<i32>());
+    assert_ne!(schema_hash::<Vec<u32>>(), schema_hash::<Option<u32>>());
+
+    let a = make_struct(FIELDS_A, TypeSemantic::Ref, "mod_a");
+    let b = make_struct(FIELDS_B, TypeSemantic::Ref, "mod_a");
+    assert_ne!(schema_hash_of(&a), schema_hash_of(&b));
+}
+
+#[test]
+fn hash_ignores_module_and_semantic() {
+    let a = make_struct(FIELDS_A, TypeSemantic::Ref, "mod_a");
+    let b = make_struct(FIELDS_A, TypeSemantic::Val, "mod_b");
+    assert_eq!(schema_hash_of(&a), schema_hash_of(&b));
+}
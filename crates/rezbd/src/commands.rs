@@ -1,4 +1,7 @@
-use crate::{InterpOpts, ZMapOpts, ZipOpts};
+use crate::{
+    GamezMergeOpts, GamezOpts, GamezUnusedOpts, InterpMergeOpts, InterpOpts, ZMapFormat, ZMapOpts,
+    ZipOpts,
+};
 use eyre::{Context as _, Result, bail};
 use mech3ax_api_types::archive::ArchiveEntry;
 use mech3ax_api_types::gamez::{GameZ, MechlibMaterial, MechlibModel};
@@ -9,14 +12,21 @@ use mech3ax_api_types::saves::AnimActivation;
 use mech3ax_api_types::zmap::Zmap;
 use mech3ax_archive::{Mode, Version, write_archive};
 use mech3ax_common::GameType;
-use mech3ax_common::io_ext::CountingWriter;
+use mech3ax_common::io_ext::{CountingReader, CountingWriter};
+use mech3ax_gamez::bounds::recompute_gamez_bounds;
+use mech3ax_gamez::compact::{Unused, compact, find_unused};
 use mech3ax_gamez::gamez;
+use mech3ax_gamez::gamez::partitions::recompute_partitions;
 use mech3ax_gamez::mechlib::{self, write_format, write_materials, write_version};
+use mech3ax_gamez::planes::write_planes;
+use mech3ax_gamez::scene::SceneGraph;
 use mech3ax_image::write_textures;
-use mech3ax_interp::write_interp;
+use mech3ax_interp::{ScriptChange, merge_scripts, read_interp, write_interp};
 use mech3ax_motion::write_motion;
 use mech3ax_reader::write_reader;
 use mech3ax_saves::{write_activation, write_save_header};
+use mech3ax_timestamp::DateTime;
+use mech3ax_zmap::ImportOptions;
 use serde_json::Value;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Cursor, Read, Seek};
@@ -70,6 +80,38 @@ pub(crate) fn interp(opts: InterpOpts) -> Result<()> {
     Ok(())
 }
 
+/// Read scripts from either an `interp.zbd` file or JSON.
+pub(crate) fn read_scripts(path: &str) -> Result<Vec<Script>> {
+    let is_zbd = Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zbd"));
+    if is_zbd {
+        let mut read = CountingReader::new(buf_reader(path)?);
+        read_interp(&mut read).context("Failed to read interpreter data")
+    } else {
+        let buf = std::fs::read(path).context("Failed to open input")?;
+        serde_json::from_slice(&buf).context("Failed to parse input")
+    }
+}
+
+pub(crate) fn interp_merge(opts: InterpMergeOpts) -> Result<()> {
+    log::info!("INTERP: Reading `{}`", opts.input);
+    let existing = read_scripts(&opts.input)?;
+
+    log::info!("INTERP: Reading changes `{}`", opts.changes);
+    let buf = std::fs::read(&opts.changes).context("Failed to open changes")?;
+    let changes: Vec<ScriptChange> =
+        serde_json::from_slice(&buf).context("Failed to parse changes")?;
+
+    let stamp = opts.datetime.unwrap_or_else(DateTime::now_utc);
+    let scripts = merge_scripts(existing, changes, stamp).context("Failed to merge scripts")?;
+
+    let mut write = buf_writer(&opts.output)?;
+    write_interp(&mut write, &scripts).context("Failed to write interpreter data")?;
+    log::info!("INTERP: Wrote `{}`", opts.output);
+    Ok(())
+}
+
 fn _zarchive<F>(
     input: &str,
     output: &str,
@@ -236,20 +278,8 @@ pub(crate) fn textures(input: String, output: String) -> Result<()> {
     Ok(())
 }
 
-pub(crate) fn gamez(opts: ZipOpts) -> Result<()> {
-    log::info!("GAMEZ: Reading `{}` ({})", opts.input, opts.game);
-    match opts.game {
-        GameType::RC => gamez_rc(&opts)?,
-        GameType::MW => gamez_mw(&opts)?,
-        GameType::PM => gamez_pm(&opts)?,
-        GameType::CS => bail!("Crimson Skies support for GameZ isn't implemented any more"),
-    }
-    log::info!("GAMEZ: Wrote `{}`", opts.output);
-    Ok(())
-}
-
-fn gamez_mw(opts: &ZipOpts) -> Result<()> {
-    let input = buf_reader(&opts.input)?;
+fn read_gamez_zip(path: &str) -> Result<GameZ> {
+    let input = buf_reader(path)?;
     let mut zip = ZipArchive::new(input).context("Failed to open input")?;
 
     let metadata = zip_json(&mut zip, "metadata.json")?;
@@ -258,45 +288,142 @@ fn gamez_mw(opts: &ZipOpts) -> Result<()> {
     let models = zip_json(&mut zip, "models.json")?;
     let nodes = zip_json(&mut zip, "nodes.json")?;
 
-    drop(zip);
-
-    let gamez = GameZ {
-        metadata,
+    Ok(GameZ {
         textures,
         materials,
         models,
         nodes,
-    };
+        metadata,
+    })
+}
 
-    let mut write = buf_writer(&opts.output)?;
-    gamez::mw::write_gamez(&mut write, &gamez).context("Failed to write gamez data")
+fn write_gamez_zbd(path: &str, game: GameType, gamez: &GameZ) -> Result<()> {
+    let mut write = buf_writer(path)?;
+    match game {
+        GameType::RC => gamez::rc::write_gamez(&mut write, gamez),
+        GameType::MW => gamez::mw::write_gamez(&mut write, gamez),
+        GameType::PM => gamez::pm::write_gamez(&mut write, gamez),
+        GameType::CS => gamez::cs::write_gamez(&mut write, gamez),
+    }
+    .context("Failed to write gamez data")
 }
 
-fn gamez_pm(opts: &ZipOpts) -> Result<()> {
-    let input = buf_reader(&opts.input)?;
-    let mut zip = ZipArchive::new(input).context("Failed to open input")?;
+pub(crate) fn gamez(opts: GamezOpts) -> Result<()> {
+    log::info!("GAMEZ: Reading `{}` ({})", opts.input, opts.game);
+    let mut gamez = read_gamez_zip(&opts.input)?;
+
+    if opts.compact {
+        let removed = compact(&mut gamez).context("Failed to compact gamez data")?;
+        log::info!(
+            "GAMEZ: Removed {} textures, {} materials, {} models",
+            removed.textures.len(),
+            removed.materials.len(),
+            removed.models.len()
+        );
+    }
 
-    let metadata = zip_json(&mut zip, "metadata.json")?;
-    let textures = zip_json(&mut zip, "textures.json")?;
-    let materials = zip_json(&mut zip, "materials.json")?;
-    let models = zip_json(&mut zip, "models.json")?;
-    let nodes = zip_json(&mut zip, "nodes.json")?;
+    // before the partitions, which are computed from the node bounding boxes
+    if opts.recompute_bounds {
+        let diffs = recompute_gamez_bounds(&mut gamez).context("Failed to recompute bounds")?;
+        for diff in &diffs {
+            log::info!("GAMEZ: Recomputed {}", diff);
+        }
+        log::info!("GAMEZ: Recomputed {} differing bounds", diffs.len());
+    }
 
-    drop(zip);
+    if opts.recompute_partitions {
+        recompute_partitions(&mut gamez.nodes, opts.game)
+            .context("Failed to recompute partitions")?;
+    }
 
-    let gamez = GameZ {
-        metadata,
+    write_gamez_zbd(&opts.output, opts.game, &gamez)?;
+    log::info!("GAMEZ: Wrote `{}`", opts.output);
+    Ok(())
+}
+
+pub(crate) fn gamez_unused(opts: GamezUnusedOpts) -> Result<()> {
+    log::info!("GAMEZ: Reading `{}`", opts.input);
+    let gamez = read_gamez_zip(&opts.input)?;
+    let unused = find_unused(&gamez).context("Failed to find unused gamez data")?;
+
+    let Unused {
         textures,
         materials,
         models,
-        nodes,
+    } = &unused;
+    for &index in textures {
+        println!("Unused texture {} `{}`", index, gamez.textures[index].name);
+    }
+    for &index in materials {
+        println!("Unused material {}", index);
+    }
+    for &index in models {
+        println!("Unused model {}", index);
+    }
+    if unused.is_empty() {
+        println!("No unused entries found");
+    } else {
+        println!(
+            "{} textures, {} materials, {} models unused",
+            textures.len(),
+            materials.len(),
+            models.len()
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn gamez_merge(opts: GamezMergeOpts) -> Result<()> {
+    log::info!("GAMEZ: Reading `{}` ({})", opts.target, opts.game);
+    let mut target =
+        SceneGraph::new(read_gamez_zip(&opts.target)?).context("Failed to read target nodes")?;
+    log::info!("GAMEZ: Reading `{}` ({})", opts.source, opts.game);
+    let source =
+        SceneGraph::new(read_gamez_zip(&opts.source)?).context("Failed to read source nodes")?;
+
+    let Some(index) = source.find(&opts.node) else {
+        bail!("Source node `{}` not found", opts.node);
+    };
+    let parent = match &opts.parent {
+        Some(name) => match target.find(name) {
+            Some(parent) => Some(parent),
+            None => bail!("Target node `{}` not found", name),
+        },
+        None => {
+            let name = source
+                .parents(index)
+                .next()
+                .and_then(|parent| source.node(parent))
+                .map(|parent| parent.name.as_str());
+            name.and_then(|name| target.find(name))
+        }
     };
 
-    let mut write = buf_writer(&opts.output)?;
-    gamez::pm::write_gamez(&mut write, &gamez).context("Failed to write gamez data")
+    let merged = target
+        .merge_subtree(&source, index, parent, opts.game)
+        .context("Failed to merge nodes")?;
+    log::info!(
+        "GAMEZ: Copied `{}` to node {} (parent: {:?})",
+        opts.node,
+        merged,
+        parent
+    );
+
+    let gamez = target.into_gamez(opts.game);
+    write_gamez_zbd(&opts.output, opts.game, &gamez)?;
+    log::info!("GAMEZ: Wrote `{}`", opts.output);
+    Ok(())
 }
 
-fn gamez_rc(opts: &ZipOpts) -> Result<()> {
+pub(crate) fn planes(opts: ZipOpts) -> Result<()> {
+    match opts.game {
+        GameType::CS => {}
+        GameType::MW => bail!("MechWarrior 3 does not have planes"),
+        GameType::PM => bail!("Pirate's Moon does not have planes"),
+        GameType::RC => bail!("Recoil does not have planes"),
+    }
+
+    log::info!("PLANES: Reading `{}`", opts.input);
     let input = buf_reader(&opts.input)?;
     let mut zip = ZipArchive::new(input).context("Failed to open input")?;
 
@@ -308,16 +435,18 @@ fn gamez_rc(opts: &ZipOpts) -> Result<()> {
 
     drop(zip);
 
-    let gamez = GameZ {
+    let planes = GameZ {
+        metadata,
         textures,
         materials,
         models,
         nodes,
-        metadata,
     };
 
     let mut write = buf_writer(&opts.output)?;
-    gamez::rc::write_gamez(&mut write, &gamez).context("Failed to write gamez data")
+    write_planes(&mut write, &planes).context("Failed to write planes data")?;
+    log::info!("PLANES: Wrote `{}`", opts.output);
+    Ok(())
 }
 
 fn make_load_item<R: Read + Seek>(
@@ -426,10 +555,27 @@ pub(crate) fn zmap(opts: ZMapOpts) -> Result<()> {
 
     log::info!("ZMAP: Reading `{}`", opts.input);
     let buf = std::fs::read(opts.input).context("Failed to open input")?;
-    let map: Zmap = serde_json::from_slice(&buf).context("Failed to parse input")?;
+    let map: Zmap = match opts.from {
+        ZMapFormat::Json => serde_json::from_slice(&buf).context("Failed to parse input")?,
+        ZMapFormat::Svg | ZMapFormat::Geojson => {
+            let text = std::str::from_utf8(&buf).context("Failed to parse input")?;
+            let import = ImportOptions {
+                scale: opts.scale,
+                ..Default::default()
+            };
+            let features = match opts.from {
+                ZMapFormat::Svg => mech3ax_zmap::import_svg(text, &import),
+                _ => mech3ax_zmap::import_geojson(text, &import),
+            }
+            .context("Failed to import map features")?;
+            log::info!("ZMAP: Imported {} features", features.len());
+            mech3ax_zmap::map_from_features(opts.unk04, features)
+                .context("Failed to import map features")?
+        }
+    };
 
     let mut write = buf_writer(&opts.output)?;
-    mech3ax_zmap::write_map(&mut write, &map).context("Failed to write interpreter data")?;
+    mech3ax_zmap::write_map(&mut write, &map).context("Failed to write zmap data")?;
     log::info!("ZMAP: Wrote `{}`", opts.output);
     Ok(())
 }
@@ -0,0 +1,114 @@
+use crate::InterpLintOpts;
+use crate::commands::{buf_reader, read_scripts};
+use eyre::{Context as _, Result, bail};
+use mech3ax_archive::{Mode, Version, read_archive};
+use mech3ax_common::GameType;
+use mech3ax_common::io_ext::CountingReader;
+use mech3ax_interp::{KnownCommands, lint_scripts};
+use std::collections::HashSet;
+use std::path::Path;
+
+fn normalize(path: &str) -> String {
+    let path = path.replace('\\', "/").to_ascii_lowercase();
+    path.trim_start_matches("./").to_string()
+}
+
+fn is_reader_archive(name: &str) -> bool {
+    name.ends_with(".zbd") && (name.starts_with("reader") || name == "zrdr.zbd")
+}
+
+/// All files in an install directory, and all entries in reader archives, as
+/// normalized relative paths.
+struct FileIndex {
+    paths: HashSet<String>,
+}
+
+impl FileIndex {
+    fn new(root: &Path, game: GameType) -> Result<Self> {
+        let mut index = Self {
+            paths: HashSet::new(),
+        };
+        index.walk(root, root, game)?;
+        log::info!("LINT: Indexed {} files", index.paths.len());
+        Ok(index)
+    }
+
+    fn walk(&mut self, root: &Path, dir: &Path, game: GameType) -> Result<()> {
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read directory `{}`", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                self.walk(root, &path, game)?;
+                continue;
+            }
+            let Ok(rel) = path.strip_prefix(root) else {
+                continue;
+            };
+            let rel = normalize(&rel.to_string_lossy());
+            let name = rel.rsplit('/').next().unwrap_or(&rel).to_string();
+            if is_reader_archive(&name) {
+                self.add_archive(&path, game)?;
+            }
+            self.paths.insert(rel);
+        }
+        Ok(())
+    }
+
+    fn add_archive(&mut self, path: &Path, game: GameType) -> Result<()> {
+        let version = match game {
+            GameType::MW | GameType::RC | GameType::CS => Version::One,
+            GameType::PM => Version::Two(Mode::ReaderBypass),
+        };
+        let mut read = CountingReader::new(buf_reader(path)?);
+        let entries = read_archive::<_, _, eyre::Report>(&mut read, |_, _, _| Ok(()), version)
+            .with_context(|| format!("Failed to read reader archive `{}`", path.display()))?;
+        for entry in entries {
+            self.paths.insert(normalize(&entry.name));
+        }
+        Ok(())
+    }
+
+    fn contains(&self, path: &str) -> bool {
+        let path = normalize(path);
+        let suffix = format!("/{}", path);
+        self.paths
+            .iter()
+            .any(|known| *known == path || known.ends_with(&suffix))
+    }
+}
+
+pub(crate) fn interp_lint(opts: InterpLintOpts, game: GameType) -> Result<()> {
+    log::info!("LINT: Reading `{}`", opts.input);
+    let scripts = read_scripts(&opts.input)?;
+
+    let known = match &opts.reference {
+        Some(reference) => {
+            log::info!("LINT: Reading reference `{}`", reference);
+            let vanilla = read_scripts(reference).context("Failed to load reference")?;
+            Some(KnownCommands::from_scripts(&vanilla))
+        }
+        None => None,
+    };
+
+    let index = match &opts.install_dir {
+        Some(install_dir) => Some(FileIndex::new(Path::new(install_dir), game)?),
+        None => None,
+    };
+    let mut file_exists = |path: &str| index.as_ref().is_some_and(|index| index.contains(path));
+    let file_exists: Option<&mut dyn FnMut(&str) -> bool> = if opts.install_dir.is_some() {
+        Some(&mut file_exists)
+    } else {
+        None
+    };
+
+    let messages = lint_scripts(&scripts, known.as_ref(), file_exists);
+    for message in &messages {
+        println!("{}", message);
+    }
+    if !messages.is_empty() {
+        bail!("Found {} issue(s)", messages.len());
+    }
+    println!("No issues found");
+    Ok(())
+}
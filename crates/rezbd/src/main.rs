@@ -1,11 +1,14 @@
 mod commands;
+mod lint;
 mod modding;
+mod patch;
 
 use clap::Parser as _;
 use env_logger::Env;
 use eyre::Result;
 use mech3ax_archive::{Mode, Version};
 use mech3ax_common::GameType;
+use mech3ax_timestamp::DateTime;
 use mech3ax_version::VERSION;
 
 #[derive(clap::Parser)]
@@ -86,6 +89,188 @@ impl ZipOpts {
     }
 }
 
+#[derive(clap::Args)]
+struct GamezArgs {
+    #[clap(help = "The source ZIP path")]
+    input: String,
+    #[clap(help = "The destination ZBD path (will be overwritten)")]
+    output: String,
+    #[clap(
+        long,
+        help = "Recompute the node bounding boxes and model bounds from the geometry and transforms, and log any differences (a best guess at the engine's rules)"
+    )]
+    recompute_bounds: bool,
+    #[clap(
+        long,
+        help = "Recompute the world partitions and node area partitions from the node bounding boxes (a best guess at the engine's rules, so this is lossy for unmodified files)"
+    )]
+    recompute_partitions: bool,
+    #[clap(
+        long,
+        help = "Remove textures, materials, and models that aren't used by any node, and remap the indices"
+    )]
+    compact: bool,
+}
+
+impl GamezArgs {
+    fn opts(self, game: GameType) -> Result<GamezOpts> {
+        let Self {
+            input,
+            output,
+            recompute_bounds,
+            recompute_partitions,
+            compact,
+        } = self;
+        Ok(GamezOpts {
+            game,
+            input,
+            output,
+            recompute_bounds,
+            recompute_partitions,
+            compact,
+        })
+    }
+}
+
+struct GamezOpts {
+    game: GameType,
+    input: String,
+    output: String,
+    recompute_bounds: bool,
+    recompute_partitions: bool,
+    compact: bool,
+}
+
+#[derive(clap::Args)]
+struct GamezUnusedOpts {
+    #[clap(help = "The source ZIP path")]
+    input: String,
+}
+
+#[derive(clap::Args)]
+struct GamezMergeArgs {
+    #[clap(help = "The target ZIP path, to copy the nodes into")]
+    target: String,
+    #[clap(help = "The source ZIP path, to copy the nodes from")]
+    source: String,
+    #[clap(help = "The name of the source node to copy, with its descendants")]
+    node: String,
+    #[clap(help = "The destination ZBD path (will be overwritten)")]
+    output: String,
+    #[clap(
+        long,
+        help = "The name of the target node to attach the copy to (default: the target node with the same name as the source node's parent, if any)"
+    )]
+    parent: Option<String>,
+}
+
+impl GamezMergeArgs {
+    fn opts(self, game: GameType) -> Result<GamezMergeOpts> {
+        let Self {
+            target,
+            source,
+            node,
+            output,
+            parent,
+        } = self;
+        Ok(GamezMergeOpts {
+            game,
+            target,
+            source,
+            node,
+            output,
+            parent,
+        })
+    }
+}
+
+struct GamezMergeOpts {
+    game: GameType,
+    target: String,
+    source: String,
+    node: String,
+    output: String,
+    parent: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ArchiveKind {
+    Sounds,
+    Reader,
+    Motion,
+    Mechlib,
+}
+
+#[derive(clap::Args)]
+struct PatchArgs {
+    #[clap(help = "The source ZBD path")]
+    input: String,
+    #[clap(
+        long,
+        help = "The destination ZBD path (will be overwritten; default: patch the source in place)"
+    )]
+    output: Option<String>,
+    #[clap(
+        long,
+        value_enum,
+        default_value = "sounds",
+        help = "The archive kind (only matters for PM)"
+    )]
+    kind: ArchiveKind,
+    #[clap(long, value_name = "NAME=PATH", help = "Replace an entry's data")]
+    replace: Vec<String>,
+    #[clap(long, value_name = "NAME=PATH", help = "Append a new entry")]
+    add: Vec<String>,
+    #[clap(long, value_name = "NAME", help = "Delete an entry")]
+    delete: Vec<String>,
+}
+
+impl PatchArgs {
+    fn opts(self, game: GameType) -> Result<PatchOpts> {
+        let Self {
+            input,
+            output,
+            kind,
+            replace,
+            add,
+            delete,
+        } = self;
+        Ok(PatchOpts {
+            game,
+            input,
+            output,
+            kind,
+            replace,
+            add,
+            delete,
+        })
+    }
+}
+
+struct PatchOpts {
+    game: GameType,
+    input: String,
+    output: Option<String>,
+    kind: ArchiveKind,
+    replace: Vec<String>,
+    add: Vec<String>,
+    delete: Vec<String>,
+}
+
+impl PatchOpts {
+    fn version(&self) -> Version {
+        let mode = match self.kind {
+            ArchiveKind::Sounds | ArchiveKind::Mechlib => Mode::Sounds,
+            ArchiveKind::Reader => Mode::Reader,
+            ArchiveKind::Motion => Mode::Motion,
+        };
+        match self.game {
+            GameType::MW | GameType::RC | GameType::CS => Version::One,
+            GameType::PM => Version::Two(mode),
+        }
+    }
+}
+
 #[derive(clap::Args)]
 struct InterpOpts {
     #[clap(help = "The source JSON path")]
@@ -94,6 +279,37 @@ struct InterpOpts {
     output: String,
 }
 
+#[derive(clap::Args)]
+struct InterpMergeOpts {
+    #[clap(help = "The existing JSON or 'interp.zbd' path")]
+    input: String,
+    #[clap(help = "The JSON path of scripts to keep, add, or modify, in order")]
+    changes: String,
+    #[clap(help = "The destination ZBD path (will be overwritten)")]
+    output: String,
+    #[clap(
+        long,
+        help = "The RFC 3339 datetime for new or modified scripts (default: now)"
+    )]
+    datetime: Option<DateTime>,
+}
+
+#[derive(clap::Args)]
+struct InterpLintOpts {
+    #[clap(help = "The source JSON or 'interp.zbd' path")]
+    input: String,
+    #[clap(
+        long,
+        help = "A vanilla JSON or 'interp.zbd' path to learn commands and argument counts from"
+    )]
+    reference: Option<String>,
+    #[clap(
+        long,
+        help = "The game install directory to check file references against (including reader archives)"
+    )]
+    install_dir: Option<String>,
+}
+
 #[derive(clap::Args)]
 struct TextureOpts {
     #[clap(help = "The source ZIP path")]
@@ -115,21 +331,51 @@ struct ZrdOpts {
     output: String,
 }
 
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ZMapFormat {
+    Json,
+    Svg,
+    Geojson,
+}
+
 #[derive(clap::Args)]
 struct ZMapArgs {
-    #[clap(help = "The source JSON path")]
+    #[clap(help = "The source JSON, SVG, or GeoJSON path")]
     input: String,
     #[clap(help = "The destination ZMAP path (will be overwritten)")]
     output: String,
+    #[clap(long, value_enum, default_value = "json", help = "The source format")]
+    from: ZMapFormat,
+    #[clap(
+        long,
+        default_value_t = 1.0,
+        help = "Multiply SVG or GeoJSON coordinates by this value"
+    )]
+    scale: f32,
+    #[clap(
+        long,
+        default_value_t = 1,
+        help = "The map header value `unk04` for SVG or GeoJSON"
+    )]
+    unk04: u32,
 }
 
 impl ZMapArgs {
     fn opts(self, game: GameType) -> Result<ZMapOpts> {
-        let Self { input, output } = self;
+        let Self {
+            input,
+            output,
+            from,
+            scale,
+            unk04,
+        } = self;
         Ok(ZMapOpts {
             game,
             input,
             output,
+            from,
+            scale,
+            unk04,
         })
     }
 }
@@ -138,6 +384,9 @@ struct ZMapOpts {
     game: GameType,
     input: String,
     output: String,
+    from: ZMapFormat,
+    scale: f32,
+    unk04: u32,
 }
 
 #[derive(clap::Subcommand)]
@@ -148,6 +397,10 @@ enum SubCommand {
     Sounds(ZipArgs),
     #[clap(about = "Reconstruct 'interp.zbd' files from JSON")]
     Interp(InterpOpts),
+    #[clap(about = "Check 'interp.zbd' scripts (JSON or ZBD) for common mistakes")]
+    InterpLint(InterpLintOpts),
+    #[clap(about = "Add, remove, or reorder 'interp.zbd' scripts, keeping unchanged entries")]
+    InterpMerge(InterpMergeOpts),
     #[clap(about = "Reconstruct 'reader*.zbd' archives from ZIP")]
     Reader(ZipArgs),
     #[clap(
@@ -159,15 +412,29 @@ enum SubCommand {
     #[clap(about = "Reconstruct 'mechlib.zbd' archives from ZIP (MW, PM)")]
     Mechlib(ZipArgs),
     #[clap(about = "Reconstruct 'gamez.zbd' archives from ZIP")]
-    Gamez(ZipArgs),
+    Gamez(GamezArgs),
+    #[clap(
+        about = "Copy a node and its descendants, with their models, materials, and textures, from one 'gamez.zbd' ZIP into another"
+    )]
+    GamezMerge(GamezMergeArgs),
+    #[clap(
+        about = "Report textures, materials, and models in a 'gamez.zbd' ZIP that aren't used by any node"
+    )]
+    GamezUnused(GamezUnusedOpts),
+    #[clap(about = "Reconstruct 'planes.zbd' archives from ZIP (CS)")]
+    Planes(ZipArgs),
     #[clap(about = "Reconstruct 'anim.zbd' archives from ZIP (MW)")]
     Anim(ZipArgs),
     #[clap(about = "Reconstruct savegames '*.mw3' archives from ZIP (MW)")]
     Savegame(ZipArgs),
     #[clap(about = "Reconstruct reader '*.zrd' files from JSON")]
     Zrd(ZrdOpts),
-    #[clap(about = "Reconstruct map '*.zmap' files from JSON (RC)")]
+    #[clap(about = "Reconstruct map '*.zmap' files from JSON, SVG, or GeoJSON (RC)")]
     Zmap(ZMapArgs),
+    #[clap(
+        about = "Delete, replace, then add archive entries, without extracting (deletes, replaces, and adds are applied in that order)"
+    )]
+    Patch(PatchArgs),
 }
 
 fn main() -> Result<()> {
@@ -188,6 +455,8 @@ fn main() -> Result<()> {
     match cli.subcmd {
         SubCommand::Sounds(args) => commands::sounds(args.opts(game)?),
         SubCommand::Interp(opts) => commands::interp(opts),
+        SubCommand::InterpLint(opts) => lint::interp_lint(opts, game),
+        SubCommand::InterpMerge(opts) => commands::interp_merge(opts),
         SubCommand::Reader(args) => commands::reader(args.opts(game)?),
         SubCommand::Textures(TextureOpts {
             input,
@@ -202,10 +471,14 @@ fn main() -> Result<()> {
         SubCommand::Motion(args) => commands::motion(args.opts(game)?),
         SubCommand::Mechlib(args) => commands::mechlib(args.opts(game)?),
         SubCommand::Gamez(args) => commands::gamez(args.opts(game)?),
+        SubCommand::GamezMerge(args) => commands::gamez_merge(args.opts(game)?),
+        SubCommand::GamezUnused(opts) => commands::gamez_unused(opts),
+        SubCommand::Planes(args) => commands::planes(args.opts(game)?),
         SubCommand::Anim(args) => commands::anim(args.opts(game)?),
         SubCommand::Savegame(args) => commands::savegame(args.opts(game)?),
         SubCommand::Zrd(opts) => modding::zrd(opts),
         SubCommand::Zmap(args) => commands::zmap(args.opts(game)?),
+        SubCommand::Patch(args) => patch::patch(args.opts(game)?),
         SubCommand::License => commands::license(),
     }
 }
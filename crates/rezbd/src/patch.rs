@@ -0,0 +1,62 @@
+use crate::PatchOpts;
+use crate::commands::buf_reader;
+use eyre::{Context as _, Result, eyre};
+use mech3ax_api_types::archive::{ArchiveEntry, ArchiveEntryInfo, ArchiveEntryInfoValid};
+use mech3ax_archive::{ArchivePatch, ArchiveReader, patch_archive};
+use mech3ax_common::io_ext::{CountingReader, CountingWriter};
+use mech3ax_timestamp::DateTime;
+
+fn split_arg(arg: &str) -> Result<(String, String)> {
+    let (name, path) = arg
+        .split_once('=')
+        .ok_or_else(|| eyre!("Expected `NAME=PATH`, but got `{}`", arg))?;
+    Ok((name.to_string(), path.to_string()))
+}
+
+fn read_data(name: &str, path: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read `{}` for `{}`", path, name))
+}
+
+pub(crate) fn patch(opts: PatchOpts) -> Result<()> {
+    let version = opts.version();
+    let output = opts.output.as_deref().unwrap_or(&opts.input);
+
+    let mut patches = Vec::new();
+    for name in opts.delete {
+        patches.push(ArchivePatch::Delete { name });
+    }
+    for arg in &opts.replace {
+        let (name, path) = split_arg(arg)?;
+        let data = read_data(&name, &path)?;
+        patches.push(ArchivePatch::Replace { name, data });
+    }
+    let datetime = DateTime::now_utc();
+    for arg in &opts.add {
+        let (name, path) = split_arg(arg)?;
+        let data = read_data(&name, &path)?;
+        let entry = ArchiveEntry {
+            name,
+            rename: None,
+            flags: 0,
+            info: ArchiveEntryInfo::Valid(ArchiveEntryInfoValid {
+                comment: String::new(),
+                datetime,
+            }),
+        };
+        patches.push(ArchivePatch::Add { entry, data });
+    }
+
+    log::info!("PATCH: Reading `{}` ({})", opts.input, opts.game);
+    let read = CountingReader::new(buf_reader(&opts.input)?);
+    let mut reader = ArchiveReader::new(read, version).context("Failed to read archive table")?;
+
+    // write to memory first, so the input can be patched in place
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    let manifest = patch_archive(&mut reader, &mut write, patches, version)
+        .context("Failed to patch archive")?;
+    drop(reader);
+
+    std::fs::write(output, write.into_inner()).context("Failed to write output")?;
+    log::info!("PATCH: Wrote `{}` ({} entries)", output, manifest.len());
+    Ok(())
+}
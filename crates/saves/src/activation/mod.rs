@@ -8,6 +8,7 @@ pub use read::read_activation;
 pub use write::write_activation;
 
 const VALUES_SIZE: usize = 9 * 4;
+pub const NODE_STATE_SIZE: usize = 68;
 
 type Status = Maybe<u8, ActivationStatus>;
 
@@ -1,4 +1,4 @@
-use super::{AnimActivationC, VALUES_SIZE};
+use super::{AnimActivationC, NODE_STATE_SIZE, VALUES_SIZE};
 use log::trace;
 use mech3ax_api_types::Bytes;
 use mech3ax_api_types::saves::{ActivationType, AnimActivation};
@@ -125,8 +125,11 @@ pub fn read_activation(read: &mut CountingReader<impl Read>) -> Result<AnimActiv
 
     let node_states = (0..activation.count)
         .map(|i| {
-            trace!("Reading node state {} ({}) at {}", i, 68, read.offset);
-            let mut buf = vec![0u8; 68];
+            trace!(
+                "Reading node state {} ({}) at {}",
+                i, NODE_STATE_SIZE, read.offset
+            );
+            let mut buf = vec![0u8; NODE_STATE_SIZE];
             read.read_exact(&mut buf)?;
             Ok(buf)
         })
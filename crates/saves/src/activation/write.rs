@@ -1,4 +1,4 @@
-use super::{AnimActivationC, VALUES_SIZE};
+use super::{AnimActivationC, NODE_STATE_SIZE, VALUES_SIZE};
 use mech3ax_api_types::saves::{ActivationType, AnimActivation};
 use mech3ax_common::io_ext::CountingWriter;
 use mech3ax_common::{Result, assert_with_msg};
@@ -72,6 +72,13 @@ pub fn write_activation(
 
     write.write_struct(&activ)?;
     for node_state in &activation.node_states {
+        if node_state.len() != NODE_STATE_SIZE {
+            return Err(assert_with_msg!(
+                "Expected node state to have exactly {} bytes, but was {}",
+                NODE_STATE_SIZE,
+                node_state.len()
+            ));
+        }
         write.write_all(node_state)?;
     }
     Ok(())
@@ -2,6 +2,8 @@
 #![allow(clippy::identity_op)]
 mod activation;
 mod header;
+mod savegame;
 
-pub use activation::{read_activation, write_activation};
+pub use activation::{NODE_STATE_SIZE, read_activation, write_activation};
 pub use header::{read_save_header, write_save_header};
+pub use savegame::{Savegame, read_savegame, write_savegame};
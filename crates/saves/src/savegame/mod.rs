@@ -0,0 +1,117 @@
+mod read;
+mod write;
+
+use crate::activation::NODE_STATE_SIZE;
+use mech3ax_api_types::archive::ArchiveEntry;
+use mech3ax_api_types::saves::{ActivationStatus, AnimActivation};
+use mech3ax_common::{Result, assert_with_msg};
+pub use read::read_savegame;
+pub use write::write_savegame;
+
+const SAVE_HEADER_NAME: &str = "zSaveHeader";
+
+/// A savegame archive, with all anim activations decoded.
+///
+/// The activations are stored in archive order, skipping the save header.
+#[derive(Debug)]
+pub struct Savegame {
+    pub entries: Vec<ArchiveEntry>,
+    pub activations: Vec<AnimActivation>,
+}
+
+impl Savegame {
+    pub fn find(&self, name: &str) -> Option<&AnimActivation> {
+        self.activations.iter().find(|activ| activ.name == name)
+    }
+
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut AnimActivation> {
+        self.activations.iter_mut().find(|activ| activ.name == name)
+    }
+
+    fn find_or_err(&mut self, name: &str) -> Result<&mut AnimActivation> {
+        self.find_mut(name)
+            .ok_or_else(|| assert_with_msg!("Anim activation `{}` not found", name))
+    }
+
+    /// Set the status of the named anim activation.
+    pub fn set_status(&mut self, name: &str, status: ActivationStatus) -> Result<()> {
+        let activ = self.find_or_err(name)?;
+        activ.status = status;
+        Ok(())
+    }
+
+    /// Mark the named anim activation as executed.
+    pub fn trigger(&mut self, name: &str) -> Result<()> {
+        self.set_status(name, ActivationStatus::Executed)
+    }
+
+    /// Mark the named anim activation as not executed, with the given status.
+    ///
+    /// Which status the engine expects for an activation that hasn't executed
+    /// isn't known, so it must be chosen by the caller (e.g. copied from an
+    /// earlier savegame). The node states are kept as they are.
+    pub fn reset(&mut self, name: &str, status: ActivationStatus) -> Result<()> {
+        if status == ActivationStatus::Executed {
+            return Err(assert_with_msg!(
+                "Expected reset status for `{}` to not be {:?}",
+                name,
+                status
+            ));
+        }
+        self.set_status(name, status)
+    }
+
+    /// Replace a single node state of the named anim activation.
+    pub fn set_node_state(&mut self, name: &str, index: usize, state: &[u8]) -> Result<()> {
+        check_node_state(name, state)?;
+        let activ = self.find_or_err(name)?;
+        let count = activ.node_states.len();
+        let node_state = activ.node_states.get_mut(index).ok_or_else(|| {
+            assert_with_msg!(
+                "Expected node state index for `{}` to be < {}, but was {}",
+                name,
+                count,
+                index
+            )
+        })?;
+        node_state.copy_from_slice(state);
+        Ok(())
+    }
+
+    /// Append a node state to the named anim activation.
+    pub fn push_node_state(&mut self, name: &str, state: &[u8]) -> Result<()> {
+        check_node_state(name, state)?;
+        let activ = self.find_or_err(name)?;
+        if activ.node_states.len() >= usize::from(u8::MAX) {
+            return Err(assert_with_msg!(
+                "Expected activation `{}` to have {} node states or fewer",
+                name,
+                u8::MAX
+            ));
+        }
+        activ.node_states.push(state.to_vec());
+        Ok(())
+    }
+
+    /// Remove all node states from the named anim activation.
+    pub fn clear_node_states(&mut self, name: &str) -> Result<()> {
+        let activ = self.find_or_err(name)?;
+        activ.node_states.clear();
+        Ok(())
+    }
+}
+
+fn check_node_state(name: &str, state: &[u8]) -> Result<()> {
+    if state.len() != NODE_STATE_SIZE {
+        return Err(assert_with_msg!(
+            "Expected node state for `{}` to have exactly {} bytes, but was {}",
+            name,
+            NODE_STATE_SIZE,
+            state.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
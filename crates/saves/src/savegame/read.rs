@@ -0,0 +1,31 @@
+use super::{SAVE_HEADER_NAME, Savegame};
+use crate::{read_activation, read_save_header};
+use mech3ax_archive::{Version, read_archive};
+use mech3ax_common::Result;
+use mech3ax_common::io_ext::CountingReader;
+use std::io::{Cursor, Read, Seek};
+
+pub fn read_savegame(read: &mut CountingReader<impl Read + Seek>) -> Result<Savegame> {
+    let mut activations = Vec::new();
+    let entries = read_archive(
+        read,
+        |name, data, offset| {
+            let mut read = CountingReader::new(Cursor::new(data));
+            // translate to absolute offset
+            read.offset = offset;
+            match name {
+                SAVE_HEADER_NAME => read_save_header(&mut read),
+                _ => {
+                    let activation = read_activation(&mut read)?;
+                    activations.push(activation);
+                    Ok(())
+                }
+            }
+        },
+        Version::One,
+    )?;
+    Ok(Savegame {
+        entries,
+        activations,
+    })
+}
@@ -0,0 +1,101 @@
+use super::*;
+use mech3ax_api_types::archive::{ArchiveEntryInfo, ArchiveEntryInfoInvalid};
+use mech3ax_api_types::saves::ActivationType;
+use mech3ax_common::io_ext::{CountingReader, CountingWriter};
+use std::io::Cursor;
+
+fn entry(name: &str) -> ArchiveEntry {
+    ArchiveEntry {
+        name: name.to_string(),
+        rename: None,
+        flags: 0,
+        info: ArchiveEntryInfo::Invalid(ArchiveEntryInfoInvalid {
+            comment: vec![0; 64],
+            filetime: 0,
+        }),
+    }
+}
+
+fn activation(name: &str, node_states: usize) -> AnimActivation {
+    AnimActivation {
+        name: name.to_string(),
+        node_index: None,
+        status: ActivationStatus::Invalid,
+        type_: ActivationType::One,
+        node_states: vec![vec![0; NODE_STATE_SIZE]; node_states],
+        ptr: None,
+    }
+}
+
+fn savegame() -> Savegame {
+    Savegame {
+        entries: vec![entry(SAVE_HEADER_NAME), entry("foo"), entry("bar")],
+        activations: vec![activation("foo", 0), activation("bar", 2)],
+    }
+}
+
+fn write(save: &Savegame) -> Vec<u8> {
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    write_savegame(&mut write, save).unwrap();
+    write.into_inner()
+}
+
+#[test]
+fn savegame_roundtrip() {
+    let expected = write(&savegame());
+    let mut read = CountingReader::new(Cursor::new(expected.clone()));
+    let save = read_savegame(&mut read).unwrap();
+    assert_eq!(save.activations.len(), 2);
+    let actual = write(&save);
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn savegame_edit_roundtrip() {
+    let mut save = savegame();
+    save.trigger("foo").unwrap();
+    save.reset("bar", ActivationStatus::Unk1).unwrap();
+    assert!(save.reset("bar", ActivationStatus::Executed).is_err());
+    save.set_node_state("bar", 1, &[0xFF; NODE_STATE_SIZE])
+        .unwrap();
+    save.push_node_state("foo", &[0xAA; NODE_STATE_SIZE])
+        .unwrap();
+
+    let data = write(&save);
+    let mut read = CountingReader::new(Cursor::new(data));
+    let save = read_savegame(&mut read).unwrap();
+
+    let foo = save.find("foo").unwrap();
+    assert_eq!(foo.status, ActivationStatus::Executed);
+    assert_eq!(foo.node_states, vec![vec![0xAA; NODE_STATE_SIZE]]);
+    let bar = save.find("bar").unwrap();
+    assert_eq!(bar.status, ActivationStatus::Unk1);
+    assert_eq!(bar.node_states[0], vec![0; NODE_STATE_SIZE]);
+    assert_eq!(bar.node_states[1], vec![0xFF; NODE_STATE_SIZE]);
+}
+
+#[test]
+fn savegame_edit_not_found() {
+    let mut save = savegame();
+    assert!(save.trigger("baz").is_err());
+    assert!(save.clear_node_states("baz").is_err());
+}
+
+#[test]
+fn savegame_edit_node_state_invalid() {
+    let mut save = savegame();
+    assert!(
+        save.set_node_state("bar", 2, &[0; NODE_STATE_SIZE])
+            .is_err()
+    );
+    assert!(save.set_node_state("bar", 0, &[0; 4]).is_err());
+    assert!(save.push_node_state("foo", &[]).is_err());
+}
+
+#[test]
+fn savegame_activation_count_mismatch() {
+    let mut save = savegame();
+    save.activations.pop();
+    let mut write = CountingWriter::new(Vec::new(), 0);
+    assert!(write_savegame(&mut write, &save).is_err());
+}
@@ -0,0 +1,40 @@
+use super::{SAVE_HEADER_NAME, Savegame};
+use crate::{write_activation, write_save_header};
+use mech3ax_archive::{Version, write_archive};
+use mech3ax_common::io_ext::CountingWriter;
+use mech3ax_common::{Result, assert_with_msg};
+use std::io::Write;
+
+pub fn write_savegame(write: &mut CountingWriter<impl Write>, save: &Savegame) -> Result<()> {
+    let count = save
+        .entries
+        .iter()
+        .filter(|entry| entry.rename.as_deref().unwrap_or(&entry.name) != SAVE_HEADER_NAME)
+        .count();
+    if count != save.activations.len() {
+        return Err(assert_with_msg!(
+            "Expected savegame to have {} anim activations, but was {}",
+            count,
+            save.activations.len()
+        ));
+    }
+
+    let mut activations = save.activations.iter();
+    write_archive(
+        write,
+        &save.entries,
+        |name, offset| {
+            let mut buf = CountingWriter::new(Vec::new(), offset);
+            match name {
+                SAVE_HEADER_NAME => write_save_header(&mut buf)?,
+                _ => {
+                    // the count was checked above
+                    let activation = activations.next().unwrap();
+                    write_activation(&mut buf, activation)?;
+                }
+            }
+            Ok(buf.into_inner())
+        },
+        Version::One,
+    )
+}
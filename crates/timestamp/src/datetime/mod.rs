@@ -2,10 +2,11 @@ use crate::consts::date;
 use serde::{de, ser};
 use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
 use std::sync::LazyLock;
 use time::format_description::well_known::Rfc3339;
 use time::format_description::{Component, FormatItem, modifier};
-use time::{PrimitiveDateTime, Time};
+use time::{OffsetDateTime, PrimitiveDateTime, Time};
 const TIME_MIN: Time = Time::MIDNIGHT;
 const UNIX_EPOCH: PrimitiveDateTime = PrimitiveDateTime::new(date(1970, 1, 1), TIME_MIN);
 
@@ -58,6 +59,22 @@ pub struct DateTime(pub(crate) PrimitiveDateTime);
 
 impl DateTime {
     pub const UNIX_EPOCH: Self = Self(UNIX_EPOCH);
+
+    /// The current time in UTC, truncated to whole seconds.
+    pub fn now_utc() -> Self {
+        let dt = OffsetDateTime::now_utc();
+        let dt = PrimitiveDateTime::new(dt.date(), dt.time());
+        Self(dt.replace_nanosecond(0).unwrap_or(dt))
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = time::error::Parse;
+
+    /// Parse a RFC 3339 datetime, e.g. `2001-02-03T04:05:06Z`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PrimitiveDateTime::parse(s, &Rfc3339).map(Self)
+    }
 }
 
 impl fmt::Debug for DateTime {
@@ -55,3 +55,10 @@ fn time_readable() {
         ],
     );
 }
+
+#[test]
+fn time_from_str() {
+    let dt: DateTime = "1970-01-01T00:00:00Z".parse().unwrap();
+    assert_eq!(dt, DateTime::UNIX_EPOCH);
+    assert!("1970-01-01".parse::<DateTime>().is_err());
+}
@@ -13,5 +13,10 @@ pub fn to_timestamp(dt: &DateTime) -> u32 {
     dt.0.assume_utc().unix_timestamp() as _
 }
 
+/// Convert a datetime to a UNIX timestamp, or `None` if it is out of range.
+pub fn try_to_timestamp(dt: &DateTime) -> Option<u32> {
+    dt.0.assume_utc().unix_timestamp().try_into().ok()
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,4 +1,4 @@
-use super::{DateTime, from_timestamp, to_timestamp};
+use super::{DateTime, from_timestamp, to_timestamp, try_to_timestamp};
 use crate::consts::{date, time};
 use time::PrimitiveDateTime;
 
@@ -33,3 +33,14 @@ fn timestamp_max() {
     let ts = to_timestamp(&dt);
     assert_eq!(ts, u32::MAX, "timestamp");
 }
+
+#[test]
+fn try_timestamp_range() {
+    let dt = from_timestamp(u32::MAX);
+    assert_eq!(try_to_timestamp(&dt), Some(u32::MAX));
+    let dt = DateTime(PrimitiveDateTime::new(
+        date(1969, 12, 31),
+        time(23, 59, 59, 0),
+    ));
+    assert_eq!(try_to_timestamp(&dt), None);
+}
@@ -0,0 +1,50 @@
+use crate::{ArchiveOpts, ExtractEntryOpts};
+use eyre::{Context as _, Result, eyre};
+use mech3ax_archive::ArchiveReader;
+use mech3ax_common::io_ext::CountingReader;
+use std::fs::File;
+use std::io::BufReader;
+
+fn open_archive(opts: &ArchiveOpts) -> Result<ArchiveReader<BufReader<File>>> {
+    let input = File::open(&opts.input).context("Failed to open input")?;
+    let read = CountingReader::new(BufReader::new(input));
+    ArchiveReader::new(read, opts.version()).context("Failed to read archive table")
+}
+
+pub(crate) fn ls(opts: ArchiveOpts) -> Result<()> {
+    log::info!("LS: Reading `{}` ({})", opts.input, opts.game);
+    let reader = open_archive(&opts)?;
+    println!(
+        "{:>5} {:>10} {:>10} {:>10}  NAME",
+        "INDEX", "START", "LENGTH", "FLAGS"
+    );
+    for (index, entry) in reader.entries().iter().enumerate() {
+        print!(
+            "{:>5} {:>10} {:>10} 0x{:08X}  {}",
+            index, entry.start, entry.len, entry.flags, entry.name
+        );
+        match &entry.rename {
+            Some(rename) => println!(" (as `{}`)", rename),
+            None => println!(),
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn extract_entry(opts: ExtractEntryOpts) -> Result<()> {
+    log::info!(
+        "EXTRACT: Reading `{}` ({})",
+        opts.archive.input,
+        opts.archive.game
+    );
+    let mut reader = open_archive(&opts.archive)?;
+    let index = reader
+        .find(&opts.name)
+        .ok_or_else(|| eyre!("Entry `{}` not found in archive", opts.name))?;
+    let data = reader
+        .read_entry(index)
+        .with_context(|| format!("Failed to read entry `{}`", opts.name))?;
+    std::fs::write(&opts.output, data).context("Failed to write output")?;
+    log::info!("EXTRACT: Wrote `{}`", opts.output);
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use crate::{AutoOpts, InterpOpts, MsgOpts, ReaderOpts, ZMapOpts, ZipOpts, commands};
+use eyre::{Context as _, Result, bail, eyre};
+use mech3ax_common::GameType;
+use mech3ax_common::io_ext::CountingReader;
+use mech3ax_detect::{ALL_GAMES, Detection, FileKind, detect};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+pub(crate) fn detect_file(input: &str) -> Result<Detection> {
+    let name = Path::new(input).file_name().and_then(|name| name.to_str());
+    let file = File::open(input).context("Failed to open input")?;
+    let mut read = CountingReader::new(BufReader::new(file));
+    detect(&mut read, name)
+        .context("Failed to detect file kind")?
+        .ok_or_else(|| eyre!("Unrecognised file `{}`", input))
+}
+
+pub(crate) fn detect_cmd(input: String, game: Option<GameType>) -> Result<()> {
+    let detection = detect_file(&input)?;
+    println!("{}", detection);
+    if let Some(game) = game
+        && !detection.games.contains(&game)
+    {
+        bail!("File is {}, but {} was specified", detection, game);
+    }
+    Ok(())
+}
+
+fn select_game(detection: &Detection, game: Option<GameType>) -> Result<GameType> {
+    if let Some(game) = game {
+        if !detection.games.contains(&game) {
+            bail!("File is {}, but {} was specified", detection, game);
+        }
+        return Ok(game);
+    }
+    match (detection.kind, detection.games) {
+        // messages are read differently per game (except MW and PM)
+        (FileKind::Messages, games) if games == ALL_GAMES => {
+            bail!("File is {}, please specify the game", detection)
+        }
+        // otherwise, the data is read the same way for all detected games
+        (_, [game, ..]) => Ok(*game),
+        (_, []) => bail!("File is {}, but no game matches", detection),
+    }
+}
+
+/// The extension of the extracted output for a file kind.
+pub(crate) fn output_ext(kind: FileKind) -> &'static str {
+    match kind {
+        FileKind::Interp | FileKind::Messages | FileKind::Zmap => "json",
+        _ => "zip",
+    }
+}
+
+/// Extract a file of a known kind.
+pub(crate) fn extract(kind: FileKind, game: GameType, input: String, output: String) -> Result<()> {
+    match kind {
+        FileKind::Sounds => commands::sounds(ZipOpts {
+            game,
+            input,
+            output,
+        }),
+        FileKind::Interp => commands::interp(InterpOpts { input, output }),
+        FileKind::Reader => commands::reader(ReaderOpts {
+            game,
+            input,
+            output,
+            skip_crc: false,
+        }),
+        FileKind::Messages => commands::messages(MsgOpts {
+            game,
+            input,
+            output,
+        }),
+        FileKind::Textures => commands::textures(input, output),
+        FileKind::Motion => commands::motion(ZipOpts {
+            game,
+            input,
+            output,
+        }),
+        FileKind::Mechlib => commands::mechlib(ZipOpts {
+            game,
+            input,
+            output,
+        }),
+        FileKind::Gamez => commands::gamez(ZipOpts {
+            game,
+            input,
+            output,
+        }),
+        FileKind::Anim => commands::anim(ZipOpts {
+            game,
+            input,
+            output,
+        }),
+        FileKind::Savegame => commands::savegame(ZipOpts {
+            game,
+            input,
+            output,
+        }),
+        FileKind::Zmap => commands::zmap(ZMapOpts {
+            game,
+            input,
+            output,
+            render: None,
+        }),
+        FileKind::Archive => bail!(
+            "File is an archive with unrecognised entries, use `ls` or `extract-entry` instead"
+        ),
+    }
+}
+
+pub(crate) fn auto(opts: AutoOpts) -> Result<()> {
+    let detection = detect_file(&opts.input)?;
+    let game = select_game(&detection, opts.game)?;
+    log::info!("AUTO: Detected `{}` as {}", opts.input, detection);
+    extract(detection.kind, game, opts.input, opts.output)
+}
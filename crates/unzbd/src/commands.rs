@@ -1,17 +1,24 @@
-use crate::{InterpOpts, MsgOpts, ReaderOpts, ZMapOpts, ZipOpts};
+use crate::{
+    CyclePreviewOpts, InterpOpts, MsgOpts, OverviewOpts, ReaderOpts, SoilMapOpts, ZMapOpts, ZipOpts,
+};
 use eyre::{Context as _, Result, bail};
 use image::ImageFormat;
+use mech3ax_api_types::gamez::materials::Material;
+use mech3ax_api_types::gamez::{GameZ, SoilMapCoverage, SoilMapLegend};
 use mech3ax_archive::{Mode, Version, read_archive};
 use mech3ax_common::GameType;
 use mech3ax_common::io_ext::CountingReader;
 use mech3ax_gamez::gamez;
 use mech3ax_gamez::mechlib::{self, read_format, read_materials, read_version};
-use mech3ax_image::read_textures;
+use mech3ax_gamez::planes::read_planes;
+use mech3ax_gamez::render::{SOIL_BACKGROUND, render_overview, render_soil_map};
+use mech3ax_image::{read_textures, write_cycle_apng};
 use mech3ax_interp::read_interp;
 use mech3ax_messages::read_messages;
 use mech3ax_motion::read_motion;
 use mech3ax_reader::read_reader;
 use mech3ax_saves::{read_activation, read_save_header};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Cursor, Seek, Write};
 use std::path::Path;
@@ -256,7 +263,7 @@ pub(crate) fn gamez(opts: ZipOpts) -> Result<()> {
         GameType::RC => gamez_rc(&opts)?,
         GameType::MW => gamez_mw(&opts)?,
         GameType::PM => gamez_pm(&opts)?,
-        GameType::CS => bail!("Crimson Skies support for GameZ isn't implemented any more"),
+        GameType::CS => gamez_cs(&opts)?,
     }
     log::info!("GAMEZ: Wrote `{}`", opts.output);
     Ok(())
@@ -298,6 +305,24 @@ fn gamez_pm(opts: &ZipOpts) -> Result<()> {
     Ok(())
 }
 
+fn gamez_cs(opts: &ZipOpts) -> Result<()> {
+    let mut input = CountingReader::new(buf_reader(&opts.input)?);
+    let gamez = gamez::cs::read_gamez(&mut input).context("Failed to read gamez data")?;
+    drop(input);
+
+    let output = buf_writer(&opts.output)?;
+    let mut zip = ZipWriter::new(output);
+
+    zip_json(&mut zip, "metadata.json", &gamez.metadata)?;
+    zip_json(&mut zip, "textures.json", &gamez.textures)?;
+    zip_json(&mut zip, "materials.json", &gamez.materials)?;
+    zip_json(&mut zip, "models.json", &gamez.models)?;
+    zip_json(&mut zip, "nodes.json", &gamez.nodes)?;
+
+    zip.finish()?;
+    Ok(())
+}
+
 fn gamez_rc(opts: &ZipOpts) -> Result<()> {
     let mut input = CountingReader::new(buf_reader(&opts.input)?);
     let gamez = gamez::rc::read_gamez(&mut input).context("Failed to read gamez data")?;
@@ -316,6 +341,33 @@ fn gamez_rc(opts: &ZipOpts) -> Result<()> {
     Ok(())
 }
 
+pub(crate) fn planes(opts: ZipOpts) -> Result<()> {
+    match opts.game {
+        GameType::CS => {}
+        GameType::MW => bail!("MechWarrior 3 does not have planes"),
+        GameType::PM => bail!("Pirate's Moon does not have planes"),
+        GameType::RC => bail!("Recoil does not have planes"),
+    }
+
+    log::info!("PLANES: Reading `{}`", opts.input);
+    let mut input = CountingReader::new(buf_reader(&opts.input)?);
+    let planes = read_planes(&mut input).context("Failed to read planes data")?;
+    drop(input);
+
+    let output = buf_writer(&opts.output)?;
+    let mut zip = ZipWriter::new(output);
+
+    zip_json(&mut zip, "metadata.json", &planes.metadata)?;
+    zip_json(&mut zip, "textures.json", &planes.textures)?;
+    zip_json(&mut zip, "materials.json", &planes.materials)?;
+    zip_json(&mut zip, "models.json", &planes.models)?;
+    zip_json(&mut zip, "nodes.json", &planes.nodes)?;
+
+    zip.finish()?;
+    log::info!("PLANES: Wrote `{}`", opts.output);
+    Ok(())
+}
+
 pub(crate) fn anim(opts: ZipOpts) -> Result<()> {
     match opts.game {
         GameType::MW => {}
@@ -415,12 +467,161 @@ pub(crate) fn zmap(opts: ZMapOpts) -> Result<()> {
     log::info!("ZMAP: Reading `{}`", opts.input);
     let mut input = CountingReader::new(buf_reader(opts.input)?);
     let map = mech3ax_zmap::read_map(&mut input).context("Failed to read zmap data")?;
-    let contents = serde_json::to_vec_pretty(&map)?;
-    std::fs::write(&opts.output, contents).context("Failed to write output")?;
+    match opts.render {
+        Some(render) => {
+            let image =
+                mech3ax_zmap::render_map(&map, &render).context("Failed to render zmap data")?;
+            image
+                .save_with_format(&opts.output, ImageFormat::Png)
+                .context("Failed to write output")?;
+        }
+        None => {
+            let contents = serde_json::to_vec_pretty(&map)?;
+            std::fs::write(&opts.output, contents).context("Failed to write output")?;
+        }
+    }
     log::info!("ZMAP: Wrote `{}`", opts.output);
     Ok(())
 }
 
+fn read_gamez(game: GameType, path: &str) -> Result<GameZ> {
+    let mut input = CountingReader::new(buf_reader(path)?);
+    let gamez = match game {
+        GameType::MW => gamez::mw::read_gamez(&mut input),
+        GameType::PM => gamez::pm::read_gamez(&mut input),
+        GameType::RC => gamez::rc::read_gamez(&mut input),
+        GameType::CS => gamez::cs::read_gamez(&mut input),
+    };
+    gamez.context("Failed to read gamez data")
+}
+
+fn hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+pub(crate) fn soil_map(opts: SoilMapOpts) -> Result<()> {
+    log::info!("SOIL MAP: Reading `{}` ({})", opts.input, opts.game);
+    let gamez = read_gamez(opts.game, &opts.input)?;
+
+    let map = render_soil_map(&gamez, &opts.render).context("Failed to render soil map")?;
+    map.image
+        .save_with_format(&opts.output, ImageFormat::Png)
+        .context("Failed to write output")?;
+
+    let soils = map
+        .legend
+        .iter()
+        .map(|coverage| SoilMapCoverage {
+            soil: coverage.soil,
+            color: hex_color(coverage.color),
+            pixels: coverage.pixels,
+        })
+        .collect();
+    let legend = SoilMapLegend {
+        width: map.image.width(),
+        height: map.image.height(),
+        background: hex_color(SOIL_BACKGROUND),
+        soils,
+    };
+    let contents = serde_json::to_vec_pretty(&legend)?;
+    std::fs::write(&opts.legend, contents).context("Failed to write legend")?;
+    log::info!("SOIL MAP: Wrote `{}` and `{}`", opts.output, opts.legend);
+    Ok(())
+}
+
+pub(crate) fn overview(opts: OverviewOpts) -> Result<()> {
+    log::info!("OVERVIEW: Reading `{}` ({})", opts.input, opts.game);
+    let gamez = read_gamez(opts.game, &opts.input)?;
+
+    let mut textures = HashMap::new();
+    for path in &opts.textures {
+        log::info!("OVERVIEW: Reading `{}`", path);
+        let mut input = CountingReader::new(buf_reader(path)?);
+        read_textures::<_, eyre::Report>(&mut input, |name, image| {
+            // the first match is used
+            if !textures.contains_key(name) {
+                textures.insert(name.to_string(), image.to_rgba8());
+            }
+            Ok(())
+        })
+        .with_context(|| format!("Failed to read texture data from `{}`", path))?;
+    }
+
+    let image =
+        render_overview(&gamez, &textures, &opts.render).context("Failed to render overview")?;
+    image
+        .save_with_format(&opts.output, ImageFormat::Png)
+        .context("Failed to write output")?;
+    log::info!("OVERVIEW: Wrote `{}`", opts.output);
+    Ok(())
+}
+
+pub(crate) fn cycle_preview(opts: CyclePreviewOpts) -> Result<()> {
+    log::info!("CYCLE: Reading `{}` ({})", opts.input, opts.game);
+    let gamez = read_gamez(opts.game, &opts.input)?;
+
+    let cycle = match gamez.materials.get(opts.material) {
+        Some(Material::Textured(textured)) => match &textured.cycle {
+            Some(cycle) => cycle,
+            None => bail!("Material {} does not have a texture cycle", opts.material),
+        },
+        Some(Material::Colored(_)) => bail!("Material {} is not textured", opts.material),
+        None => bail!(
+            "Material {} not found ({} materials)",
+            opts.material,
+            gamez.materials.len()
+        ),
+    };
+    let names = cycle
+        .texture_indices
+        .iter()
+        .map(|index| match gamez.textures.get(index.to_usize()) {
+            Some(texture) => Ok(texture.name.as_str()),
+            None => bail!(
+                "Texture {} not found ({} textures)",
+                index,
+                gamez.textures.len()
+            ),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut textures = HashMap::new();
+    for path in &opts.textures {
+        log::info!("CYCLE: Reading `{}`", path);
+        let mut input = CountingReader::new(buf_reader(path)?);
+        read_textures::<_, eyre::Report>(&mut input, |name, image| {
+            // the first match is used
+            if names.contains(&name) && !textures.contains_key(name) {
+                textures.insert(name.to_string(), image.to_rgba8());
+            }
+            Ok(())
+        })
+        .with_context(|| format!("Failed to read texture data from `{}`", path))?;
+    }
+
+    let frames = names
+        .iter()
+        .map(|name| match textures.get(*name) {
+            Some(image) => Ok(image.clone()),
+            None => bail!("Texture `{}` not found in the texture packages", name),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let write = buf_writer(&opts.output)?;
+    write_cycle_apng(write, cycle, &frames, opts.ticks_per_second)
+        .context("Failed to write cycle data")?;
+    log::info!("CYCLE: Wrote `{}` ({} frames)", opts.output, frames.len());
+    Ok(())
+}
+
+pub(crate) fn exchange_dump(input: String) -> Result<()> {
+    let data = std::fs::read(input).context("Failed to open input")?;
+    let mut out = String::new();
+    let result = mech3ax_exchange::dump(&data, &mut out);
+    print!("{}", out);
+    result.context("Failed to read exchange data")
+}
+
 pub(crate) fn license() -> Result<()> {
     print!(
         "mech3ax extracts assets from certain games developed by Zipper
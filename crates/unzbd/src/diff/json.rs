@@ -0,0 +1,171 @@
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum JsonChange {
+    Added(Value),
+    Removed(Value),
+    Changed(Value, Value),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct JsonDiff {
+    pub path: String,
+    pub change: JsonChange,
+}
+
+/// The longest value that is printed, before it is truncated.
+const MAX_VALUE_LEN: usize = 60;
+
+struct Short<'a>(&'a Value);
+
+impl fmt::Display for Short<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self.0.to_string();
+        match s.char_indices().nth(MAX_VALUE_LEN) {
+            Some((index, _)) => write!(f, "{}...", &s[..index]),
+            None => f.write_str(&s),
+        }
+    }
+}
+
+impl fmt::Display for JsonDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.change {
+            JsonChange::Added(value) => write!(f, "+ {}: {}", self.path, Short(value)),
+            JsonChange::Removed(value) => write!(f, "- {}: {}", self.path, Short(value)),
+            JsonChange::Changed(old, new) => {
+                write!(f, "~ {}: {} -> {}", self.path, Short(old), Short(new))
+            }
+        }
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn key_path(path: &str, key: &str) -> String {
+    if is_identifier(key) {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}[{}]", path, Value::from(key))
+    }
+}
+
+fn diff_value(path: String, a: &Value, b: &Value, diffs: &mut Vec<JsonDiff>) {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, a_value) in a {
+                let path = key_path(&path, key);
+                match b.get(key) {
+                    Some(b_value) => diff_value(path, a_value, b_value, diffs),
+                    None => diffs.push(JsonDiff {
+                        path,
+                        change: JsonChange::Removed(a_value.clone()),
+                    }),
+                }
+            }
+            for (key, b_value) in b {
+                if !a.contains_key(key) {
+                    diffs.push(JsonDiff {
+                        path: key_path(&path, key),
+                        change: JsonChange::Added(b_value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => match (named(a), named(b)) {
+            (Some(a_named), Some(b_named)) => diff_named(&path, a_named, b_named, diffs),
+            _ => diff_indexed(&path, a, b, diffs),
+        },
+        (a, b) if a == b => {}
+        (a, b) => diffs.push(JsonDiff {
+            path,
+            change: JsonChange::Changed(a.clone(), b.clone()),
+        }),
+    }
+}
+
+fn diff_indexed(path: &str, a: &[Value], b: &[Value], diffs: &mut Vec<JsonDiff>) {
+    for (index, a_value) in a.iter().enumerate() {
+        let path = format!("{}[{}]", path, index);
+        match b.get(index) {
+            Some(b_value) => diff_value(path, a_value, b_value, diffs),
+            None => diffs.push(JsonDiff {
+                path,
+                change: JsonChange::Removed(a_value.clone()),
+            }),
+        }
+    }
+    for (index, b_value) in b.iter().enumerate().skip(a.len()) {
+        diffs.push(JsonDiff {
+            path: format!("{}[{}]", path, index),
+            change: JsonChange::Added(b_value.clone()),
+        });
+    }
+}
+
+/// The key of an object in an array, which is the `rename` if present (for
+/// archive entries), or otherwise the `name`.
+fn object_key(value: &Value) -> Option<&str> {
+    let object = value.as_object()?;
+    object
+        .get("rename")
+        .and_then(Value::as_str)
+        .or_else(|| object.get("name").and_then(Value::as_str))
+}
+
+/// If every element of the array is an object with a unique key, return the
+/// keys and elements.
+fn named(values: &[Value]) -> Option<Vec<(&str, &Value)>> {
+    let mut seen = HashSet::with_capacity(values.len());
+    values
+        .iter()
+        .map(|value| {
+            let key = object_key(value)?;
+            seen.insert(key).then_some((key, value))
+        })
+        .collect()
+}
+
+fn diff_named(
+    path: &str,
+    a: Vec<(&str, &Value)>,
+    b: Vec<(&str, &Value)>,
+    diffs: &mut Vec<JsonDiff>,
+) {
+    let lookup: HashMap<&str, &Value> = b.iter().copied().collect();
+    let a_keys: HashSet<&str> = a.iter().map(|(key, _)| *key).collect();
+    for (key, a_value) in a {
+        let path = format!("{}[name={}]", path, Value::from(key));
+        match lookup.get(key) {
+            Some(b_value) => diff_value(path, a_value, b_value, diffs),
+            None => diffs.push(JsonDiff {
+                path,
+                change: JsonChange::Removed(a_value.clone()),
+            }),
+        }
+    }
+    for (key, b_value) in b {
+        if !a_keys.contains(key) {
+            diffs.push(JsonDiff {
+                path: format!("{}[name={}]", path, Value::from(key)),
+                change: JsonChange::Added(b_value.clone()),
+            });
+        }
+    }
+}
+
+/// Compare two JSON values. Objects are compared by key, and arrays by name
+/// if every element has a unique name, otherwise by index.
+pub(crate) fn diff_json(a: &Value, b: &Value) -> Vec<JsonDiff> {
+    let mut diffs = Vec::new();
+    diff_value("$".to_string(), a, b, &mut diffs);
+    diffs
+}
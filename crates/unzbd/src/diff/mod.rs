@@ -0,0 +1,270 @@
+mod json;
+
+use crate::{DiffKind, DiffOpts};
+use eyre::{Context as _, Result, bail};
+use image::DynamicImage;
+use json::diff_json;
+use mech3ax_archive::{Mode, Version, read_archive};
+use mech3ax_common::GameType;
+use mech3ax_common::io_ext::CountingReader;
+use mech3ax_gamez::gamez;
+use mech3ax_image::read_textures;
+use mech3ax_interp::read_interp;
+use mech3ax_motion::read_motion;
+use mech3ax_reader::read_reader;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+
+/// A named part of a file, named like the files in the extracted ZIP.
+enum Item {
+    Json(Value),
+    Bytes(Vec<u8>),
+    Image(DynamicImage),
+}
+
+type Items = Vec<(String, Item)>;
+
+fn json_item(name: impl Into<String>, value: &impl Serialize) -> Result<(String, Item)> {
+    let value = serde_json::to_value(value)?;
+    Ok((name.into(), Item::Json(value)))
+}
+
+fn open(path: &str) -> Result<CountingReader<BufReader<File>>> {
+    let file = File::open(path).with_context(|| format!("Failed to open `{}`", path))?;
+    Ok(CountingReader::new(BufReader::new(file)))
+}
+
+fn archive_version(game: GameType, mode: Mode) -> Version {
+    match game {
+        GameType::MW | GameType::RC | GameType::CS => Version::One,
+        GameType::PM => Version::Two(mode),
+    }
+}
+
+fn load_archive<F>(path: &str, version: Version, mut parse: F) -> Result<Items>
+where
+    F: FnMut(&str, Vec<u8>, usize) -> Result<(String, Item)>,
+{
+    let mut read = open(path)?;
+    let mut items = Vec::new();
+    let manifest = read_archive::<_, _, eyre::Report>(
+        &mut read,
+        |name, data, offset| {
+            items.push(parse(name, data, offset)?);
+            Ok(())
+        },
+        version,
+    )
+    .context("Failed to read archive data")?;
+    items.insert(0, json_item("manifest.json", &manifest)?);
+    Ok(items)
+}
+
+fn entry_reader(data: Vec<u8>, offset: usize) -> CountingReader<Cursor<Vec<u8>>> {
+    let mut read = CountingReader::new(Cursor::new(data));
+    // translate to absolute offset
+    read.offset = offset;
+    read
+}
+
+fn load_gamez(path: &str, game: GameType) -> Result<Items> {
+    let mut read = open(path)?;
+    macro_rules! gamez_items {
+        ($module:ident) => {{
+            let gamez =
+                gamez::$module::read_gamez(&mut read).context("Failed to read gamez data")?;
+            vec![
+                json_item("metadata.json", &gamez.metadata)?,
+                json_item("textures.json", &gamez.textures)?,
+                json_item("materials.json", &gamez.materials)?,
+                json_item("models.json", &gamez.models)?,
+                json_item("nodes.json", &gamez.nodes)?,
+            ]
+        }};
+    }
+    let items = match game {
+        GameType::MW => gamez_items!(mw),
+        GameType::PM => gamez_items!(pm),
+        GameType::RC => gamez_items!(rc),
+        GameType::CS => gamez_items!(cs),
+    };
+    Ok(items)
+}
+
+fn load_anim(path: &str, game: GameType) -> Result<Items> {
+    let mut read = open(path)?;
+    let mut items = Vec::new();
+    let save_item = |item: mech3ax_anim::SaveItem<'_>| -> Result<()> {
+        let item = match item {
+            mech3ax_anim::SaveItem::AnimDef { name, anim_def } => {
+                json_item(format!("{}.json", name), anim_def)?
+            }
+            mech3ax_anim::SaveItem::SiScript { name, si_script } => json_item(name, si_script)?,
+        };
+        items.push(item);
+        Ok(())
+    };
+    let metadata = match game {
+        GameType::MW => json_item(
+            "metadata.json",
+            &mech3ax_anim::mw::read_anim(&mut read, save_item)
+                .context("Failed to read anim data")?,
+        )?,
+        GameType::PM => json_item(
+            "metadata.json",
+            &mech3ax_anim::pm::read_anim(&mut read, save_item)
+                .context("Failed to read anim data")?,
+        )?,
+        GameType::RC => json_item(
+            "metadata.json",
+            &mech3ax_anim::rc::read_anim(&mut read, save_item)
+                .context("Failed to read anim data")?,
+        )?,
+        GameType::CS => bail!("Crimson Skies support for Anim isn't implemented yet"),
+    };
+    items.insert(0, metadata);
+    Ok(items)
+}
+
+fn load_textures(path: &str) -> Result<Items> {
+    let mut read = open(path)?;
+    let mut items = Vec::new();
+    let manifest = read_textures::<_, eyre::Report>(&mut read, |name, image| {
+        items.push((format!("{}.png", name), Item::Image(image)));
+        Ok(())
+    })
+    .context("Failed to read texture data")?;
+    items.insert(0, json_item("manifest.json", &manifest)?);
+    Ok(items)
+}
+
+fn load(path: &str, kind: DiffKind, game: GameType) -> Result<Items> {
+    log::info!("DIFF: Reading `{}` ({})", path, game);
+    match kind {
+        DiffKind::Archive => load_archive(
+            path,
+            archive_version(game, Mode::ReaderBypass),
+            |name, data, _offset| Ok((name.to_string(), Item::Bytes(data))),
+        ),
+        DiffKind::Reader => load_archive(
+            path,
+            archive_version(game, Mode::ReaderBypass),
+            |name, data, offset| {
+                let value = read_reader(&mut entry_reader(data, offset))
+                    .with_context(|| format!("Failed to read reader data for `{}`", name))?;
+                json_item(name, &value)
+            },
+        ),
+        DiffKind::Motion => load_archive(
+            path,
+            archive_version(game, Mode::Motion),
+            |name, data, offset| {
+                let motion = read_motion(&mut entry_reader(data, offset))
+                    .with_context(|| format!("Failed to read motion data for `{}`", name))?;
+                json_item(name, &motion)
+            },
+        ),
+        DiffKind::Gamez => load_gamez(path, game),
+        DiffKind::Anim => load_anim(path, game),
+        DiffKind::Textures => load_textures(path),
+        DiffKind::Interp => {
+            let scripts =
+                read_interp(&mut open(path)?).context("Failed to read interpreter data")?;
+            Ok(vec![json_item("interp.json", &scripts)?])
+        }
+    }
+}
+
+fn diff_bytes(a: &[u8], b: &[u8]) -> Option<String> {
+    let offset = a
+        .iter()
+        .zip(b)
+        .position(|(a, b)| a != b)
+        .or_else(|| (a.len() != b.len()).then(|| a.len().min(b.len())))?;
+    Some(format!(
+        "data differs at offset {} (length {} -> {})",
+        offset,
+        a.len(),
+        b.len()
+    ))
+}
+
+fn diff_image(a: &DynamicImage, b: &DynamicImage) -> Option<String> {
+    let (a_width, a_height) = (a.width(), a.height());
+    let (b_width, b_height) = (b.width(), b.height());
+    if (a_width, a_height) != (b_width, b_height) {
+        return Some(format!(
+            "size {}x{} -> {}x{}",
+            a_width, a_height, b_width, b_height
+        ));
+    }
+    if a.color() != b.color() {
+        return Some(format!("color {:?} -> {:?}", a.color(), b.color()));
+    }
+    let a = a.to_rgba8();
+    let b = b.to_rgba8();
+    let count = a.pixels().zip(b.pixels()).filter(|(a, b)| a != b).count();
+    (count > 0).then(|| {
+        let total = u64::from(a_width) * u64::from(a_height);
+        format!("{} of {} pixels differ", count, total)
+    })
+}
+
+/// Compare items by name, and return the differences as lines.
+fn diff_items(a: Items, b: Items) -> Vec<String> {
+    let mut lines = Vec::new();
+    let b_names: Vec<String> = b.iter().map(|(name, _)| name.clone()).collect();
+    let mut lookup: HashMap<String, Item> = b.into_iter().collect();
+
+    for (name, a_item) in a {
+        let Some(b_item) = lookup.remove(&name) else {
+            lines.push(format!("- {}", name));
+            continue;
+        };
+        match (&a_item, &b_item) {
+            (Item::Json(a), Item::Json(b)) => {
+                for diff in diff_json(a, b) {
+                    lines.push(format!("{}: {}", name, diff));
+                }
+            }
+            (Item::Bytes(a), Item::Bytes(b)) => {
+                if let Some(diff) = diff_bytes(a, b) {
+                    lines.push(format!("~ {}: {}", name, diff));
+                }
+            }
+            (Item::Image(a), Item::Image(b)) => {
+                if let Some(diff) = diff_image(a, b) {
+                    lines.push(format!("~ {}: {}", name, diff));
+                }
+            }
+            _ => lines.push(format!("~ {}: type differs", name)),
+        }
+    }
+    for name in b_names {
+        if lookup.contains_key(&name) {
+            lines.push(format!("+ {}", name));
+        }
+    }
+    lines
+}
+
+pub(crate) fn diff(opts: DiffOpts) -> Result<()> {
+    let a = load(&opts.a, opts.kind, opts.game)?;
+    let b = load(&opts.b, opts.kind, opts.game)?;
+    let lines = diff_items(a, b);
+    for line in &lines {
+        println!("{}", line);
+    }
+    if lines.is_empty() {
+        println!("No differences");
+    } else {
+        println!("{} difference(s)", lines.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,89 @@
+use super::json::{JsonChange, diff_json};
+use serde_json::{Value, json};
+
+fn lines(a: &Value, b: &Value) -> Vec<String> {
+    diff_json(a, b)
+        .iter()
+        .map(|diff| diff.to_string())
+        .collect()
+}
+
+#[test]
+fn json_equal() {
+    let value = json!({"a": [1, 2], "b": {"c": null}});
+    assert!(diff_json(&value, &value).is_empty());
+}
+
+#[test]
+fn json_keys_added_and_removed() {
+    let a = json!({"kept": 1, "removed": true, "nested": {"x": 1}});
+    let b = json!({"kept": 2, "nested": {"x": 1, "y": "new"}, "added": [1]});
+    assert_eq!(
+        lines(&a, &b),
+        vec![
+            "~ $.kept: 1 -> 2",
+            "+ $.nested.y: \"new\"",
+            "- $.removed: true",
+            "+ $.added: [1]",
+        ]
+    );
+}
+
+#[test]
+fn json_array_length_changes() {
+    let a = json!([1, 2, 3]);
+    let b = json!([1, 5]);
+    let diffs = diff_json(&a, &b);
+    assert_eq!(diffs.len(), 2);
+    assert_eq!(diffs[0].path, "$[1]");
+    assert_eq!(diffs[0].change, JsonChange::Changed(json!(2), json!(5)));
+    assert_eq!(diffs[1].path, "$[2]");
+    assert_eq!(diffs[1].change, JsonChange::Removed(json!(3)));
+
+    assert_eq!(lines(&b, &a), vec!["~ $[1]: 5 -> 2", "+ $[2]: 3"]);
+}
+
+#[test]
+fn json_named_arrays_match_by_name() {
+    // reordering named elements isn't a change
+    let a = json!([{"name": "a", "v": 1}, {"name": "b", "v": 2}]);
+    let b = json!([{"name": "c", "v": 3}, {"name": "b", "v": 4}, {"name": "a", "v": 1}]);
+    assert_eq!(
+        lines(&a, &b),
+        vec![
+            "~ $[name=\"b\"].v: 2 -> 4",
+            "+ $[name=\"c\"]: {\"name\":\"c\",\"v\":3}",
+        ]
+    );
+    // archive entries are matched by their rename, if any
+    let a = json!([{"name": "x", "rename": "x1"}, {"name": "x", "rename": "x2"}]);
+    let b = json!([{"name": "x", "rename": "x2"}]);
+    assert_eq!(
+        lines(&a, &b),
+        vec!["- $[name=\"x1\"]: {\"name\":\"x\",\"rename\":\"x1\"}"]
+    );
+    // duplicate names fall back to indices
+    let a = json!([{"name": "a"}, {"name": "a"}]);
+    let b = json!([{"name": "a"}]);
+    assert_eq!(lines(&a, &b), vec!["- $[1]: {\"name\":\"a\"}"]);
+}
+
+#[test]
+fn json_path_formatting() {
+    let a = json!({"ok_key1": {"with space": {"1st": 0}}});
+    let b = json!({"ok_key1": {"with space": {"1st": 1}}});
+    assert_eq!(
+        lines(&a, &b),
+        vec!["~ $.ok_key1[\"with space\"][\"1st\"]: 0 -> 1"]
+    );
+}
+
+#[test]
+fn json_type_changes_and_truncation() {
+    let a = json!({"v": [1]});
+    let b = json!({"v": "x".repeat(100)});
+    let actual = lines(&a, &b);
+    assert_eq!(actual.len(), 1);
+    let expected = format!("~ $.v: [1] -> \"{}...", "x".repeat(59));
+    assert_eq!(actual[0], expected);
+}
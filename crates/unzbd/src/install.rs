@@ -0,0 +1,250 @@
+use crate::InstallOpts;
+use crate::auto::{detect_file, extract, output_ext};
+use eyre::{Context as _, Result, bail};
+use mech3ax_common::GameType;
+use mech3ax_detect::FileKind;
+use serde::Serialize;
+use std::any::Any;
+use std::num::NonZero;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Serialize)]
+struct Succeeded {
+    input: String,
+    output: String,
+    kind: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Failed {
+    input: String,
+    kind: String,
+    error: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Skipped {
+    input: String,
+    reason: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Report {
+    game: String,
+    succeeded: Vec<Succeeded>,
+    failed: Vec<Failed>,
+    skipped: Vec<Skipped>,
+}
+
+enum Outcome {
+    Succeeded(Succeeded),
+    Failed(Failed),
+    Skipped(Skipped),
+}
+
+fn messages_name(game: GameType) -> &'static str {
+    match game {
+        GameType::MW | GameType::PM => "mech3msg.dll",
+        GameType::RC => "messages.dll",
+        GameType::CS => "strings.dll",
+    }
+}
+
+/// Find all files that could be supported, by extension.
+pub(crate) fn discover(dir: &Path, game: GameType, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory `{}`", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            discover(&path, game, files)?;
+            continue;
+        }
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let supported = match name.rsplit_once('.') {
+            Some((_, "zbd" | "zmap" | "mw3")) => true,
+            Some((_, "dll")) => name == messages_name(game),
+            _ => false,
+        };
+        if supported {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn process(path: &Path, install_dir: &Path, output_dir: &Path, game: GameType) -> Outcome {
+    let rel = path.strip_prefix(install_dir).unwrap_or(path);
+    let input = path.display().to_string();
+
+    let detection = match detect_file(&input) {
+        Ok(detection) => detection,
+        Err(e) => {
+            return Outcome::Skipped(Skipped {
+                input,
+                reason: format!("{:#}", e),
+            });
+        }
+    };
+    if !detection.games.contains(&game) {
+        return Outcome::Skipped(Skipped {
+            input,
+            reason: format!("File is {}, not {}", detection, game),
+        });
+    }
+    if detection.kind == FileKind::Archive {
+        return Outcome::Skipped(Skipped {
+            input,
+            reason: "Archive with unrecognised entries".to_string(),
+        });
+    }
+
+    let kind = detection.kind.to_string();
+    let output_path = output_dir.join(rel.with_extension(output_ext(detection.kind)));
+    let output = output_path.display().to_string();
+    log::info!("INSTALL: Extracting `{}` as {}", input, kind);
+
+    let result = output_path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .context("Failed to create output directory")
+        .and_then(|()| extract(detection.kind, game, input.clone(), output.clone()));
+    match result {
+        Ok(()) => Outcome::Succeeded(Succeeded {
+            input,
+            output,
+            kind,
+        }),
+        Err(e) => Outcome::Failed(Failed {
+            input,
+            kind,
+            error: format!("{:#}", e),
+        }),
+    }
+}
+
+/// Get the message of a panic payload, which is usually a string.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        (*msg).to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Process items on a number of threads, returning the outputs in order.
+///
+/// If processing an item panics, `on_panic` is called with the item and the
+/// panic message instead, so one bad file doesn't abort the whole run.
+pub(crate) fn run_parallel<T, O, F, P>(
+    items: &[T],
+    jobs: Option<NonZero<usize>>,
+    f: F,
+    on_panic: P,
+) -> Vec<O>
+where
+    T: Sync,
+    O: Send,
+    F: Fn(&T) -> O + Sync,
+    P: Fn(&T, String) -> O + Sync,
+{
+    let jobs = jobs
+        .or_else(|| std::thread::available_parallelism().ok())
+        .map_or(1, NonZero::get);
+
+    let next = AtomicUsize::new(0);
+    let mut outputs: Vec<(usize, O)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut outputs = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        let output = std::panic::catch_unwind(AssertUnwindSafe(|| f(item)))
+                            .unwrap_or_else(|payload| on_panic(item, panic_message(&*payload)));
+                        outputs.push((index, output));
+                    }
+                    outputs
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("worker thread panicked"))
+            .collect()
+    });
+    outputs.sort_by_key(|(index, _)| *index);
+    outputs.into_iter().map(|(_, output)| output).collect()
+}
+
+pub(crate) fn install(opts: InstallOpts) -> Result<()> {
+    let install_dir = Path::new(&opts.install_dir);
+    let output_dir = Path::new(&opts.output_dir);
+
+    let mut files = Vec::new();
+    discover(install_dir, opts.game, &mut files)?;
+    files.sort();
+    log::info!(
+        "INSTALL: Found {} files in `{}`",
+        files.len(),
+        opts.install_dir
+    );
+
+    let outcomes = run_parallel(
+        &files,
+        opts.jobs,
+        |path| process(path, install_dir, output_dir, opts.game),
+        |path, msg| {
+            Outcome::Failed(Failed {
+                input: path.display().to_string(),
+                kind: "unknown".to_string(),
+                error: format!("Panicked: {}", msg),
+            })
+        },
+    );
+
+    let mut report = Report {
+        game: opts.game.to_string(),
+        ..Default::default()
+    };
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Succeeded(succeeded) => report.succeeded.push(succeeded),
+            Outcome::Failed(failed) => report.failed.push(failed),
+            Outcome::Skipped(skipped) => report.skipped.push(skipped),
+        }
+    }
+
+    std::fs::create_dir_all(output_dir).context("Failed to create output directory")?;
+    let report_path = output_dir.join("report.json");
+    let contents = serde_json::to_vec_pretty(&report)?;
+    std::fs::write(&report_path, contents).context("Failed to write report")?;
+
+    for failed in &report.failed {
+        println!(
+            "FAILED {} ({}): {}",
+            failed.input, failed.kind, failed.error
+        );
+    }
+    println!(
+        "{} succeeded, {} failed, {} skipped (see `{}`)",
+        report.succeeded.len(),
+        report.failed.len(),
+        report.skipped.len(),
+        report_path.display()
+    );
+    if !report.failed.is_empty() {
+        bail!("{} file(s) failed to extract", report.failed.len());
+    }
+    Ok(())
+}
@@ -1,17 +1,29 @@
+mod archive;
+mod auto;
 mod commands;
+mod diff;
+mod install;
+mod verify;
 
 use clap::Parser as _;
 use env_logger::Env;
-use eyre::Result;
+use eyre::{Result, eyre};
 use mech3ax_archive::{Mode, Version};
 use mech3ax_common::GameType;
+use mech3ax_gamez::render::{OverviewOptions, SoilMapOptions};
 use mech3ax_version::VERSION;
+use mech3ax_zmap::RenderOptions;
+use std::num::NonZero;
+use std::path::Path;
 
 #[derive(clap::Parser)]
 #[clap(version = VERSION)]
 struct Cli {
-    #[arg(value_enum)]
-    game: Game,
+    #[arg(
+        value_enum,
+        help = "The game (optional for `detect`, `auto`, and `exchange-dump`)"
+    )]
+    game: Option<Game>,
     #[clap(subcommand)]
     subcmd: SubCommand,
 }
@@ -132,6 +144,218 @@ impl ReaderOpts {
     }
 }
 
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ArchiveKind {
+    Sounds,
+    Reader,
+    Motion,
+    Mechlib,
+}
+
+#[derive(clap::Args)]
+struct ArchiveArgs {
+    #[clap(help = "The source ZBD path")]
+    input: String,
+    #[clap(
+        long,
+        value_enum,
+        default_value = "sounds",
+        help = "The archive kind (only matters for PM)"
+    )]
+    kind: ArchiveKind,
+}
+
+impl ArchiveArgs {
+    fn opts(self, game: GameType) -> Result<ArchiveOpts> {
+        let Self { input, kind } = self;
+        Ok(ArchiveOpts { game, input, kind })
+    }
+}
+
+struct ArchiveOpts {
+    game: GameType,
+    input: String,
+    kind: ArchiveKind,
+}
+
+impl ArchiveOpts {
+    fn version(&self) -> Version {
+        // the reader checksum can't be validated without reading every entry
+        let mode = match self.kind {
+            ArchiveKind::Sounds | ArchiveKind::Mechlib => Mode::Sounds,
+            ArchiveKind::Reader => Mode::ReaderBypass,
+            ArchiveKind::Motion => Mode::Motion,
+        };
+        match self.game {
+            GameType::MW | GameType::RC | GameType::CS => Version::One,
+            GameType::PM => Version::Two(mode),
+        }
+    }
+}
+
+#[derive(clap::Args)]
+struct ExtractEntryArgs {
+    #[clap(flatten)]
+    archive: ArchiveArgs,
+    #[clap(help = "The entry name (as listed by `ls`, i.e. renamed if a duplicate)")]
+    name: String,
+    #[clap(help = "The destination path (will be overwritten)")]
+    output: String,
+}
+
+impl ExtractEntryArgs {
+    fn opts(self, game: GameType) -> Result<ExtractEntryOpts> {
+        let Self {
+            archive,
+            name,
+            output,
+        } = self;
+        Ok(ExtractEntryOpts {
+            archive: archive.opts(game)?,
+            name,
+            output,
+        })
+    }
+}
+
+struct ExtractEntryOpts {
+    archive: ArchiveOpts,
+    name: String,
+    output: String,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum DiffKind {
+    Archive,
+    Reader,
+    Motion,
+    Gamez,
+    Anim,
+    Textures,
+    Interp,
+}
+
+#[derive(clap::Args)]
+struct DiffArgs {
+    #[clap(value_enum, help = "The file kind")]
+    kind: DiffKind,
+    #[clap(help = "The original ZBD path")]
+    a: String,
+    #[clap(help = "The modified ZBD path")]
+    b: String,
+}
+
+impl DiffArgs {
+    fn opts(self, game: GameType) -> Result<DiffOpts> {
+        let Self { kind, a, b } = self;
+        Ok(DiffOpts { game, kind, a, b })
+    }
+}
+
+struct DiffOpts {
+    game: GameType,
+    kind: DiffKind,
+    a: String,
+    b: String,
+}
+
+#[derive(clap::Args)]
+struct DetectOpts {
+    #[clap(help = "The source path")]
+    input: String,
+}
+
+#[derive(clap::Args)]
+struct ExchangeDumpOpts {
+    #[clap(help = "The source path")]
+    input: String,
+}
+
+#[derive(clap::Args)]
+struct AutoArgs {
+    #[clap(help = "The source path")]
+    input: String,
+    #[clap(help = "The destination path (will be overwritten)")]
+    output: String,
+}
+
+impl AutoArgs {
+    fn opts(self, game: Option<GameType>) -> AutoOpts {
+        let Self { input, output } = self;
+        AutoOpts {
+            game,
+            input,
+            output,
+        }
+    }
+}
+
+struct AutoOpts {
+    game: Option<GameType>,
+    input: String,
+    output: String,
+}
+
+#[derive(clap::Args)]
+struct InstallArgs {
+    #[clap(help = "The game install directory")]
+    install_dir: String,
+    #[clap(help = "The destination directory (files will be overwritten)")]
+    output_dir: String,
+    #[clap(
+        long,
+        help = "The number of files to extract in parallel (default: all cores)"
+    )]
+    jobs: Option<NonZero<usize>>,
+}
+
+impl InstallArgs {
+    fn opts(self, game: GameType) -> Result<InstallOpts> {
+        let Self {
+            install_dir,
+            output_dir,
+            jobs,
+        } = self;
+        Ok(InstallOpts {
+            game,
+            install_dir,
+            output_dir,
+            jobs,
+        })
+    }
+}
+
+struct InstallOpts {
+    game: GameType,
+    install_dir: String,
+    output_dir: String,
+    jobs: Option<NonZero<usize>>,
+}
+
+#[derive(clap::Args)]
+struct VerifyArgs {
+    #[clap(help = "The game install directory, or a single file")]
+    path: String,
+    #[clap(
+        long,
+        help = "The number of files to verify in parallel (default: all cores)"
+    )]
+    jobs: Option<NonZero<usize>>,
+}
+
+impl VerifyArgs {
+    fn opts(self, game: GameType) -> Result<VerifyOpts> {
+        let Self { path, jobs } = self;
+        Ok(VerifyOpts { game, path, jobs })
+    }
+}
+
+struct VerifyOpts {
+    game: GameType,
+    path: String,
+    jobs: Option<NonZero<usize>>,
+}
+
 #[derive(clap::Args)]
 struct InterpOpts {
     #[clap(help = "The source ZBD path")]
@@ -177,17 +401,47 @@ struct MsgOpts {
 struct ZMapArgs {
     #[clap(help = "The source ZMAP path")]
     input: String,
-    #[clap(help = "The destination JSON path (will be overwritten)")]
+    #[clap(help = "The destination JSON or PNG path (will be overwritten)")]
     output: String,
+    #[clap(long, help = "Render the map features to a PNG, instead of JSON")]
+    png: bool,
+    #[clap(
+        long,
+        default_value_t = 1024,
+        requires = "png",
+        help = "The size of the longest side of the PNG, in pixels"
+    )]
+    size: u32,
+    #[clap(long, requires = "png", help = "Stretch the PNG to a square")]
+    aspect_square: bool,
+    #[clap(
+        long,
+        requires = "png",
+        help = "Draw objective features with thicker lines in the PNG"
+    )]
+    objectives: bool,
 }
 
 impl ZMapArgs {
     fn opts(self, game: GameType) -> Result<ZMapOpts> {
-        let Self { input, output } = self;
+        let Self {
+            input,
+            output,
+            png,
+            size,
+            aspect_square,
+            objectives,
+        } = self;
+        let render = png.then_some(RenderOptions {
+            size,
+            aspect_square,
+            highlight_objectives: objectives,
+        });
         Ok(ZMapOpts {
             game,
             input,
             output,
+            render,
         })
     }
 }
@@ -196,6 +450,156 @@ struct ZMapOpts {
     game: GameType,
     input: String,
     output: String,
+    render: Option<RenderOptions>,
+}
+
+#[derive(clap::Args)]
+struct SoilMapArgs {
+    #[clap(help = "The source 'gamez.zbd' path")]
+    input: String,
+    #[clap(help = "The destination PNG path (will be overwritten)")]
+    output: String,
+    #[clap(
+        long,
+        default_value_t = 1024,
+        help = "The size of the longest side of the PNG, in pixels"
+    )]
+    size: u32,
+    #[clap(
+        long,
+        help = "The destination legend JSON path (default: the PNG path with a '.json' extension)"
+    )]
+    legend: Option<String>,
+}
+
+impl SoilMapArgs {
+    fn opts(self, game: GameType) -> Result<SoilMapOpts> {
+        let Self {
+            input,
+            output,
+            size,
+            legend,
+        } = self;
+        let legend = legend.unwrap_or_else(|| {
+            Path::new(&output)
+                .with_extension("json")
+                .to_string_lossy()
+                .into_owned()
+        });
+        Ok(SoilMapOpts {
+            game,
+            input,
+            output,
+            legend,
+            render: SoilMapOptions { size },
+        })
+    }
+}
+
+struct SoilMapOpts {
+    game: GameType,
+    input: String,
+    output: String,
+    legend: String,
+    render: SoilMapOptions,
+}
+
+#[derive(clap::Args)]
+struct OverviewArgs {
+    #[clap(help = "The source 'gamez.zbd' path")]
+    input: String,
+    #[clap(help = "The destination PNG path (will be overwritten)")]
+    output: String,
+    #[clap(
+        short,
+        long,
+        help = "A texture package path to look up textures by name (repeatable, the first match is used)"
+    )]
+    textures: Vec<String>,
+    #[clap(
+        long,
+        default_value_t = 1024,
+        help = "The size of the longest side of the PNG, in pixels"
+    )]
+    size: u32,
+}
+
+impl OverviewArgs {
+    fn opts(self, game: GameType) -> Result<OverviewOpts> {
+        let Self {
+            input,
+            output,
+            textures,
+            size,
+        } = self;
+        Ok(OverviewOpts {
+            game,
+            input,
+            output,
+            textures,
+            render: OverviewOptions { size },
+        })
+    }
+}
+
+struct OverviewOpts {
+    game: GameType,
+    input: String,
+    output: String,
+    textures: Vec<String>,
+    render: OverviewOptions,
+}
+
+#[derive(clap::Args)]
+struct CyclePreviewArgs {
+    #[clap(help = "The source 'gamez.zbd' path")]
+    input: String,
+    #[clap(help = "The index of the material with a texture cycle")]
+    material: usize,
+    #[clap(help = "The destination animated PNG path (will be overwritten)")]
+    output: String,
+    #[clap(
+        short,
+        long,
+        required = true,
+        help = "A texture package path to look up the frames by name (repeatable, the first match is used)"
+    )]
+    textures: Vec<String>,
+    #[clap(
+        long,
+        default_value_t = mech3ax_image::CYCLE_TICKS_PER_SECOND,
+        help = "The engine updates per second, which the cycle speed is assumed to be relative to (the default is a guess)"
+    )]
+    ticks_per_second: f32,
+}
+
+impl CyclePreviewArgs {
+    fn opts(self, game: GameType) -> Result<CyclePreviewOpts> {
+        let Self {
+            input,
+            material,
+            output,
+            textures,
+            ticks_per_second,
+        } = self;
+        Ok(CyclePreviewOpts {
+            game,
+            input,
+            material,
+            output,
+            textures,
+            ticks_per_second,
+        })
+    }
+}
+
+struct CyclePreviewOpts {
+    game: GameType,
+    input: String,
+    material: usize,
+    output: String,
+    textures: Vec<String>,
+    ticks_per_second: f32,
 }
 
 #[derive(clap::Subcommand)]
@@ -218,12 +622,44 @@ enum SubCommand {
     Mechlib(ZipArgs),
     #[clap(about = "Extract 'gamez.zbd' archives to ZIP")]
     Gamez(ZipArgs),
+    #[clap(about = "Extract 'planes.zbd' archives to ZIP (CS)")]
+    Planes(ZipArgs),
     #[clap(about = "Extract 'anim.zbd' archives to ZIP (MW)")]
     Anim(ZipArgs),
     #[clap(about = "Extract savegames '*.mw3' archives to ZIP (MW)")]
     Savegame(ZipArgs),
-    #[clap(about = "Extract map '*.zmap' files to JSON (RC)")]
+    #[clap(about = "Extract map '*.zmap' files to JSON or PNG (RC)")]
     Zmap(ZMapArgs),
+    #[clap(about = "Render the terrain of 'gamez.zbd' archives by soil type to PNG")]
+    SoilMap(SoilMapArgs),
+    #[clap(about = "Render a top-down overview of 'gamez.zbd' archives to PNG")]
+    Overview(OverviewArgs),
+    #[clap(
+        about = "Export a material's texture cycle from 'gamez.zbd' archives as an animated PNG, using the frames from texture packages"
+    )]
+    CyclePreview(CyclePreviewArgs),
+    #[clap(about = "List the entries of an archive, without extracting")]
+    Ls(ArchiveArgs),
+    #[clap(about = "Extract a single entry from an archive")]
+    ExtractEntry(ExtractEntryArgs),
+    #[clap(about = "Print the semantic differences between two files of the same kind")]
+    Diff(DiffArgs),
+    #[clap(
+        about = "Detect the kind of a file, and which games it could be from (the game is optional, and if given, the file must match it)"
+    )]
+    Detect(DetectOpts),
+    #[clap(about = "Detect the kind of a file, and extract it (the game is optional)")]
+    Auto(AutoArgs),
+    #[clap(
+        about = "Extract all supported files in a game install directory, in parallel, and write a report"
+    )]
+    Install(InstallArgs),
+    #[clap(
+        about = "Read and write back all supported files in memory, and report the first difference"
+    )]
+    Verify(VerifyArgs),
+    #[clap(about = "Print the values in an exchange format file as a tree, for debugging")]
+    ExchangeDump(ExchangeDumpOpts),
 }
 
 fn main() -> Result<()> {
@@ -239,9 +675,20 @@ fn main() -> Result<()> {
         .init();
 
     let cli: Cli = Cli::parse();
-    let game: GameType = cli.game.into();
+    let game: Option<GameType> = cli.game.map(Into::into);
 
-    match cli.subcmd {
+    let subcmd = match cli.subcmd {
+        SubCommand::Detect(DetectOpts { input }) => return auto::detect_cmd(input, game),
+        SubCommand::Auto(args) => return auto::auto(args.opts(game)),
+        SubCommand::License => return commands::license(),
+        SubCommand::ExchangeDump(ExchangeDumpOpts { input }) => {
+            return commands::exchange_dump(input);
+        }
+        subcmd => subcmd,
+    };
+    let game = game.ok_or_else(|| eyre!("The game is required for this subcommand"))?;
+
+    match subcmd {
         SubCommand::Sounds(args) => commands::sounds(args.opts(game)?),
         SubCommand::Interp(opts) => commands::interp(opts),
         SubCommand::Reader(args) => commands::reader(args.opts(game)?),
@@ -250,9 +697,23 @@ fn main() -> Result<()> {
         SubCommand::Motion(args) => commands::motion(args.opts(game)?),
         SubCommand::Mechlib(args) => commands::mechlib(args.opts(game)?),
         SubCommand::Gamez(args) => commands::gamez(args.opts(game)?),
+        SubCommand::Planes(args) => commands::planes(args.opts(game)?),
         SubCommand::Anim(args) => commands::anim(args.opts(game)?),
         SubCommand::Savegame(args) => commands::savegame(args.opts(game)?),
         SubCommand::Zmap(args) => commands::zmap(args.opts(game)?),
-        SubCommand::License => commands::license(),
+        SubCommand::SoilMap(args) => commands::soil_map(args.opts(game)?),
+        SubCommand::Overview(args) => commands::overview(args.opts(game)?),
+        SubCommand::CyclePreview(args) => commands::cycle_preview(args.opts(game)?),
+        SubCommand::Ls(args) => archive::ls(args.opts(game)?),
+        SubCommand::ExtractEntry(args) => archive::extract_entry(args.opts(game)?),
+        SubCommand::Diff(args) => diff::diff(args.opts(game)?),
+        SubCommand::Install(args) => install::install(args.opts(game)?),
+        SubCommand::Verify(args) => verify::verify(args.opts(game)?),
+        SubCommand::Detect(_)
+        | SubCommand::Auto(_)
+        | SubCommand::License
+        | SubCommand::ExchangeDump(_) => {
+            unreachable!("handled above")
+        }
     }
 }
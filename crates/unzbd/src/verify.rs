@@ -0,0 +1,509 @@
+use crate::VerifyOpts;
+use crate::auto::detect_file;
+use crate::install::{discover, run_parallel};
+use eyre::{Context as _, Result, bail, eyre};
+use image::DynamicImage;
+use mech3ax_anim::{LoadItem, LoadItemName, SaveItem};
+use mech3ax_archive::{Mode, Version, read_archive, write_archive};
+use mech3ax_common::GameType;
+use mech3ax_common::io_ext::{CountingReader, CountingWriter, ReadSpan};
+use mech3ax_detect::FileKind;
+use mech3ax_gamez::gamez;
+use mech3ax_gamez::mechlib::{self, read_format, read_materials, read_version};
+use mech3ax_gamez::mechlib::{write_format, write_materials, write_version};
+use mech3ax_image::{read_textures, write_textures};
+use mech3ax_interp::{read_interp, write_interp};
+use mech3ax_motion::{read_motion, write_motion};
+use mech3ax_reader::{read_reader, write_reader};
+use mech3ax_saves::{read_activation, read_save_header, write_activation, write_save_header};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+type Reader<'a> = CountingReader<Cursor<&'a [u8]>>;
+type Writer = CountingWriter<Vec<u8>>;
+
+/// The first difference between the original and the written data.
+#[derive(Debug)]
+struct Mismatch {
+    /// The archive entry, if the difference is inside one.
+    entry: Option<String>,
+    offset: usize,
+    expected_len: usize,
+    actual_len: usize,
+    /// The read covering the offset (or the closest one before it).
+    span: Option<ReadSpan>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(entry) = &self.entry {
+            write!(f, "entry `{}`: ", entry)?;
+        }
+        write!(
+            f,
+            "first difference at {} ({:#x})",
+            self.offset, self.offset
+        )?;
+        if let Some(span) = &self.span {
+            if self.offset < span.offset + span.len {
+                write!(
+                    f,
+                    ", in `{}` (len: {}, at {}, +{})",
+                    span.name,
+                    span.len,
+                    span.offset,
+                    self.offset - span.offset
+                )?;
+            } else {
+                write!(
+                    f,
+                    ", after `{}` (len: {}, at {})",
+                    span.name, span.len, span.offset
+                )?;
+            }
+        }
+        if self.expected_len != self.actual_len {
+            write!(
+                f,
+                "; expected {} bytes, wrote {} bytes",
+                self.expected_len, self.actual_len
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare the data byte-for-byte. The base is the absolute offset of the
+/// data, so that it matches the recorded layout.
+fn compare(expected: &[u8], actual: &[u8], base: usize, layout: &[ReadSpan]) -> Option<Mismatch> {
+    let index = expected
+        .iter()
+        .zip(actual.iter())
+        .position(|(a, b)| a != b)
+        .or_else(|| (expected.len() != actual.len()).then(|| expected.len().min(actual.len())))?;
+    let offset = base + index;
+    Some(Mismatch {
+        entry: None,
+        offset,
+        expected_len: expected.len(),
+        actual_len: actual.len(),
+        span: ReadSpan::find(layout, offset).copied(),
+    })
+}
+
+/// Read the data and write it back in memory, and compare the two.
+fn roundtrip<T>(
+    data: &[u8],
+    base: usize,
+    read: impl FnOnce(&mut Reader<'_>) -> Result<T>,
+    write: impl FnOnce(&mut Writer, T) -> Result<()>,
+) -> Result<Option<Mismatch>> {
+    let mut reader = CountingReader::new(Cursor::new(data));
+    // translate to absolute offset
+    reader.offset = base;
+    reader.record_layout();
+    let value = read(&mut reader).context("Failed to read data")?;
+    let layout = reader.take_layout();
+
+    let mut writer = CountingWriter::new(Vec::new(), base);
+    write(&mut writer, value).context("Failed to write data")?;
+    Ok(compare(data, &writer.into_inner(), base, &layout))
+}
+
+/// Verify each entry of an archive, and then the archive itself (with the
+/// original entry data, so entry and archive mismatches are independent).
+fn archive<F>(data: &[u8], version: Version, mut verify_entry: F) -> Result<Vec<Mismatch>>
+where
+    F: FnMut(&str, &[u8], usize) -> Result<Option<Mismatch>>,
+{
+    let mut mismatches = Vec::new();
+    let mut entry_data = Vec::new();
+
+    let mut reader = CountingReader::new(Cursor::new(data));
+    reader.record_layout();
+    let entries = read_archive::<_, _, eyre::Report>(
+        &mut reader,
+        |name, data, offset| {
+            let mismatch = verify_entry(name, &data, offset)
+                .with_context(|| format!("Failed to verify entry `{}`", name))?;
+            if let Some(mut mismatch) = mismatch {
+                mismatch.entry = Some(name.to_string());
+                mismatches.push(mismatch);
+            }
+            entry_data.push(data);
+            Ok(())
+        },
+        version,
+    )
+    .context("Failed to read archive")?;
+    let layout = reader.take_layout();
+
+    // entries are loaded in the order they were read
+    let mut entry_data = entry_data.into_iter();
+    let mut writer = CountingWriter::new(Vec::new(), 0);
+    write_archive::<_, _, eyre::Report>(
+        &mut writer,
+        &entries,
+        |name, _offset| {
+            entry_data
+                .next()
+                .ok_or_else(|| eyre!("Entry `{}` was not read", name))
+        },
+        version,
+    )
+    .context("Failed to write archive")?;
+    mismatches.extend(compare(data, &writer.into_inner(), 0, &layout));
+    Ok(mismatches)
+}
+
+fn archive_version(game: GameType, mode: Mode) -> Version {
+    match game {
+        GameType::MW | GameType::RC | GameType::CS => Version::One,
+        GameType::PM => Version::Two(mode),
+    }
+}
+
+fn load_item(items: &HashMap<String, LoadItem>, name: LoadItemName<'_>) -> Result<LoadItem> {
+    match items.get(name.name()) {
+        Some(LoadItem::AnimDef(anim_def)) => Ok(LoadItem::AnimDef(anim_def.clone())),
+        Some(LoadItem::SiScript(si_script)) => Ok(LoadItem::SiScript(si_script.clone())),
+        None => bail!("Anim item `{}` was not read", name.name()),
+    }
+}
+
+fn save_item(items: &mut HashMap<String, LoadItem>, item: SaveItem<'_>) -> Result<()> {
+    let value = match item {
+        SaveItem::AnimDef { anim_def, .. } => LoadItem::AnimDef(Box::new(anim_def.clone())),
+        SaveItem::SiScript { si_script, .. } => LoadItem::SiScript(si_script.clone()),
+    };
+    items.insert(item.name().to_string(), value);
+    Ok(())
+}
+
+fn anim(data: &[u8], game: GameType) -> Result<Option<Mismatch>> {
+    match game {
+        GameType::MW => roundtrip(
+            data,
+            0,
+            |read| {
+                let mut items = HashMap::new();
+                let metadata =
+                    mech3ax_anim::mw::read_anim(read, |item| save_item(&mut items, item))?;
+                Ok((metadata, items))
+            },
+            |write, (metadata, items)| {
+                mech3ax_anim::mw::write_anim(write, &metadata, |name| load_item(&items, name))
+            },
+        ),
+        GameType::PM => roundtrip(
+            data,
+            0,
+            |read| {
+                let mut items = HashMap::new();
+                let metadata =
+                    mech3ax_anim::pm::read_anim(read, |item| save_item(&mut items, item))?;
+                Ok((metadata, items))
+            },
+            |write, (metadata, items)| {
+                mech3ax_anim::pm::write_anim(write, &metadata, |name| load_item(&items, name))
+            },
+        ),
+        GameType::RC => roundtrip(
+            data,
+            0,
+            |read| {
+                let mut items = HashMap::new();
+                let metadata =
+                    mech3ax_anim::rc::read_anim(read, |item| save_item(&mut items, item))?;
+                Ok((metadata, items))
+            },
+            |write, (metadata, items)| {
+                mech3ax_anim::rc::write_anim(write, &metadata, |name| load_item(&items, name))
+            },
+        ),
+        GameType::CS => bail!("Crimson Skies support for Anim isn't implemented yet"),
+    }
+}
+
+fn textures(data: &[u8]) -> Result<Option<Mismatch>> {
+    roundtrip(
+        data,
+        0,
+        |read| {
+            let mut images: HashMap<String, DynamicImage> = HashMap::new();
+            let manifest = read_textures::<_, eyre::Report>(read, |name, image| {
+                images.insert(name.to_string(), image);
+                Ok(())
+            })?;
+            Ok((manifest, images))
+        },
+        |write, (manifest, images)| {
+            write_textures::<_, eyre::Report>(write, &manifest, |name| {
+                images
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| eyre!("Texture `{}` was not read", name))
+            })
+        },
+    )
+}
+
+fn mechlib_entry(game: GameType, name: &str, data: &[u8], base: usize) -> Result<Option<Mismatch>> {
+    match name {
+        "format" => roundtrip(
+            data,
+            base,
+            |read| Ok(read_format(read)?),
+            |write, ()| Ok(write_format(write)?),
+        ),
+        "version" => roundtrip(
+            data,
+            base,
+            |read| Ok(read_version(read, game)?),
+            |write, ()| Ok(write_version(write, game)?),
+        ),
+        "materials" => roundtrip(
+            data,
+            base,
+            |read| Ok(read_materials(read)?),
+            |write, materials| Ok(write_materials(write, &materials)?),
+        ),
+        _ => match game {
+            GameType::MW => roundtrip(
+                data,
+                base,
+                |read| Ok(mechlib::mw::read_model(read)?),
+                |write, model| Ok(mechlib::mw::write_model(write, &model)?),
+            ),
+            GameType::PM => roundtrip(
+                data,
+                base,
+                |read| Ok(mechlib::pm::read_model(read)?),
+                |write, model| Ok(mechlib::pm::write_model(write, &model)?),
+            ),
+            GameType::RC => unreachable!("Recoil does not have mechlib"),
+            GameType::CS => unreachable!("Crimson Skies does not have mechlib"),
+        },
+    }
+}
+
+fn savegame_entry(name: &str, data: &[u8], base: usize) -> Result<Option<Mismatch>> {
+    match name {
+        "zSaveHeader" => roundtrip(
+            data,
+            base,
+            |read| Ok(read_save_header(read)?),
+            |write, ()| Ok(write_save_header(write)?),
+        ),
+        _ => roundtrip(
+            data,
+            base,
+            |read| Ok(read_activation(read)?),
+            |write, activation| Ok(write_activation(write, &activation)?),
+        ),
+    }
+}
+
+fn verify_data(kind: FileKind, game: GameType, data: &[u8]) -> Result<Vec<Mismatch>> {
+    let mismatch = match kind {
+        FileKind::Sounds => {
+            let version = archive_version(game, Mode::Sounds);
+            return archive(data, version, |_name, _data, _offset| Ok(None));
+        }
+        FileKind::Archive => {
+            let version = archive_version(game, Mode::Sounds);
+            return archive(data, version, |_name, _data, _offset| Ok(None));
+        }
+        FileKind::Reader => {
+            let version = archive_version(game, Mode::Reader);
+            return archive(data, version, |_name, data, offset| {
+                roundtrip(
+                    data,
+                    offset,
+                    |read| Ok(read_reader(read)?),
+                    |write, value| Ok(write_reader(write, &value)?),
+                )
+            });
+        }
+        FileKind::Motion => {
+            match game {
+                GameType::MW | GameType::PM => {}
+                GameType::RC => bail!("Recoil does not have motion"),
+                GameType::CS => bail!("Crimson Skies does not have motion"),
+            }
+            let version = archive_version(game, Mode::Motion);
+            return archive(data, version, |_name, data, offset| {
+                roundtrip(
+                    data,
+                    offset,
+                    |read| Ok(read_motion(read)?),
+                    |write, motion| Ok(write_motion(write, &motion)?),
+                )
+            });
+        }
+        FileKind::Mechlib => {
+            match game {
+                GameType::MW | GameType::PM => {}
+                GameType::RC => bail!("Recoil does not have mechlib"),
+                GameType::CS => bail!("Crimson Skies does not have mechlib"),
+            }
+            let version = archive_version(game, Mode::Sounds);
+            return archive(data, version, |name, data, offset| {
+                mechlib_entry(game, name, data, offset)
+            });
+        }
+        FileKind::Savegame => {
+            match game {
+                GameType::MW => {}
+                GameType::PM => bail!("Pirate's Moon support for Savegames isn't implemented yet"),
+                GameType::RC => bail!("Recoil support for Savegames isn't implemented yet"),
+                GameType::CS => bail!("Crimson Skies support for Savegames isn't implemented yet"),
+            }
+            return archive(data, Version::One, savegame_entry);
+        }
+        FileKind::Interp => roundtrip(
+            data,
+            0,
+            |read| Ok(read_interp(read)?),
+            |write, scripts| Ok(write_interp(write, &scripts)?),
+        )?,
+        FileKind::Messages => bail!("Messages can't be written"),
+        FileKind::Textures => textures(data)?,
+        FileKind::Gamez => match game {
+            GameType::MW => roundtrip(
+                data,
+                0,
+                |read| Ok(gamez::mw::read_gamez(read)?),
+                |write, gamez| Ok(gamez::mw::write_gamez(write, &gamez)?),
+            )?,
+            GameType::PM => roundtrip(
+                data,
+                0,
+                |read| Ok(gamez::pm::read_gamez(read)?),
+                |write, gamez| Ok(gamez::pm::write_gamez(write, &gamez)?),
+            )?,
+            GameType::RC => roundtrip(
+                data,
+                0,
+                |read| Ok(gamez::rc::read_gamez(read)?),
+                |write, gamez| Ok(gamez::rc::write_gamez(write, &gamez)?),
+            )?,
+            GameType::CS => roundtrip(
+                data,
+                0,
+                |read| Ok(gamez::cs::read_gamez(read)?),
+                |write, gamez| Ok(gamez::cs::write_gamez(write, &gamez)?),
+            )?,
+        },
+        FileKind::Anim => anim(data, game)?,
+        FileKind::Zmap => match game {
+            GameType::RC => roundtrip(
+                data,
+                0,
+                |read| Ok(mech3ax_zmap::read_map(read)?),
+                |write, map| Ok(mech3ax_zmap::write_map(write, &map)?),
+            )?,
+            GameType::MW => bail!("MechWarrior 3 does not have zmap"),
+            GameType::PM => bail!("Pirate's Moon does not have zmap"),
+            GameType::CS => bail!("Crimson Skies does not have zmap"),
+        },
+    };
+    Ok(mismatch.into_iter().collect())
+}
+
+enum Outcome {
+    Verified,
+    Mismatched(Vec<Mismatch>),
+    Failed(eyre::Report),
+    Skipped(String),
+}
+
+fn verify_file(path: &Path, game: GameType) -> (String, Outcome) {
+    let input = path.display().to_string();
+    let detection = match detect_file(&input) {
+        Ok(detection) => detection,
+        Err(e) => return (input, Outcome::Skipped(format!("{:#}", e))),
+    };
+    let label = format!("`{}` ({})", input, detection.kind);
+    if !detection.games.contains(&game) {
+        let reason = format!("File is {}, not {}", detection, game);
+        return (label, Outcome::Skipped(reason));
+    }
+    if detection.kind == FileKind::Messages {
+        return (
+            label,
+            Outcome::Skipped("Messages can't be written".to_string()),
+        );
+    }
+
+    log::info!("VERIFY: Verifying {}", label);
+    let result = std::fs::read(path)
+        .context("Failed to open input")
+        .and_then(|data| verify_data(detection.kind, game, &data));
+    let outcome = match result {
+        Ok(mismatches) if mismatches.is_empty() => Outcome::Verified,
+        Ok(mismatches) => Outcome::Mismatched(mismatches),
+        Err(e) => Outcome::Failed(e),
+    };
+    (label, outcome)
+}
+
+pub(crate) fn verify(opts: VerifyOpts) -> Result<()> {
+    let path = PathBuf::from(&opts.path);
+    let files = if path.is_dir() {
+        let mut files = Vec::new();
+        discover(&path, opts.game, &mut files)?;
+        files.sort();
+        log::info!("VERIFY: Found {} files in `{}`", files.len(), opts.path);
+        files
+    } else {
+        vec![path]
+    };
+
+    let outcomes = run_parallel(
+        &files,
+        opts.jobs,
+        |path| verify_file(path, opts.game),
+        |path, msg| {
+            let label = format!("`{}`", path.display());
+            (label, Outcome::Failed(eyre::eyre!("Panicked: {}", msg)))
+        },
+    );
+
+    let mut verified = 0;
+    let mut mismatched = 0;
+    let mut failed = 0;
+    let mut skipped = 0;
+    for (label, outcome) in outcomes {
+        match outcome {
+            Outcome::Verified => {
+                verified += 1;
+                println!("OK {}", label);
+            }
+            Outcome::Mismatched(mismatches) => {
+                mismatched += 1;
+                for mismatch in mismatches {
+                    println!("MISMATCH {}: {}", label, mismatch);
+                }
+            }
+            Outcome::Failed(e) => {
+                failed += 1;
+                println!("FAILED {}: {:#}", label, e);
+            }
+            Outcome::Skipped(reason) => {
+                skipped += 1;
+                println!("SKIPPED {}: {}", label, reason);
+            }
+        }
+    }
+    println!(
+        "{} verified, {} mismatched, {} failed, {} skipped",
+        verified, mismatched, failed, skipped
+    );
+    if mismatched + failed > 0 {
+        bail!("{} file(s) did not round-trip", mismatched + failed);
+    }
+    Ok(())
+}
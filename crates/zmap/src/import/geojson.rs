@@ -0,0 +1,169 @@
+use super::{ImportOptions, open_ring, parse_color};
+use log::debug;
+use mech3ax_api_types::Vec3;
+use mech3ax_api_types::zmap::{MapColor, MapFeature};
+use mech3ax_common::{Result, assert_with_msg};
+use serde_json::Value;
+
+fn parse_position(value: &Value, opts: &ImportOptions) -> Result<Vec3> {
+    let coords = value
+        .as_array()
+        .ok_or_else(|| assert_with_msg!("Expected GeoJSON position to be an array"))?;
+    let coord = |index: usize| -> Result<f32> {
+        match coords.get(index) {
+            Some(v) => v
+                .as_f64()
+                .map(|v| v as f32 * opts.scale)
+                .ok_or_else(|| assert_with_msg!("Expected GeoJSON coordinate to be a number")),
+            None => Ok(0.0),
+        }
+    };
+    if coords.len() < 2 {
+        return Err(assert_with_msg!(
+            "Expected GeoJSON position to have at least 2 coordinates, but was {}",
+            coords.len()
+        ));
+    }
+    Ok(Vec3 {
+        x: coord(0)?,
+        y: coord(1)?,
+        z: coord(2)?,
+    })
+}
+
+fn parse_line(value: &Value, opts: &ImportOptions) -> Result<Vec<Vec3>> {
+    let positions = value
+        .as_array()
+        .ok_or_else(|| assert_with_msg!("Expected GeoJSON line to be an array"))?;
+    let mut vertices = positions
+        .iter()
+        .map(|position| parse_position(position, opts))
+        .collect::<Result<Vec<_>>>()?;
+    open_ring(&mut vertices);
+    Ok(vertices)
+}
+
+fn parse_lines(value: &Value, opts: &ImportOptions) -> Result<Vec<Vec<Vec3>>> {
+    value
+        .as_array()
+        .ok_or_else(|| assert_with_msg!("Expected GeoJSON lines to be an array"))?
+        .iter()
+        .map(|line| parse_line(line, opts))
+        .collect()
+}
+
+fn parse_geometry(geometry: &Value, opts: &ImportOptions) -> Result<Vec<Vec<Vec3>>> {
+    let kind = geometry.get("type").and_then(Value::as_str).unwrap_or("");
+    let coords = || {
+        geometry
+            .get("coordinates")
+            .ok_or_else(|| assert_with_msg!("Expected GeoJSON `{}` to have coordinates", kind))
+    };
+    match kind {
+        "LineString" => Ok(vec![parse_line(coords()?, opts)?]),
+        // polygon rings become separate features
+        "MultiLineString" | "Polygon" => parse_lines(coords()?, opts),
+        "MultiPolygon" => {
+            let polygons = coords()?
+                .as_array()
+                .ok_or_else(|| assert_with_msg!("Expected GeoJSON polygons to be an array"))?;
+            let mut lines = Vec::new();
+            for polygon in polygons {
+                lines.extend(parse_lines(polygon, opts)?);
+            }
+            Ok(lines)
+        }
+        "GeometryCollection" => {
+            let geometries = geometry
+                .get("geometries")
+                .and_then(Value::as_array)
+                .ok_or_else(|| assert_with_msg!("Expected GeoJSON geometries to be an array"))?;
+            let mut lines = Vec::new();
+            for geometry in geometries {
+                lines.extend(parse_geometry(geometry, opts)?);
+            }
+            Ok(lines)
+        }
+        "Point" | "MultiPoint" => {
+            debug!("Skipping GeoJSON `{}`", kind);
+            Ok(Vec::new())
+        }
+        _ => Err(assert_with_msg!("Unsupported GeoJSON geometry `{}`", kind)),
+    }
+}
+
+fn parse_feature(
+    feature: &Value,
+    opts: &ImportOptions,
+    features: &mut Vec<MapFeature>,
+) -> Result<()> {
+    let props = feature.get("properties");
+    let prop = |name: &str| props.and_then(|props| props.get(name));
+
+    let color: MapColor = match prop("stroke").and_then(Value::as_str) {
+        Some(value) => parse_color(value)
+            .ok_or_else(|| assert_with_msg!("Invalid GeoJSON stroke color `{}`", value))?,
+        None => opts.default_color,
+    };
+    let objective = match prop("objective") {
+        Some(value) => value
+            .as_i64()
+            .and_then(|v| i32::try_from(v).ok())
+            .ok_or_else(|| assert_with_msg!("Invalid GeoJSON objective `{}`", value))?,
+        None => 0,
+    };
+
+    let geometry = match feature.get("geometry") {
+        Some(Value::Null) | None => return Ok(()),
+        Some(geometry) => geometry,
+    };
+    for vertices in parse_geometry(geometry, opts)? {
+        if vertices.is_empty() {
+            continue;
+        }
+        features.push(MapFeature {
+            color,
+            vertices,
+            objective,
+        });
+    }
+    Ok(())
+}
+
+/// Import line and polygon geometries from a GeoJSON document as map
+/// features.
+///
+/// The `stroke` property (as in the simplestyle specification) is used as the
+/// feature color, and the optional `objective` property as the objective.
+/// Coordinates are used as-is, and not projected.
+pub fn import_geojson(text: &str, opts: &ImportOptions) -> Result<Vec<MapFeature>> {
+    let root: Value =
+        serde_json::from_str(text).map_err(|e| assert_with_msg!("Invalid GeoJSON: {}", e))?;
+
+    let mut features = Vec::new();
+    match root.get("type").and_then(Value::as_str).unwrap_or("") {
+        "FeatureCollection" => {
+            let items = root
+                .get("features")
+                .and_then(Value::as_array)
+                .ok_or_else(|| assert_with_msg!("Expected GeoJSON features to be an array"))?;
+            for feature in items {
+                parse_feature(feature, opts, &mut features)?;
+            }
+        }
+        "Feature" => parse_feature(&root, opts, &mut features)?,
+        _ => {
+            for vertices in parse_geometry(&root, opts)? {
+                if vertices.is_empty() {
+                    continue;
+                }
+                features.push(MapFeature {
+                    color: opts.default_color,
+                    vertices,
+                    objective: 0,
+                });
+            }
+        }
+    }
+    Ok(features)
+}
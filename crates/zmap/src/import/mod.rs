@@ -0,0 +1,121 @@
+mod geojson;
+mod svg;
+
+use mech3ax_api_types::Vec3;
+use mech3ax_api_types::zmap::{MapColor, MapFeature, Zmap};
+use mech3ax_common::{Result, assert_with_msg};
+
+pub use geojson::import_geojson;
+pub use svg::import_svg;
+
+/// Options for importing map features from vector graphics.
+#[derive(Debug, Clone, Copy)]
+pub struct ImportOptions {
+    /// Multiply all coordinates by this value.
+    pub scale: f32,
+    /// The color used for features that don't specify a stroke color.
+    pub default_color: MapColor,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            default_color: MapColor {
+                r: 255,
+                g: 255,
+                b: 255,
+            },
+        }
+    }
+}
+
+/// Construct a map from features, with the bounds computed from the feature
+/// vertices.
+pub fn map_from_features(unk04: u32, features: Vec<MapFeature>) -> Result<Zmap> {
+    let mut vertices = features.iter().flat_map(|feature| feature.vertices.iter());
+    let first = vertices
+        .next()
+        .ok_or_else(|| assert_with_msg!("Expected map to have at least one vertex"))?;
+    let (min, max) = vertices.fold((*first, *first), |(min, max), v| {
+        let min = Vec3 {
+            x: min.x.min(v.x),
+            y: min.y.min(v.y),
+            z: min.z.min(v.z),
+        };
+        let max = Vec3 {
+            x: max.x.max(v.x),
+            y: max.y.max(v.y),
+            z: max.z.max(v.z),
+        };
+        (min, max)
+    });
+    Ok(Zmap {
+        unk04,
+        min,
+        max,
+        features,
+    })
+}
+
+/// Remove the closing vertex of a ring, since map features are always closed.
+fn open_ring(vertices: &mut Vec<Vec3>) {
+    if vertices.len() > 1 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Parse a CSS-style color (`#rgb`, `#rrggbb`, `rgb(r, g, b)`, or a basic
+/// color keyword).
+fn parse_color(value: &str) -> Option<MapColor> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits = hex.bytes().map(hex_digit).collect::<Option<Vec<u8>>>()?;
+        let (r, g, b) = match digits.as_slice() {
+            [r, g, b] => (r * 17, g * 17, b * 17),
+            [r1, r2, g1, g2, b1, b2] => (r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2),
+            _ => return None,
+        };
+        return Some(MapColor { r, g, b });
+    }
+    if let Some(args) = value
+        .strip_prefix("rgb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let rgb = args
+            .split(',')
+            .map(|c| c.trim().parse::<u8>().ok())
+            .collect::<Option<Vec<u8>>>()?;
+        return match rgb.as_slice() {
+            &[r, g, b] => Some(MapColor { r, g, b }),
+            _ => None,
+        };
+    }
+    let (r, g, b) = match value.to_ascii_lowercase().as_str() {
+        "black" => (0, 0, 0),
+        "white" => (255, 255, 255),
+        "red" => (255, 0, 0),
+        "lime" => (0, 255, 0),
+        "green" => (0, 128, 0),
+        "blue" => (0, 0, 255),
+        "yellow" => (255, 255, 0),
+        "cyan" | "aqua" => (0, 255, 255),
+        "magenta" | "fuchsia" => (255, 0, 255),
+        "gray" | "grey" => (128, 128, 128),
+        "orange" => (255, 165, 0),
+        _ => return None,
+    };
+    Some(MapColor { r, g, b })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,273 @@
+use super::{ImportOptions, open_ring, parse_color};
+use log::{debug, warn};
+use mech3ax_api_types::Vec3;
+use mech3ax_api_types::zmap::{MapColor, MapFeature};
+use mech3ax_common::{Result, assert_with_msg};
+use roxmltree::{Document, Node};
+
+fn style_property<'a>(style: &'a str, name: &str) -> Option<&'a str> {
+    style.split(';').find_map(|decl| {
+        let (key, value) = decl.split_once(':')?;
+        (key.trim() == name).then(|| value.trim())
+    })
+}
+
+/// Find the stroke color, which may be inherited from ancestors.
+///
+/// Returns `Ok(None)` if the stroke is explicitly disabled.
+fn stroke_color(node: Node<'_, '_>, opts: &ImportOptions) -> Result<Option<MapColor>> {
+    for ancestor in node.ancestors().filter(Node::is_element) {
+        let value = ancestor
+            .attribute("style")
+            .and_then(|style| style_property(style, "stroke"))
+            .or_else(|| ancestor.attribute("stroke"));
+        if let Some(value) = value {
+            if value == "none" {
+                return Ok(None);
+            }
+            return parse_color(value)
+                .map(Some)
+                .ok_or_else(|| assert_with_msg!("Invalid SVG stroke color `{}`", value));
+        }
+    }
+    Ok(Some(opts.default_color))
+}
+
+fn parse_numbers(value: &str) -> Result<Vec<f32>> {
+    value
+        .split(|c: char| c == ',' || c.is_ascii_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse()
+                .map_err(|_| assert_with_msg!("Invalid SVG number `{}`", s))
+        })
+        .collect()
+}
+
+fn parse_points(value: &str) -> Result<Vec<(f32, f32)>> {
+    let numbers = parse_numbers(value)?;
+    if numbers.len() % 2 != 0 {
+        return Err(assert_with_msg!("Expected SVG points to be pairs"));
+    }
+    Ok(numbers.chunks_exact(2).map(|c| (c[0], c[1])).collect())
+}
+
+fn attr_f32(node: Node<'_, '_>, name: &str) -> Result<f32> {
+    let value = node.attribute(name).unwrap_or("0");
+    value
+        .trim()
+        .parse()
+        .map_err(|_| assert_with_msg!("Invalid SVG attribute `{}` value `{}`", name, value))
+}
+
+enum PathToken {
+    Command(u8),
+    Number(f32),
+}
+
+fn tokenize_path(d: &str) -> Result<Vec<PathToken>> {
+    let bytes = d.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c.is_ascii_whitespace() || c == b',' {
+            i += 1;
+        } else if c.is_ascii_alphabetic() && c != b'e' && c != b'E' {
+            tokens.push(PathToken::Command(c));
+            i += 1;
+        } else {
+            let start = i;
+            if bytes[i] == b'+' || bytes[i] == b'-' {
+                i += 1;
+            }
+            let mut seen_dot = false;
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'0'..=b'9' => i += 1,
+                    b'.' if !seen_dot => {
+                        seen_dot = true;
+                        i += 1;
+                    }
+                    b'e' | b'E' => {
+                        i += 1;
+                        if i < bytes.len() && (bytes[i] == b'+' || bytes[i] == b'-') {
+                            i += 1;
+                        }
+                    }
+                    _ => break,
+                }
+            }
+            let s = &d[start..i];
+            let value = s
+                .parse()
+                .map_err(|_| assert_with_msg!("Invalid SVG path number `{}` (at {})", s, start))?;
+            tokens.push(PathToken::Number(value));
+        }
+    }
+    Ok(tokens)
+}
+
+fn next_number(iter: &mut impl Iterator<Item = PathToken>, cmd: u8) -> Result<f32> {
+    match iter.next() {
+        Some(PathToken::Number(value)) => Ok(value),
+        _ => Err(assert_with_msg!(
+            "Expected number for SVG path command `{}`",
+            cmd as char
+        )),
+    }
+}
+
+/// Parse straight-line path data into subpaths.
+///
+/// Curves and arcs are not supported.
+fn parse_path(d: &str) -> Result<Vec<Vec<(f32, f32)>>> {
+    let tokens = tokenize_path(d)?;
+    let mut subpaths = Vec::new();
+    let mut current: Vec<(f32, f32)> = Vec::new();
+    let mut pos = (0.0f32, 0.0f32);
+    let mut start = pos;
+    let mut command = None;
+    let mut iter = tokens.into_iter().peekable();
+
+    while let Some(token) = iter.peek() {
+        let cmd = match *token {
+            PathToken::Command(cmd) => {
+                iter.next();
+                cmd
+            }
+            PathToken::Number(_) => match command {
+                // implicit repetition, where a move turns into a line
+                Some(b'M') => b'L',
+                Some(b'm') => b'l',
+                Some(cmd) => cmd,
+                None => {
+                    return Err(assert_with_msg!(
+                        "Expected SVG path to start with a command"
+                    ));
+                }
+            },
+        };
+        command = Some(cmd);
+        match cmd {
+            b'M' | b'm' => {
+                let x = next_number(&mut iter, cmd)?;
+                let y = next_number(&mut iter, cmd)?;
+                pos = if cmd == b'm' {
+                    (pos.0 + x, pos.1 + y)
+                } else {
+                    (x, y)
+                };
+                start = pos;
+                if !current.is_empty() {
+                    subpaths.push(std::mem::take(&mut current));
+                }
+                current.push(pos);
+            }
+            b'L' | b'l' => {
+                let x = next_number(&mut iter, cmd)?;
+                let y = next_number(&mut iter, cmd)?;
+                pos = if cmd == b'l' {
+                    (pos.0 + x, pos.1 + y)
+                } else {
+                    (x, y)
+                };
+                current.push(pos);
+            }
+            b'H' | b'h' => {
+                let x = next_number(&mut iter, cmd)?;
+                pos.0 = if cmd == b'h' { pos.0 + x } else { x };
+                current.push(pos);
+            }
+            b'V' | b'v' => {
+                let y = next_number(&mut iter, cmd)?;
+                pos.1 = if cmd == b'v' { pos.1 + y } else { y };
+                current.push(pos);
+            }
+            b'Z' | b'z' => {
+                pos = start;
+                if !current.is_empty() {
+                    subpaths.push(std::mem::take(&mut current));
+                }
+                command = None;
+            }
+            _ => {
+                return Err(assert_with_msg!(
+                    "Unsupported SVG path command `{}` (only straight lines are supported)",
+                    cmd as char
+                ));
+            }
+        }
+    }
+    if !current.is_empty() {
+        subpaths.push(current);
+    }
+    Ok(subpaths)
+}
+
+fn to_vertices(points: Vec<(f32, f32)>, opts: &ImportOptions) -> Vec<Vec3> {
+    // SVG y axis points down, map y axis points up
+    let mut vertices = points
+        .into_iter()
+        .map(|(x, y)| Vec3 {
+            x: x * opts.scale,
+            y: -y * opts.scale,
+            z: 0.0,
+        })
+        .collect();
+    open_ring(&mut vertices);
+    vertices
+}
+
+/// Import `polyline`, `polygon`, `line`, and `path` elements from an SVG
+/// document as map features.
+///
+/// The stroke color is used as the feature color, and the optional
+/// `data-objective` attribute as the objective. Transforms are not applied.
+pub fn import_svg(text: &str, opts: &ImportOptions) -> Result<Vec<MapFeature>> {
+    let doc = Document::parse(text).map_err(|e| assert_with_msg!("Invalid SVG: {}", e))?;
+
+    let mut features = Vec::new();
+    for node in doc.descendants().filter(Node::is_element) {
+        let name = node.tag_name().name();
+        let subpaths = match name {
+            "polyline" | "polygon" => vec![parse_points(node.attribute("points").unwrap_or(""))?],
+            "line" => vec![vec![
+                (attr_f32(node, "x1")?, attr_f32(node, "y1")?),
+                (attr_f32(node, "x2")?, attr_f32(node, "y2")?),
+            ]],
+            "path" => parse_path(node.attribute("d").unwrap_or(""))?,
+            _ => continue,
+        };
+
+        let Some(color) = stroke_color(node, opts)? else {
+            debug!("Skipping SVG `{}` without stroke", name);
+            continue;
+        };
+        if node
+            .ancestors()
+            .any(|ancestor| ancestor.attribute("transform").is_some())
+        {
+            warn!("SVG `{}` has a transform, which is ignored", name);
+        }
+        let objective = match node.attribute("data-objective") {
+            Some(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| assert_with_msg!("Invalid SVG objective `{}`", value))?,
+            None => 0,
+        };
+
+        for points in subpaths {
+            if points.is_empty() {
+                continue;
+            }
+            features.push(MapFeature {
+                color,
+                vertices: to_vertices(points, opts),
+                objective,
+            });
+        }
+    }
+    Ok(features)
+}
@@ -0,0 +1,132 @@
+use super::*;
+
+fn v(x: f32, y: f32) -> Vec3 {
+    Vec3 { x, y, z: 0.0 }
+}
+
+#[test]
+fn color_formats() {
+    let red = Some(MapColor { r: 255, g: 0, b: 0 });
+    assert_eq!(parse_color("#f00"), red);
+    assert_eq!(parse_color("#FF0000"), red);
+    assert_eq!(parse_color("rgb(255, 0, 0)"), red);
+    assert_eq!(parse_color("Red"), red);
+    assert_eq!(parse_color("#ff00"), None);
+    assert_eq!(parse_color("rgb(256,0,0)"), None);
+    assert_eq!(parse_color("burgundy"), None);
+}
+
+#[test]
+fn svg_elements() {
+    let text = r##"<svg xmlns="http://www.w3.org/2000/svg">
+        <g stroke="#00ff00">
+            <polygon points="0,0 10,0 10,10 0,0" data-objective="2" />
+            <line x1="1" y1="2" x2="3" y2="4" style="stroke: #0000ff" />
+        </g>
+        <polyline points="0 0 5 5" stroke="none" />
+        <path d="M 0 0 h 10 v 10 z m 20 0 l 1 1 2 2" />
+    </svg>"##;
+    let features = import_svg(text, &ImportOptions::default()).unwrap();
+    assert_eq!(features.len(), 4);
+
+    assert_eq!(features[0].color, MapColor { r: 0, g: 255, b: 0 });
+    assert_eq!(features[0].objective, 2);
+    // closing vertex is removed, and y is flipped
+    assert_eq!(
+        features[0].vertices,
+        vec![v(0.0, 0.0), v(10.0, 0.0), v(10.0, -10.0)]
+    );
+
+    assert_eq!(features[1].color, MapColor { r: 0, g: 0, b: 255 });
+    assert_eq!(features[1].vertices, vec![v(1.0, -2.0), v(3.0, -4.0)]);
+
+    assert_eq!(features[2].color, ImportOptions::default().default_color);
+    assert_eq!(
+        features[2].vertices,
+        vec![v(0.0, 0.0), v(10.0, 0.0), v(10.0, -10.0)]
+    );
+    // relative move after close is relative to the subpath start
+    assert_eq!(
+        features[3].vertices,
+        vec![v(20.0, 0.0), v(21.0, -1.0), v(23.0, -3.0)]
+    );
+}
+
+#[test]
+fn svg_curves_unsupported() {
+    let text = r#"<svg><path d="M 0 0 C 1 1 2 2 3 3" /></svg>"#;
+    assert!(import_svg(text, &ImportOptions::default()).is_err());
+}
+
+#[test]
+fn geojson_features() {
+    let text = r##"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "type": "Feature",
+                "properties": { "stroke": "#ff0000", "objective": 1 },
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 0]]]
+                }
+            },
+            {
+                "type": "Feature",
+                "properties": null,
+                "geometry": {
+                    "type": "MultiLineString",
+                    "coordinates": [[[0, 0, 5], [1, 1, 5]], [[2, 2], [3, 3]]]
+                }
+            }
+        ]
+    }"##;
+    let opts = ImportOptions {
+        scale: 2.0,
+        ..Default::default()
+    };
+    let features = import_geojson(text, &opts).unwrap();
+    assert_eq!(features.len(), 3);
+    assert_eq!(features[0].color, MapColor { r: 255, g: 0, b: 0 });
+    assert_eq!(features[0].objective, 1);
+    assert_eq!(
+        features[0].vertices,
+        vec![v(0.0, 0.0), v(20.0, 0.0), v(20.0, 20.0)]
+    );
+    assert_eq!(features[1].objective, 0);
+    assert_eq!(
+        features[1].vertices,
+        vec![
+            Vec3 {
+                x: 0.0,
+                y: 0.0,
+                z: 10.0
+            },
+            Vec3 {
+                x: 2.0,
+                y: 2.0,
+                z: 10.0
+            }
+        ]
+    );
+}
+
+#[test]
+fn bounds_from_features() {
+    let features = vec![
+        MapFeature {
+            color: MapColor { r: 0, g: 0, b: 0 },
+            vertices: vec![v(-1.0, 2.0), v(3.0, -4.0)],
+            objective: 0,
+        },
+        MapFeature {
+            color: MapColor { r: 0, g: 0, b: 0 },
+            vertices: vec![v(5.0, 0.0)],
+            objective: 0,
+        },
+    ];
+    let map = map_from_features(1, features).unwrap();
+    assert_eq!(map.min, v(-1.0, -4.0));
+    assert_eq!(map.max, v(5.0, 2.0));
+    assert!(map_from_features(1, vec![]).is_err());
+}
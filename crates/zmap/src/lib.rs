@@ -1,9 +1,13 @@
 #![warn(clippy::all, clippy::cargo)]
 #![allow(clippy::identity_op)]
+mod import;
 mod read;
+mod render;
 mod write;
 
+pub use import::{ImportOptions, import_geojson, import_svg, map_from_features};
 pub use read::read_map;
+pub use render::{RenderOptions, render_map};
 pub use write::write_map;
 
 use bytemuck::{AnyBitPattern, NoUninit};
@@ -19,4 +23,4 @@ struct MapHeaderC {
     max: Vec3,    // 20
 }
 impl_as_bytes!(MapHeaderC, 32);
-const MAP_VERSION: u32 = 5;
+pub const MAP_VERSION: u32 = 5;
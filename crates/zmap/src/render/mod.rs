@@ -0,0 +1,169 @@
+use image::{Rgb, RgbImage};
+use log::trace;
+use mech3ax_api_types::zmap::{MapFeature, Zmap};
+use mech3ax_common::{Result, assert_with_msg};
+
+const LINE_WIDTH: i32 = 1;
+const OBJECTIVE_LINE_WIDTH: i32 = 3;
+
+/// Options for rasterising a map.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    /// The size of the longest side of the image, in pixels.
+    pub size: u32,
+    /// Stretch the map to a square image, instead of preserving the aspect
+    /// ratio of the map bounds.
+    pub aspect_square: bool,
+    /// Draw features with a non-zero objective with thicker lines, and on top
+    /// of other features.
+    pub highlight_objectives: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            size: 1024,
+            aspect_square: false,
+            highlight_objectives: false,
+        }
+    }
+}
+
+struct Transform {
+    min_x: f32,
+    max_y: f32,
+    scale_x: f32,
+    scale_y: f32,
+}
+
+impl Transform {
+    #[inline]
+    fn apply(&self, x: f32, y: f32) -> (i32, i32) {
+        // image y axis points down, map y axis points up
+        let px = (x - self.min_x) * self.scale_x;
+        let py = (self.max_y - y) * self.scale_y;
+        (px.round() as i32, py.round() as i32)
+    }
+}
+
+fn dimensions(range_x: f32, range_y: f32, opts: &RenderOptions) -> (u32, u32) {
+    if opts.aspect_square {
+        return (opts.size, opts.size);
+    }
+    let size = opts.size as f32;
+    if range_x >= range_y {
+        let height = (size * range_y / range_x).round() as u32;
+        (opts.size, height.max(1))
+    } else {
+        let width = (size * range_x / range_y).round() as u32;
+        (width.max(1), opts.size)
+    }
+}
+
+fn plot(image: &mut RgbImage, x: i32, y: i32, width: i32, color: Rgb<u8>) {
+    let (w, h) = (image.width() as i32, image.height() as i32);
+    let lo = -(width / 2);
+    let hi = lo + width;
+    for dy in lo..hi {
+        for dx in lo..hi {
+            let (px, py) = (x + dx, y + dy);
+            if px >= 0 && px < w && py >= 0 && py < h {
+                image.put_pixel(px as u32, py as u32, color);
+            }
+        }
+    }
+}
+
+fn draw_line(image: &mut RgbImage, from: (i32, i32), to: (i32, i32), width: i32, color: Rgb<u8>) {
+    // Bresenham's line algorithm, for all octants
+    let (mut x0, mut y0) = from;
+    let (x1, y1) = to;
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let sx = if x0 < x1 { 1 } else { -1 };
+    let sy = if y0 < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+    loop {
+        plot(image, x0, y0, width, color);
+        if x0 == x1 && y0 == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x0 += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y0 += sy;
+        }
+    }
+}
+
+fn draw_feature(image: &mut RgbImage, transform: &Transform, feature: &MapFeature, width: i32) {
+    let color = Rgb([feature.color.r, feature.color.g, feature.color.b]);
+    let points: Vec<(i32, i32)> = feature
+        .vertices
+        .iter()
+        .map(|v| transform.apply(v.x, v.y))
+        .collect();
+    match points.as_slice() {
+        [] => {}
+        [point] => plot(image, point.0, point.1, width, color),
+        [first, .., last] => {
+            for pair in points.windows(2) {
+                draw_line(image, pair[0], pair[1], width, color);
+            }
+            // features are closed polylines
+            draw_line(image, *last, *first, width, color);
+        }
+    }
+}
+
+/// Rasterise the map features as lines onto a black background.
+///
+/// The map's `min` and `max` bounds (X and Y) are used as the image extents.
+pub fn render_map(map: &Zmap, opts: &RenderOptions) -> Result<RgbImage> {
+    let range_x = map.max.x - map.min.x;
+    let range_y = map.max.y - map.min.y;
+    if !(range_x > 0.0 && range_y > 0.0) {
+        return Err(assert_with_msg!(
+            "Expected map max to be greater than min, but was {:?}/{:?}",
+            map.min,
+            map.max
+        ));
+    }
+    if opts.size == 0 {
+        return Err(assert_with_msg!("Expected image size to be > 0"));
+    }
+
+    let (width, height) = dimensions(range_x, range_y, opts);
+    trace!("Rendering map with {}x{} pixels", width, height);
+
+    let transform = Transform {
+        min_x: map.min.x,
+        max_y: map.max.y,
+        scale_x: (width - 1) as f32 / range_x,
+        scale_y: (height - 1) as f32 / range_y,
+    };
+
+    let mut image = RgbImage::new(width, height);
+    if opts.highlight_objectives {
+        let (objectives, others): (Vec<_>, Vec<_>) =
+            map.features.iter().partition(|f| f.objective != 0);
+        for feature in others {
+            draw_feature(&mut image, &transform, feature, LINE_WIDTH);
+        }
+        for feature in objectives {
+            draw_feature(&mut image, &transform, feature, OBJECTIVE_LINE_WIDTH);
+        }
+    } else {
+        for feature in &map.features {
+            draw_feature(&mut image, &transform, feature, LINE_WIDTH);
+        }
+    }
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,95 @@
+use super::*;
+use mech3ax_api_types::Vec3;
+use mech3ax_api_types::zmap::MapColor;
+
+const RED: MapColor = MapColor { r: 255, g: 0, b: 0 };
+const BLACK: Rgb<u8> = Rgb([0, 0, 0]);
+
+fn map(features: Vec<MapFeature>) -> Zmap {
+    Zmap {
+        unk04: 1,
+        min: Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        },
+        max: Vec3 {
+            x: 200.0,
+            y: 100.0,
+            z: 0.0,
+        },
+        features,
+    }
+}
+
+fn feature(vertices: &[(f32, f32)], objective: i32) -> MapFeature {
+    MapFeature {
+        color: RED,
+        vertices: vertices
+            .iter()
+            .map(|&(x, y)| Vec3 { x, y, z: 0.0 })
+            .collect(),
+        objective,
+    }
+}
+
+#[test]
+fn dimensions_preserve_aspect() {
+    let opts = RenderOptions {
+        size: 100,
+        ..Default::default()
+    };
+    let image = render_map(&map(vec![]), &opts).unwrap();
+    assert_eq!(image.dimensions(), (100, 50));
+}
+
+#[test]
+fn dimensions_square() {
+    let opts = RenderOptions {
+        size: 100,
+        aspect_square: true,
+        ..Default::default()
+    };
+    let image = render_map(&map(vec![]), &opts).unwrap();
+    assert_eq!(image.dimensions(), (100, 100));
+}
+
+#[test]
+fn feature_is_closed_and_flipped() {
+    let opts = RenderOptions {
+        size: 201,
+        ..Default::default()
+    };
+    let square = feature(&[(0.0, 0.0), (200.0, 0.0), (200.0, 100.0)], 0);
+    let image = render_map(&map(vec![square]), &opts).unwrap();
+    let red = Rgb([255, 0, 0]);
+    // map y = 0 is the bottom of the image
+    assert_eq!(*image.get_pixel(100, 100), red);
+    assert_eq!(*image.get_pixel(200, 50), red);
+    // closing edge from the last vertex back to the first vertex
+    assert_eq!(*image.get_pixel(100, 50), red);
+    assert_eq!(*image.get_pixel(100, 0), BLACK);
+}
+
+#[test]
+fn objective_is_highlighted() {
+    let opts = RenderOptions {
+        size: 201,
+        highlight_objectives: true,
+        ..Default::default()
+    };
+    let line = feature(&[(0.0, 50.0), (200.0, 50.0)], 1);
+    let image = render_map(&map(vec![line]), &opts).unwrap();
+    let red = Rgb([255, 0, 0]);
+    assert_eq!(*image.get_pixel(100, 49), red);
+    assert_eq!(*image.get_pixel(100, 50), red);
+    assert_eq!(*image.get_pixel(100, 51), red);
+    assert_eq!(*image.get_pixel(100, 52), BLACK);
+}
+
+#[test]
+fn invalid_bounds() {
+    let mut map = map(vec![]);
+    map.max.x = 0.0;
+    assert!(render_map(&map, &RenderOptions::default()).is_err());
+}